@@ -0,0 +1,7 @@
+//! Lints the scripts of a load order, the plugins are provided as
+//! arguments in load order
+
+fn main() {
+    let paths = std::env::args().skip(1);
+    std::process::exit(open_mojave::script::load_order::run_lint(paths));
+}
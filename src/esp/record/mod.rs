@@ -31,6 +31,7 @@ use std::f32::consts::E;
 use std::path::Path;
 use std::{
     fmt::{Debug, Display},
+    io::{self, Read},
     iter::Peekable,
 };
 use thiserror::Error;
@@ -129,6 +130,20 @@ pub struct OwnedRawRecord {
     pub data: Vec<u8>,
 }
 
+impl OwnedRawRecord {
+    /// Borrows the record as a raw record
+    pub fn as_raw(&self) -> RawRecord<'_> {
+        RawRecord {
+            ty: self.ty,
+            flags: self.flags.clone(),
+            form_id: self.form_id,
+            revision: self.revision,
+            version: self.version,
+            data: &self.data,
+        }
+    }
+}
+
 impl<'b> RawRecord<'b> {
     #[inline]
     pub fn parsed<'a>(&'a self) -> Result<RecordValue, RecordParseError<'b>> {
@@ -166,6 +181,33 @@ impl<'b> RawRecord<'b> {
     pub fn parse_inner(&self) -> IResult<&[u8], Vec<RawSubRecord<'_>>> {
         RawSubRecord::parse_all(self.data)
     }
+
+    /// Inflates the data of a compressed record, which is stored as the
+    /// decompressed size followed by a zlib stream. The data of records
+    /// that aren't compressed is copied as is
+    pub fn decompress(&self) -> io::Result<OwnedRawRecord> {
+        let mut flags = self.flags.clone();
+        let data = if flags.contains(RecordFlags::COMPRESSED) {
+            flags.remove(RecordFlags::COMPRESSED);
+
+            let (stream, size) = le_u32::<_, nom::error::Error<_>>(self.data)
+                .map_err(|_| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+            let mut data = Vec::with_capacity(size as usize);
+            libflate::zlib::Decoder::new(stream)?.read_to_end(&mut data)?;
+            data
+        } else {
+            self.data.to_vec()
+        };
+
+        Ok(OwnedRawRecord {
+            ty: self.ty,
+            flags,
+            form_id: self.form_id,
+            revision: self.revision,
+            version: self.version,
+            data,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -8,7 +8,7 @@ use super::{
 #[derive(Debug)]
 pub struct CLMT {
     pub editor_id: EditorId,
-    pub weather_types: Option<WeatherTypes>,
+    pub weather_types: Option<Vec<WeatherType>>,
     pub sun_texture: Option<String>,
     pub sun_glare_texture: Option<String>,
     pub model_data: Option<ModelData>,
@@ -20,7 +20,9 @@ impl Record for CLMT {
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
         let editor_id: EditorId = parser.parse(EDID)?;
-        let weather_types: Option<WeatherTypes> = parser.try_parse(WLST)?;
+        let weather_types: Option<Vec<WeatherType>> = parser
+            .try_parse::<Repeated<WeatherType>>(WLST)?
+            .map(|value| value.into_inner());
         let sun_texture: Option<String> = parser.try_parse(FNAM)?;
         let sun_glare_texture: Option<String> = parser.try_parse(GNAM)?;
         let model_data: Option<ModelData> = ModelData::parse_first(parser)?;
@@ -38,7 +40,7 @@ impl Record for CLMT {
 }
 
 #[derive(Debug)]
-pub struct WeatherTypes {
+pub struct WeatherType {
     pub weather: NTypedFormId<WTHR>,
     pub chance: i32,
     pub global: NTypedFormId<GLOB>,
@@ -54,7 +56,7 @@ pub struct ClimateTiming {
     pub moon_phase_length: u8,
 }
 
-impl FromRecordBytes for WeatherTypes {
+impl FromRecordBytes for WeatherType {
     fn parse(input: &[u8]) -> IResult<&[u8], Self> {
         map(
             tuple((NTypedFormId::parse, le_i32, NTypedFormId::parse)),
@@ -1,13 +1,148 @@
-use super::prelude::*;
+use super::{chip::CHIP, prelude::*, qust::QUST};
 
 /// Casino
 #[derive(Debug)]
-pub struct CSNO {}
+pub struct CSNO {
+    pub editor_id: EditorId,
+    pub name: Option<String>,
+    pub data: CasinoData,
+    pub chip_models: CasinoChipModels,
+    pub slot_machine_model: Option<String>,
+    pub blackjack_table_model: Option<String>,
+    pub roulette_table_model: Option<String>,
+    /// Textures of the slot machine reel symbols, in the
+    /// same order as [`SlotReelStops`]
+    pub slot_reel_textures: Vec<String>,
+    /// Textures of the blackjack decks
+    pub blackjack_decks: Vec<String>,
+}
 
 impl Record for CSNO {
     const TYPE: RecordType = CSNO;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let name: Option<String> = parser.try_parse(FULL)?;
+        let data: CasinoData = parser.parse(DATA)?;
+        let chip_models = CasinoChipModels {
+            chip_1: parser.try_parse(MODL)?,
+            chip_5: parser.try_parse(MODL)?,
+            chip_10: parser.try_parse(MODL)?,
+            chip_25: parser.try_parse(MODL)?,
+            chip_100: parser.try_parse(MODL)?,
+            chip_500: parser.try_parse(MODL)?,
+            roulette_chip: parser.try_parse(MODL)?,
+        };
+        let slot_machine_model: Option<String> = parser.try_parse(MODL)?;
+        parser.skip_type(MOD2);
+        let blackjack_table_model: Option<String> = parser.try_parse(MOD3)?;
+        parser.skip_type(MODT);
+        let roulette_table_model: Option<String> = parser.try_parse(MOD4)?;
+        let slot_reel_textures: Vec<String> = parser.try_parse_many(ICON)?;
+        let blackjack_decks: Vec<String> = parser.try_parse_many(ICO2)?;
+
+        Ok(Self {
+            editor_id,
+            name,
+            data,
+            chip_models,
+            slot_machine_model,
+            blackjack_table_model,
+            roulette_table_model,
+            slot_reel_textures,
+            blackjack_decks,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CasinoData {
+    /// Percentage of the decks dealt before they are shuffled
+    pub decks_percent_before_shuffle: f32,
+    pub blackjack_payout_ratio: f32,
+    pub slot_reel_stops: SlotReelStops,
+    pub number_of_decks: u32,
+    pub max_winnings: u32,
+    pub currency: TypedFormId<CHIP>,
+    pub winnings_quest: NTypedFormId<QUST>,
+    pub flags: CasinoFlags,
+}
+
+/// Number of stops on the slot machine reels for each
+/// symbol, determining the chance of the symbol landing
+#[derive(Debug, Clone, Copy)]
+pub struct SlotReelStops {
+    pub symbols: [u32; 6],
+    pub wild: u32,
+}
+
+impl SlotReelStops {
+    /// Total number of stops on a reel
+    pub fn total(&self) -> u32 {
+        self.symbols.iter().sum::<u32>() + self.wild
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct CasinoFlags: u32 {
+        const DEALER_STAY_ON_SOFT_17 = 0x00000001;
     }
 }
+
+impl FromRecordBytes for CasinoData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, decks_percent_before_shuffle) = le_f32(input)?;
+        let (input, blackjack_payout_ratio) = le_f32(input)?;
+        let (input, slot_reel_stops) = SlotReelStops::parse(input)?;
+        let (input, number_of_decks) = le_u32(input)?;
+        let (input, max_winnings) = le_u32(input)?;
+        let (input, currency) = TypedFormId::parse(input)?;
+        let (input, winnings_quest) = NTypedFormId::parse(input)?;
+        let (input, flags) = CasinoFlags::parse(input)?;
+
+        Ok((
+            input,
+            Self {
+                decks_percent_before_shuffle,
+                blackjack_payout_ratio,
+                slot_reel_stops,
+                number_of_decks,
+                max_winnings,
+                currency,
+                winnings_quest,
+                flags,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for SlotReelStops {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32)),
+            |(symbol_1, symbol_2, symbol_3, symbol_4, symbol_5, symbol_6, wild)| Self {
+                symbols: [symbol_1, symbol_2, symbol_3, symbol_4, symbol_5, symbol_6],
+                wild,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for CasinoFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u32, Self::from_bits_retain)(input)
+    }
+}
+
+/// Models used for the casino chips of each value
+#[derive(Debug)]
+pub struct CasinoChipModels {
+    pub chip_1: Option<String>,
+    pub chip_5: Option<String>,
+    pub chip_10: Option<String>,
+    pub chip_25: Option<String>,
+    pub chip_100: Option<String>,
+    pub chip_500: Option<String>,
+    pub roulette_chip: Option<String>,
+}
@@ -3,13 +3,309 @@ use super::prelude::*;
 /// Combat Style
 #[derive(Debug)]
 pub struct CSTY {
-    // TODO:
+    pub editor_id: EditorId,
+    pub standard: CombatStyleStandard,
+    pub advanced: Option<CombatStyleAdvanced>,
+    pub simple: Option<CombatStyleSimple>,
 }
 
 impl Record for CSTY {
     const TYPE: RecordType = CSTY;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let standard: CombatStyleStandard = parser.parse(CSTD)?;
+        let advanced: Option<CombatStyleAdvanced> = parser.try_parse(CSAD)?;
+        let simple: Option<CombatStyleSimple> = parser.try_parse(CSSD)?;
+
+        Ok(Self {
+            editor_id,
+            standard,
+            advanced,
+            simple,
+        })
+    }
+}
+
+/// Range of time in seconds
+#[derive(Debug, Clone, Copy)]
+pub struct Timer {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl FromRecordBytes for Timer {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(tuple((le_f32, le_f32)), |(min, max)| Self { min, max })(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct CombatStyleStandard {
+    pub dodge_chance: u8,
+    pub left_right_chance: u8,
+    pub dodge_left_right_timer: Timer,
+    pub dodge_forward_timer: Timer,
+    pub dodge_back_timer: Timer,
+    pub idle_timer: Timer,
+    pub block_chance: u8,
+    pub attack_chance: u8,
+    pub recoil_stagger_bonus_to_attack: f32,
+    pub unconscious_bonus_to_attack: f32,
+    pub hand_to_hand_bonus_to_attack: f32,
+    pub power_attack_chance: u8,
+    pub recoil_stagger_bonus_to_power_attack: f32,
+    pub unconscious_bonus_to_power_attack: f32,
+    pub power_attack: PowerAttackChances,
+    pub hold_timer: Timer,
+    pub flags: CombatStyleFlags,
+    pub acrobatic_dodge_chance: u8,
+    pub rush_attack_chance: u8,
+    pub rush_attack_distance_mult: f32,
+}
+
+/// Chance of each power attack direction being chosen
+#[derive(Debug, Clone, Copy)]
+pub struct PowerAttackChances {
+    pub normal: u8,
+    pub forward: u8,
+    pub back: u8,
+    pub left: u8,
+    pub right: u8,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct CombatStyleFlags: u32 {
+        const CHOOSE_ATTACK_USING_PERCENT_CHANCE = 0x00000001;
+        const MELEE_ALERT_OK                     = 0x00000002;
+        const FLEE_BASED_ON_PERSONAL_SURVIVAL    = 0x00000004;
+        const U1                                 = 0x00000008;
+        const IGNORE_THREATS                     = 0x00000010;
+        const IGNORE_DAMAGING_NEUTRALS           = 0x00000020;
+        const IGNORE_ATTACKING_NEUTRALS          = 0x00000040;
+        const IGNORE_DAMAGING_SPECTATORS         = 0x00000080;
+        const IGNORE_ATTACKING_SPECTATORS        = 0x00000100;
+        const IGNORE_DAMAGING_GROUP_MEMBERS      = 0x00000200;
+        const IGNORE_ATTACKING_GROUP_MEMBERS     = 0x00000400;
+        const IGNORE_SNEAK_ATTACKS               = 0x00000800;
+    }
+}
+
+impl FromRecordBytes for CombatStyleStandard {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, dodge_chance) = u8(input)?;
+        let (input, left_right_chance) = u8(input)?;
+        let (input, _unused) = take(2usize)(input)?;
+        let (input, dodge_left_right_timer) = Timer::parse(input)?;
+        let (input, dodge_forward_timer) = Timer::parse(input)?;
+        let (input, dodge_back_timer) = Timer::parse(input)?;
+        let (input, idle_timer) = Timer::parse(input)?;
+        let (input, block_chance) = u8(input)?;
+        let (input, attack_chance) = u8(input)?;
+        let (input, _unused) = take(2usize)(input)?;
+        let (input, recoil_stagger_bonus_to_attack) = le_f32(input)?;
+        let (input, unconscious_bonus_to_attack) = le_f32(input)?;
+        let (input, hand_to_hand_bonus_to_attack) = le_f32(input)?;
+        let (input, power_attack_chance) = u8(input)?;
+        let (input, _unused) = take(3usize)(input)?;
+        let (input, recoil_stagger_bonus_to_power_attack) = le_f32(input)?;
+        let (input, unconscious_bonus_to_power_attack) = le_f32(input)?;
+        let (input, power_attack) = PowerAttackChances::parse(input)?;
+        let (input, _unused) = take(3usize)(input)?;
+        let (input, hold_timer) = Timer::parse(input)?;
+        let (input, flags) = CombatStyleFlags::parse(input)?;
+        let (input, acrobatic_dodge_chance) = u8(input)?;
+        let (input, rush_attack_chance) = u8(input)?;
+        let (input, _unused) = take(2usize)(input)?;
+        let (input, rush_attack_distance_mult) = le_f32(input)?;
+
+        Ok((
+            input,
+            Self {
+                dodge_chance,
+                left_right_chance,
+                dodge_left_right_timer,
+                dodge_forward_timer,
+                dodge_back_timer,
+                idle_timer,
+                block_chance,
+                attack_chance,
+                recoil_stagger_bonus_to_attack,
+                unconscious_bonus_to_attack,
+                hand_to_hand_bonus_to_attack,
+                power_attack_chance,
+                recoil_stagger_bonus_to_power_attack,
+                unconscious_bonus_to_power_attack,
+                power_attack,
+                hold_timer,
+                flags,
+                acrobatic_dodge_chance,
+                rush_attack_chance,
+                rush_attack_distance_mult,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for PowerAttackChances {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((u8, u8, u8, u8, u8)),
+            |(normal, forward, back, left, right)| Self {
+                normal,
+                forward,
+                back,
+                left,
+                right,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for CombatStyleFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u32, Self::from_bits_retain)(input)
+    }
+}
+
+/// Multipliers and bases applied to the standard combat style chances
+#[derive(Debug)]
+pub struct CombatStyleAdvanced {
+    pub dodge_fatigue_mod_mult: f32,
+    pub dodge_fatigue_mod_base: f32,
+    pub encumbered_speed_mod_base: f32,
+    pub encumbered_speed_mod_mult: f32,
+    pub dodge_while_under_attack_mult: f32,
+    pub dodge_not_under_attack_mult: f32,
+    pub dodge_back_while_under_attack_mult: f32,
+    pub dodge_back_not_under_attack_mult: f32,
+    pub dodge_forward_while_attacking_mult: f32,
+    pub dodge_forward_not_attacking_mult: f32,
+    pub block_skill_modifier_mult: f32,
+    pub block_skill_modifier_base: f32,
+    pub block_while_under_attack_mult: f32,
+    pub block_not_under_attack_mult: f32,
+    pub attack_skill_modifier_mult: f32,
+    pub attack_skill_modifier_base: f32,
+    pub attack_while_under_attack_mult: f32,
+    pub attack_not_under_attack_mult: f32,
+    pub attack_during_block_mult: f32,
+    pub power_attack_fatigue_mod_base: f32,
+    pub power_attack_fatigue_mod_mult: f32,
+}
+
+impl FromRecordBytes for CombatStyleAdvanced {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, dodge_fatigue_mod_mult) = le_f32(input)?;
+        let (input, dodge_fatigue_mod_base) = le_f32(input)?;
+        let (input, encumbered_speed_mod_base) = le_f32(input)?;
+        let (input, encumbered_speed_mod_mult) = le_f32(input)?;
+        let (input, dodge_while_under_attack_mult) = le_f32(input)?;
+        let (input, dodge_not_under_attack_mult) = le_f32(input)?;
+        let (input, dodge_back_while_under_attack_mult) = le_f32(input)?;
+        let (input, dodge_back_not_under_attack_mult) = le_f32(input)?;
+        let (input, dodge_forward_while_attacking_mult) = le_f32(input)?;
+        let (input, dodge_forward_not_attacking_mult) = le_f32(input)?;
+        let (input, block_skill_modifier_mult) = le_f32(input)?;
+        let (input, block_skill_modifier_base) = le_f32(input)?;
+        let (input, block_while_under_attack_mult) = le_f32(input)?;
+        let (input, block_not_under_attack_mult) = le_f32(input)?;
+        let (input, attack_skill_modifier_mult) = le_f32(input)?;
+        let (input, attack_skill_modifier_base) = le_f32(input)?;
+        let (input, attack_while_under_attack_mult) = le_f32(input)?;
+        let (input, attack_not_under_attack_mult) = le_f32(input)?;
+        let (input, attack_during_block_mult) = le_f32(input)?;
+        let (input, power_attack_fatigue_mod_base) = le_f32(input)?;
+        let (input, power_attack_fatigue_mod_mult) = le_f32(input)?;
+
+        Ok((
+            input,
+            Self {
+                dodge_fatigue_mod_mult,
+                dodge_fatigue_mod_base,
+                encumbered_speed_mod_base,
+                encumbered_speed_mod_mult,
+                dodge_while_under_attack_mult,
+                dodge_not_under_attack_mult,
+                dodge_back_while_under_attack_mult,
+                dodge_back_not_under_attack_mult,
+                dodge_forward_while_attacking_mult,
+                dodge_forward_not_attacking_mult,
+                block_skill_modifier_mult,
+                block_skill_modifier_base,
+                block_while_under_attack_mult,
+                block_not_under_attack_mult,
+                attack_skill_modifier_mult,
+                attack_skill_modifier_base,
+                attack_while_under_attack_mult,
+                attack_not_under_attack_mult,
+                attack_during_block_mult,
+                power_attack_fatigue_mod_base,
+                power_attack_fatigue_mod_mult,
+            },
+        ))
+    }
+}
+
+/// Cover, firing and weapon preference settings
+#[derive(Debug)]
+pub struct CombatStyleSimple {
+    pub cover_search_radius: f32,
+    /// Chance (0-1) of the actor taking cover
+    pub take_cover_chance: f32,
+    pub wait_timer: Timer,
+    pub wait_to_fire_timer: Timer,
+    pub fire_timer: Timer,
+    pub ranged_weapon_range_mult_min: f32,
+    pub weapon_restrictions: WeaponRestrictions,
+    pub ranged_weapon_range_mult_max: f32,
+    pub max_targeting_fov: f32,
+    pub combat_radius: f32,
+    pub semi_automatic_firing_delay_mult_min: f32,
+    pub semi_automatic_firing_delay_mult_max: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum WeaponRestrictions {
+    None = 0,
+    MeleeOnly = 1,
+    RangedOnly = 2,
+}
+
+impl FromRecordBytes for CombatStyleSimple {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, cover_search_radius) = le_f32(input)?;
+        let (input, take_cover_chance) = le_f32(input)?;
+        let (input, wait_timer) = Timer::parse(input)?;
+        let (input, wait_to_fire_timer) = Timer::parse(input)?;
+        let (input, fire_timer) = Timer::parse(input)?;
+        let (input, ranged_weapon_range_mult_min) = le_f32(input)?;
+        let (input, _unused) = take(4usize)(input)?;
+        let (input, weapon_restrictions) = enum_value::<WeaponRestrictions>(input)?;
+        let (input, ranged_weapon_range_mult_max) = le_f32(input)?;
+        let (input, max_targeting_fov) = le_f32(input)?;
+        let (input, combat_radius) = le_f32(input)?;
+        let (input, semi_automatic_firing_delay_mult_min) = le_f32(input)?;
+        let (input, semi_automatic_firing_delay_mult_max) = le_f32(input)?;
+
+        Ok((
+            input,
+            Self {
+                cover_search_radius,
+                take_cover_chance,
+                wait_timer,
+                wait_to_fire_timer,
+                fire_timer,
+                ranged_weapon_range_mult_min,
+                weapon_restrictions,
+                ranged_weapon_range_mult_max,
+                max_targeting_fov,
+                combat_radius,
+                semi_automatic_firing_delay_mult_min,
+                semi_automatic_firing_delay_mult_max,
+            },
+        ))
     }
 }
@@ -1,15 +1,154 @@
 use super::prelude::*;
+use crate::esp::record::{parse_string, RawBytes};
 
 /// Debris
 #[derive(Debug)]
 pub struct DEBR {
-    // TODO:
+    pub editor_id: EditorId,
+    pub models: Vec<DebrisModel>,
 }
 
 impl Record for DEBR {
     const TYPE: RecordType = DEBR;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let models: Vec<DebrisModel> = parser.parse_collection()?;
+
+        Ok(Self { editor_id, models })
+    }
+}
+
+impl DEBR {
+    /// Splits the number of debris pieces to spawn between the models
+    /// of the debris based on their percentages. Pieces left over from
+    /// rounding down go to the models with the largest remainders so
+    /// the counts always add up to `count`
+    pub fn distribute(&self, count: u32) -> Vec<(&DebrisModel, u32)> {
+        let total: u64 = self
+            .models
+            .iter()
+            .map(|model| model.data.percentage as u64)
+            .sum();
+
+        if total == 0 {
+            return Vec::new();
+        }
+
+        let shares: Vec<(u32, u64)> = self
+            .models
+            .iter()
+            .map(|model| {
+                let share = count as u64 * model.data.percentage as u64;
+                ((share / total) as u32, share % total)
+            })
+            .collect();
+        let mut counts: Vec<u32> = shares.iter().map(|(count, _)| *count).collect();
+
+        let remaining = count - counts.iter().sum::<u32>();
+        let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+        // Stable so earlier models win ties
+        by_remainder.sort_by_key(|&index| std::cmp::Reverse(shares[index].1));
+        for &index in by_remainder.iter().take(remaining as usize) {
+            counts[index] += 1;
+        }
+
+        self.models
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct DebrisModel {
+    pub data: DebrisModelData,
+    pub texture_hashes: Option<Vec<u8>>,
+}
+
+impl RecordCollection for DebrisModel {
+    fn parse_next<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        let data: DebrisModelData = match parser.try_parse(DATA)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let texture_hashes: Option<Vec<u8>> = parser
+            .try_parse::<RawBytes>(MODT)?
+            .map(|value| value.into_inner());
+
+        Ok(Some(Self {
+            data,
+            texture_hashes,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct DebrisModelData {
+    /// Percentage of the debris pieces using the model
+    pub percentage: u8,
+    pub model_file_name: String,
+    pub flags: DebrisModelFlags,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct DebrisModelFlags: u8 {
+        const HAS_COLLISION_DATA = 0x01;
+    }
+}
+
+impl FromRecordBytes for DebrisModelData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((u8, parse_string, u8)),
+            |(percentage, model_file_name, flags)| Self {
+                percentage,
+                model_file_name,
+                flags: DebrisModelFlags::from_bits_retain(flags),
+            },
+        )(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DebrisModel, DebrisModelData, DebrisModelFlags, DEBR};
+    use crate::esp::shared::EditorId;
+
+    fn debris(percentages: &[u8]) -> DEBR {
+        DEBR {
+            editor_id: EditorId("TestDebris".to_string()),
+            models: percentages
+                .iter()
+                .map(|&percentage| DebrisModel {
+                    data: DebrisModelData {
+                        percentage,
+                        model_file_name: format!("Debris{percentage}.nif"),
+                        flags: DebrisModelFlags::empty(),
+                    },
+                    texture_hashes: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_distribute() {
+        let counts = |debris: &DEBR, count| -> Vec<u32> {
+            debris
+                .distribute(count)
+                .into_iter()
+                .map(|(_, count)| count)
+                .collect()
+        };
+
+        assert_eq!(counts(&debris(&[50, 50]), 1), [1]);
+        assert_eq!(counts(&debris(&[33, 33, 34]), 10), [3, 3, 4]);
+        assert_eq!(counts(&debris(&[10, 60, 30]), 7), [1, 4, 2]);
+        assert!(debris(&[0]).distribute(5).is_empty());
     }
 }
@@ -3,13 +3,166 @@ use super::prelude::*;
 /// Default Object Manager
 #[derive(Debug)]
 pub struct DOBJ {
-    // TODO:
+    pub editor_id: EditorId,
+    /// FormIDs of the default objects indexed by [`DefaultObject`]
+    pub objects: Vec<FormId>,
 }
 
 impl Record for DOBJ {
     const TYPE: RecordType = DOBJ;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let objects: Vec<FormId> = parser.parse::<Repeated<FormId>>(DATA)?.into_inner();
+
+        Ok(Self { editor_id, objects })
+    }
+}
+
+impl DOBJ {
+    /// Finds the FormID of the default object, returns [`None`] when
+    /// the object is not set
+    pub fn get(&self, object: DefaultObject) -> Option<&FormId> {
+        self.objects
+            .get(object as usize)
+            .filter(|form_id| !form_id.is_null())
+    }
+
+    /// Finds the FormID of the default object with the provided name
+    pub fn get_by_name(&self, name: &str) -> Option<&FormId> {
+        DefaultObject::from_name(name).and_then(|object| self.get(object))
+    }
+
+    /// Iterates the default objects that are set along with their FormIDs
+    pub fn iter(&self) -> impl Iterator<Item = (DefaultObject, &FormId)> {
+        DefaultObject::ALL
+            .iter()
+            .filter_map(|&object| self.get(object).map(|form_id| (object, form_id)))
+    }
+}
+
+/// Slots within the default object table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive)]
+#[repr(u32)]
+pub enum DefaultObject {
+    Stimpack = 0,
+    SuperStimpack = 1,
+    RadX = 2,
+    RadAway = 3,
+    Morphine = 4,
+    PerkParalysis = 5,
+    PlayerFaction = 6,
+    MysteriousStrangerNpc = 7,
+    MysteriousStrangerFaction = 8,
+    DefaultMusic = 9,
+    BattleMusic = 10,
+    DeathMusic = 11,
+    SuccessMusic = 12,
+    LevelUpMusic = 13,
+    PlayerVoiceMale = 14,
+    PlayerVoiceMaleChild = 15,
+    PlayerVoiceFemale = 16,
+    PlayerVoiceFemaleChild = 17,
+    EatPackageDefaultFood = 18,
+    EveryActorAbility = 19,
+    DrugWearsOffImageSpace = 20,
+    DoctorsBag = 21,
+    MissFortuneNpc = 22,
+    MissFortuneFaction = 23,
+    MeltdownExplosion = 24,
+    UnarmedPowerAttackForward = 25,
+    UnarmedPowerAttackBackward = 26,
+    UnarmedPowerAttackLeft = 27,
+    UnarmedPowerAttackRight = 28,
+    UnarmedPowerAttackCrouch = 29,
+    UnarmedPowerAttackCounter = 30,
+    SpotterEffect = 31,
+    ItemDetectedEffect = 32,
+    CateyeMobileEffect = 33,
+}
+
+impl DefaultObject {
+    pub const ALL: [DefaultObject; 34] = [
+        Self::Stimpack,
+        Self::SuperStimpack,
+        Self::RadX,
+        Self::RadAway,
+        Self::Morphine,
+        Self::PerkParalysis,
+        Self::PlayerFaction,
+        Self::MysteriousStrangerNpc,
+        Self::MysteriousStrangerFaction,
+        Self::DefaultMusic,
+        Self::BattleMusic,
+        Self::DeathMusic,
+        Self::SuccessMusic,
+        Self::LevelUpMusic,
+        Self::PlayerVoiceMale,
+        Self::PlayerVoiceMaleChild,
+        Self::PlayerVoiceFemale,
+        Self::PlayerVoiceFemaleChild,
+        Self::EatPackageDefaultFood,
+        Self::EveryActorAbility,
+        Self::DrugWearsOffImageSpace,
+        Self::DoctorsBag,
+        Self::MissFortuneNpc,
+        Self::MissFortuneFaction,
+        Self::MeltdownExplosion,
+        Self::UnarmedPowerAttackForward,
+        Self::UnarmedPowerAttackBackward,
+        Self::UnarmedPowerAttackLeft,
+        Self::UnarmedPowerAttackRight,
+        Self::UnarmedPowerAttackCrouch,
+        Self::UnarmedPowerAttackCounter,
+        Self::SpotterEffect,
+        Self::ItemDetectedEffect,
+        Self::CateyeMobileEffect,
+    ];
+
+    /// Name of the slot as shown in the editor
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Stimpack => "Stimpack",
+            Self::SuperStimpack => "SuperStimpack",
+            Self::RadX => "RadX",
+            Self::RadAway => "RadAway",
+            Self::Morphine => "Morphine",
+            Self::PerkParalysis => "PerkParalysis",
+            Self::PlayerFaction => "PlayerFaction",
+            Self::MysteriousStrangerNpc => "MysteriousStrangerNPC",
+            Self::MysteriousStrangerFaction => "MysteriousStrangerFaction",
+            Self::DefaultMusic => "DefaultMusic",
+            Self::BattleMusic => "BattleMusic",
+            Self::DeathMusic => "DeathMusic",
+            Self::SuccessMusic => "SuccessMusic",
+            Self::LevelUpMusic => "LevelUpMusic",
+            Self::PlayerVoiceMale => "PlayerVoiceMale",
+            Self::PlayerVoiceMaleChild => "PlayerVoiceMaleChild",
+            Self::PlayerVoiceFemale => "PlayerVoiceFemale",
+            Self::PlayerVoiceFemaleChild => "PlayerVoiceFemaleChild",
+            Self::EatPackageDefaultFood => "EatPackageDefaultFood",
+            Self::EveryActorAbility => "EveryActorAbility",
+            Self::DrugWearsOffImageSpace => "DrugWearsOffImageSpace",
+            Self::DoctorsBag => "DoctorsBag",
+            Self::MissFortuneNpc => "MissFortuneNPC",
+            Self::MissFortuneFaction => "MissFortuneFaction",
+            Self::MeltdownExplosion => "MeltdownExplosion",
+            Self::UnarmedPowerAttackForward => "UnarmedForwardPA",
+            Self::UnarmedPowerAttackBackward => "UnarmedBackwardPA",
+            Self::UnarmedPowerAttackLeft => "UnarmedLeftPA",
+            Self::UnarmedPowerAttackRight => "UnarmedRightPA",
+            Self::UnarmedPowerAttackCrouch => "UnarmedCrouchPA",
+            Self::UnarmedPowerAttackCounter => "UnarmedCounterPA",
+            Self::SpotterEffect => "SpotterEffect",
+            Self::ItemDetectedEffect => "ItemDetectedEffect",
+            Self::CateyeMobileEffect => "CateyeMobileEffect",
+        }
+    }
+
+    /// Finds the slot with the provided name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|object| object.name().eq_ignore_ascii_case(name))
     }
 }
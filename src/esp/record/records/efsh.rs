@@ -1,19 +1,37 @@
-use super::prelude::*;
+use super::{debr::DEBR, prelude::*};
+use nom::combinator::opt;
 
 /// Effect Shader
 #[derive(Debug)]
 pub struct EFSH {
-    // TODO:
+    pub editor_id: EditorId,
+    pub fill_texture: Option<String>,
+    pub particle_shader_texture: Option<String>,
+    pub holes_texture: Option<String>,
+    pub data: EffectShaderData,
 }
 
 impl Record for EFSH {
     const TYPE: RecordType = RecordType::new(b"EFSH");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let fill_texture: Option<String> = parser.try_parse(ICON)?;
+        let particle_shader_texture: Option<String> = parser.try_parse(ICO2)?;
+        let holes_texture: Option<String> = parser.try_parse(NAM7)?;
+        let data: EffectShaderData = parser.parse(DATA)?;
+
+        Ok(Self {
+            editor_id,
+            fill_texture,
+            particle_shader_texture,
+            holes_texture,
+            data,
+        })
     }
 }
 
+#[derive(Debug)]
 pub struct EffectShaderData {
     pub flags: EffectShaderFlags,
     /// Membrain shader
@@ -37,12 +55,73 @@ pub struct EffectShaderData {
 
     // Particle Shader
     pub pt_source_blend_mode: BlendMode,
-    pub pt_blend_operation: BlendMode,
+    pub pt_blend_operation: BlendOperation,
     pub pt_z_test_function: ZTestFunction,
     pub pt_dest_blend_mode: BlendMode,
     pub pt_particle_birth_ramp_up_time: f32,
     pub pt_full_particle_birth_up_time: f32,
-    // TODO:
+    pub pt_particle_birth_ramp_down_time: f32,
+    pub pt_full_particle_birth_ratio: f32,
+    pub pt_persistent_particle_birth_ratio: f32,
+    pub pt_particle_lifetime: f32,
+    pub pt_particle_lifetime_variance: f32,
+    pub pt_initial_speed_along_normal: f32,
+    pub pt_acceleration_along_normal: f32,
+    pub pt_initial_velocity: Vector3<f32>,
+    pub pt_acceleration: Vector3<f32>,
+    pub pt_scale_keys: [ScaleKey; 2],
+    pub pt_color_keys: [ColorKey; 3],
+    /// Data only present in newer records
+    pub extended: Option<EffectShaderExtendedData>,
+}
+
+/// Effect shader data that was appended to the end of
+/// [`EffectShaderData`] in later versions
+#[derive(Debug)]
+pub struct EffectShaderExtendedData {
+    // Particle Shader
+    pub pt_initial_speed_along_normal_variance: f32,
+    /// Initial rotation in degrees
+    pub pt_initial_rotation: f32,
+    pub pt_initial_rotation_variance: f32,
+    /// Rotation speed in degrees per second
+    pub pt_rotation_speed: f32,
+    pub pt_rotation_speed_variance: f32,
+    // Addon Models
+    pub am_addon_models: NTypedFormId<DEBR>,
+    // Holes
+    pub ho_start_time: f32,
+    pub ho_end_time: f32,
+    pub ho_start_value: f32,
+    pub ho_end_value: f32,
+    // Edge Effect
+    /// Width of the edge in alpha units
+    pub ee_width: f32,
+    pub ee_edge_color: RGBA,
+    // Particle Shader
+    pub pt_explosion_wind_speed: f32,
+    pub pt_texture_count_u: u32,
+    pub pt_texture_count_v: u32,
+    // Addon Models
+    pub am_fade_in_time: f32,
+    pub am_fade_out_time: f32,
+    pub am_scale_start: f32,
+    pub am_scale_end: f32,
+    pub am_scale_in_time: f32,
+    pub am_scale_out_time: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScaleKey {
+    pub scale: f32,
+    pub time: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ColorKey {
+    pub color: RGBA,
+    pub alpha: f32,
+    pub time: f32,
 }
 
 bitflags! {
@@ -56,6 +135,7 @@ bitflags! {
         const MEMBRANE_SHADER_AFFECT_SKIN_ONL = 0x20;
     }
 }
+#[derive(Debug)]
 pub struct AlphaFade {
     pub alpha_fade_in_time: f32,
     pub full_alpha_time: f32,
@@ -64,7 +144,6 @@ pub struct AlphaFade {
     pub alpha_pulse_amplitude: f32,
     pub alpha_pulse_frequency: f32,
 }
-pub struct EdgeEffect {}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u32)]
@@ -113,3 +192,188 @@ impl FromRecordBytes for EffectShaderFlags {
         map(u8, Self::from_bits_retain)(input)
     }
 }
+
+impl FromRecordBytes for EffectShaderData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, flags) = EffectShaderFlags::parse(input)?;
+        let (input, _unused) = take(3usize)(input)?;
+        let (input, mb_source_blend_mode) = enum_value::<BlendMode>(input)?;
+        let (input, mb_blend_operation) = enum_value::<BlendOperation>(input)?;
+        let (input, mb_z_test_function) = enum_value::<ZTestFunction>(input)?;
+        let (input, te_color) = RGBA::parse(input)?;
+        let (input, te_alpha_fade) = AlphaFade::parse(input)?;
+        let (input, te_texture_animation_speed_u) = le_f32(input)?;
+        let (input, te_texture_animation_speed_v) = le_f32(input)?;
+        let (input, ee_fall_off) = le_f32(input)?;
+        let (input, ee_color) = RGBA::parse(input)?;
+        let (input, ee_alpha_fade) = AlphaFade::parse(input)?;
+        let (input, ee_te_full_alpha_ratio) = le_f32(input)?;
+        let (input, ee_full_alpha_ratio) = le_f32(input)?;
+        let (input, mb_dest_blend_mode) = enum_value::<BlendMode>(input)?;
+        let (input, pt_source_blend_mode) = enum_value::<BlendMode>(input)?;
+        let (input, pt_blend_operation) = enum_value::<BlendOperation>(input)?;
+        let (input, pt_z_test_function) = enum_value::<ZTestFunction>(input)?;
+        let (input, pt_dest_blend_mode) = enum_value::<BlendMode>(input)?;
+        let (input, pt_particle_birth_ramp_up_time) = le_f32(input)?;
+        let (input, pt_full_particle_birth_up_time) = le_f32(input)?;
+        let (input, pt_particle_birth_ramp_down_time) = le_f32(input)?;
+        let (input, pt_full_particle_birth_ratio) = le_f32(input)?;
+        let (input, pt_persistent_particle_birth_ratio) = le_f32(input)?;
+        let (input, pt_particle_lifetime) = le_f32(input)?;
+        let (input, pt_particle_lifetime_variance) = le_f32(input)?;
+        let (input, pt_initial_speed_along_normal) = le_f32(input)?;
+        let (input, pt_acceleration_along_normal) = le_f32(input)?;
+        let (input, pt_initial_velocity) = Vector3::parse(input)?;
+        let (input, pt_acceleration) = Vector3::parse(input)?;
+
+        // Scale and color keys are stored as separate arrays for each of their values
+        let (input, (scale_1, scale_2, scale_time_1, scale_time_2)) =
+            tuple((le_f32, le_f32, le_f32, le_f32))(input)?;
+        let (input, (color_1, color_2, color_3)) =
+            tuple((RGBA::parse, RGBA::parse, RGBA::parse))(input)?;
+        let (input, (alpha_1, alpha_2, alpha_3)) = tuple((le_f32, le_f32, le_f32))(input)?;
+        let (input, (color_time_1, color_time_2, color_time_3)) =
+            tuple((le_f32, le_f32, le_f32))(input)?;
+
+        let (input, extended) = opt(EffectShaderExtendedData::parse)(input)?;
+
+        Ok((
+            input,
+            Self {
+                flags,
+                mb_source_blend_mode,
+                mb_blend_operation,
+                mb_z_test_function,
+                te_color,
+                te_alpha_fade,
+                te_texture_animation_speed_u,
+                te_texture_animation_speed_v,
+                ee_fall_off,
+                ee_color,
+                ee_alpha_fade,
+                ee_te_full_alpha_ratio,
+                ee_full_alpha_ratio,
+                mb_dest_blend_mode,
+                pt_source_blend_mode,
+                pt_blend_operation,
+                pt_z_test_function,
+                pt_dest_blend_mode,
+                pt_particle_birth_ramp_up_time,
+                pt_full_particle_birth_up_time,
+                pt_particle_birth_ramp_down_time,
+                pt_full_particle_birth_ratio,
+                pt_persistent_particle_birth_ratio,
+                pt_particle_lifetime,
+                pt_particle_lifetime_variance,
+                pt_initial_speed_along_normal,
+                pt_acceleration_along_normal,
+                pt_initial_velocity,
+                pt_acceleration,
+                pt_scale_keys: [
+                    ScaleKey {
+                        scale: scale_1,
+                        time: scale_time_1,
+                    },
+                    ScaleKey {
+                        scale: scale_2,
+                        time: scale_time_2,
+                    },
+                ],
+                pt_color_keys: [
+                    ColorKey {
+                        color: color_1,
+                        alpha: alpha_1,
+                        time: color_time_1,
+                    },
+                    ColorKey {
+                        color: color_2,
+                        alpha: alpha_2,
+                        time: color_time_2,
+                    },
+                    ColorKey {
+                        color: color_3,
+                        alpha: alpha_3,
+                        time: color_time_3,
+                    },
+                ],
+                extended,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for EffectShaderExtendedData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, pt_initial_speed_along_normal_variance) = le_f32(input)?;
+        let (input, pt_initial_rotation) = le_f32(input)?;
+        let (input, pt_initial_rotation_variance) = le_f32(input)?;
+        let (input, pt_rotation_speed) = le_f32(input)?;
+        let (input, pt_rotation_speed_variance) = le_f32(input)?;
+        let (input, am_addon_models) = NTypedFormId::parse(input)?;
+        let (input, ho_start_time) = le_f32(input)?;
+        let (input, ho_end_time) = le_f32(input)?;
+        let (input, ho_start_value) = le_f32(input)?;
+        let (input, ho_end_value) = le_f32(input)?;
+        let (input, ee_width) = le_f32(input)?;
+        let (input, ee_edge_color) = RGBA::parse(input)?;
+        let (input, pt_explosion_wind_speed) = le_f32(input)?;
+        let (input, pt_texture_count_u) = le_u32(input)?;
+        let (input, pt_texture_count_v) = le_u32(input)?;
+        let (input, am_fade_in_time) = le_f32(input)?;
+        let (input, am_fade_out_time) = le_f32(input)?;
+        let (input, am_scale_start) = le_f32(input)?;
+        let (input, am_scale_end) = le_f32(input)?;
+        let (input, am_scale_in_time) = le_f32(input)?;
+        let (input, am_scale_out_time) = le_f32(input)?;
+
+        Ok((
+            input,
+            Self {
+                pt_initial_speed_along_normal_variance,
+                pt_initial_rotation,
+                pt_initial_rotation_variance,
+                pt_rotation_speed,
+                pt_rotation_speed_variance,
+                am_addon_models,
+                ho_start_time,
+                ho_end_time,
+                ho_start_value,
+                ho_end_value,
+                ee_width,
+                ee_edge_color,
+                pt_explosion_wind_speed,
+                pt_texture_count_u,
+                pt_texture_count_v,
+                am_fade_in_time,
+                am_fade_out_time,
+                am_scale_start,
+                am_scale_end,
+                am_scale_in_time,
+                am_scale_out_time,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for AlphaFade {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32, le_f32, le_f32, le_f32)),
+            |(
+                alpha_fade_in_time,
+                full_alpha_time,
+                alpha_fade_out_time,
+                persistent_alpha_ratio,
+                alpha_pulse_amplitude,
+                alpha_pulse_frequency,
+            )| Self {
+                alpha_fade_in_time,
+                full_alpha_time,
+                alpha_fade_out_time,
+                persistent_alpha_ratio,
+                alpha_pulse_amplitude,
+                alpha_pulse_frequency,
+            },
+        )(input)
+    }
+}
@@ -1,15 +1,119 @@
-use super::prelude::*;
+use super::{imad::IMAD, ipds::IPDS, ligh::LIGH, prelude::*, soun::SOUN};
+use crate::esp::record::sub::{
+    model::ModelData, object_bounds::ObjectBounds, sound_level::SoundLevel,
+};
 
 /// Explosion
 #[derive(Debug)]
 pub struct EXPL {
-    // TODO:
+    pub editor_id: EditorId,
+    pub object_bounds: ObjectBounds,
+    pub name: Option<String>,
+    pub model_data: Option<ModelData>,
+    /// FormID of either a ENCH or SPEL record
+    pub object_effect: Option<FormId>,
+    pub image_space_modifier: Option<TypedFormId<IMAD>>,
+    pub data: ExplosionData,
+    /// FormID of the object placed at the explosion (e.g. scorch marks)
+    pub placed_impact_object: Option<FormId>,
 }
 
 impl Record for EXPL {
     const TYPE: RecordType = RecordType::new(b"EXPL");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let object_bounds: ObjectBounds = parser.parse(OBND)?;
+        let name: Option<String> = parser.try_parse(FULL)?;
+        let model_data: Option<ModelData> = ModelData::parse_first(parser)?;
+        let object_effect: Option<FormId> = parser.try_parse(EITM)?;
+        let image_space_modifier: Option<TypedFormId<IMAD>> = parser.try_parse(MNAM)?;
+        let data: ExplosionData = parser.parse(DATA)?;
+        let placed_impact_object: Option<FormId> = parser.try_parse(INAM)?;
+
+        Ok(Self {
+            editor_id,
+            object_bounds,
+            name,
+            model_data,
+            object_effect,
+            image_space_modifier,
+            data,
+            placed_impact_object,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ExplosionData {
+    pub force: f32,
+    pub damage: f32,
+    pub radius: f32,
+    pub light: NTypedFormId<LIGH>,
+    pub sound_1: NTypedFormId<SOUN>,
+    pub flags: ExplosionFlags,
+    /// Radius of the image space modifier effect
+    pub image_space_radius: f32,
+    pub impact_data_set: NTypedFormId<IPDS>,
+    pub sound_2: NTypedFormId<SOUN>,
+    pub radiation_level: f32,
+    pub radiation_dissipation_time: f32,
+    pub radiation_radius: f32,
+    pub sound_level: SoundLevel,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct ExplosionFlags: u32 {
+        const U1                                = 0x00000001;
+        const ALWAYS_USES_WORLD_ORIENTATION     = 0x00000002;
+        const KNOCK_DOWN_ALWAYS                 = 0x00000004;
+        const KNOCK_DOWN_BY_FORMULA             = 0x00000008;
+        const IGNORE_LOS_CHECK                  = 0x00000010;
+        const PUSH_EXPLOSION_SOURCE_REF_ONLY    = 0x00000020;
+        const IGNORE_IMAGE_SPACE_SWAP           = 0x00000040;
+    }
+}
+
+impl FromRecordBytes for ExplosionData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, force) = le_f32(input)?;
+        let (input, damage) = le_f32(input)?;
+        let (input, radius) = le_f32(input)?;
+        let (input, light) = NTypedFormId::parse(input)?;
+        let (input, sound_1) = NTypedFormId::parse(input)?;
+        let (input, flags) = ExplosionFlags::parse(input)?;
+        let (input, image_space_radius) = le_f32(input)?;
+        let (input, impact_data_set) = NTypedFormId::parse(input)?;
+        let (input, sound_2) = NTypedFormId::parse(input)?;
+        let (input, radiation_level) = le_f32(input)?;
+        let (input, radiation_dissipation_time) = le_f32(input)?;
+        let (input, radiation_radius) = le_f32(input)?;
+        let (input, sound_level) = SoundLevel::parse(input)?;
+
+        Ok((
+            input,
+            Self {
+                force,
+                damage,
+                radius,
+                light,
+                sound_1,
+                flags,
+                image_space_radius,
+                impact_data_set,
+                sound_2,
+                radiation_level,
+                radiation_dissipation_time,
+                radiation_radius,
+                sound_level,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for ExplosionFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u32, Self::from_bits_retain)(input)
     }
 }
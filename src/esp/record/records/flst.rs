@@ -1,7 +1,7 @@
 use super::prelude::*;
 
 /// FormID List
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FLST {
     pub editor_id: EditorId,
     pub form_ids: Vec<FormId>,
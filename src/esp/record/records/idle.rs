@@ -1,15 +1,113 @@
-use super::prelude::*;
+use super::prelude::{condition::CTDA, model::ModelData, *};
+use nom::combinator::opt;
 
 /// Idle Animation
 #[derive(Debug)]
 pub struct IDLE {
-    // TODO:
+    pub editor_id: EditorId,
+    pub model_data: Option<ModelData>,
+    pub conditions: Vec<CTDA>,
+    pub related: RelatedIdles,
+    pub data: IdleData,
 }
 
 impl Record for IDLE {
     const TYPE: RecordType = RecordType::new(b"IDLE");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let model_data: Option<ModelData> = ModelData::parse_first(parser)?;
+        let conditions: Vec<CTDA> = parser.try_parse_many(CTDA)?;
+        let related: RelatedIdles = parser.parse(ANAM)?;
+        let data: IdleData = parser.parse(DATA)?;
+
+        Ok(Self {
+            editor_id,
+            model_data,
+            conditions,
+            related,
+            data,
+        })
+    }
+}
+
+/// Position of the idle within the idle tree
+#[derive(Debug)]
+pub struct RelatedIdles {
+    pub parent: NTypedFormId<IDLE>,
+    pub previous_sibling: NTypedFormId<IDLE>,
+}
+
+impl FromRecordBytes for RelatedIdles {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((NTypedFormId::parse, NTypedFormId::parse)),
+            |(parent, previous_sibling)| Self {
+                parent,
+                previous_sibling,
+            },
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct IdleData {
+    pub animation_group_section: AnimationGroupSection,
+    pub loop_min: u8,
+    pub loop_max: u8,
+    /// Delay before the idle can be replayed
+    pub replay_delay: i16,
+    pub flags: IdleFlags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum AnimationGroupSection {
+    Idle = 0,
+    Movement = 1,
+    LeftArm = 2,
+    LeftHand = 3,
+    Weapon = 4,
+    WeaponUp = 5,
+    WeaponDown = 6,
+    SpecialIdle = 7,
+    WholeBody = 20,
+    UpperBody = 21,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct IdleFlags: u8 {
+        const NO_ATTACKING = 0x01;
+    }
+}
+
+impl FromRecordBytes for IdleData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                enum_value::<AnimationGroupSection>,
+                u8,
+                u8,
+                u8,
+                le_i16,
+                // Flags are missing from older records
+                opt(IdleFlags::parse),
+                rest,
+            )),
+            |(animation_group_section, loop_min, loop_max, _, replay_delay, flags, _)| Self {
+                animation_group_section,
+                loop_min,
+                loop_max,
+                replay_delay,
+                flags: flags.unwrap_or_else(IdleFlags::empty),
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for IdleFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
     }
 }
@@ -1,13 +1,25 @@
-use super::prelude::*;
+use super::prelude::{idle_animations::IdleAnimations, object_bounds::ObjectBounds, *};
 
 /// Idle Marker
 #[derive(Debug)]
-pub struct IDLM {}
+pub struct IDLM {
+    pub editor_id: EditorId,
+    pub object_bounds: ObjectBounds,
+    pub idle_animations: Option<IdleAnimations>,
+}
 
 impl Record for IDLM {
     const TYPE: RecordType = RecordType::new(b"IDLM");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let object_bounds: ObjectBounds = parser.parse(OBND)?;
+        let idle_animations: Option<IdleAnimations> = IdleAnimations::parse_next(parser)?;
+
+        Ok(Self {
+            editor_id,
+            object_bounds,
+            idle_animations,
+        })
     }
 }
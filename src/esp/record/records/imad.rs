@@ -1,17 +1,331 @@
-use super::prelude::*;
+use super::{prelude::*, soun::SOUN};
+use nalgebra::Vector4;
 
 /// Image Space Adapter
 #[derive(Debug)]
 pub struct IMAD {
     pub editor_id: EditorId,
-    pub data_count: (),
-    // TODO:
+    pub data: ImageSpaceModifierData,
+    pub blur_radius: FloatCurve,
+    pub double_vision_strength: FloatCurve,
+    pub tint_color: ColorCurve,
+    pub fade_color: ColorCurve,
+    pub radial_blur: RadialBlurCurves,
+    pub depth_of_field: DepthOfFieldCurves,
+    pub motion_blur_strength: FloatCurve,
+    pub hdr: HdrModifierCurves,
+    pub bloom: BloomModifierCurves,
+    pub cinematic: CinematicModifierCurves,
+    pub sound_intro: Option<TypedFormId<SOUN>>,
+    pub sound_outro: Option<TypedFormId<SOUN>>,
 }
 
 impl Record for IMAD {
     const TYPE: RecordType = RecordType::new(b"IMAD");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let data: ImageSpaceModifierData = parser.parse(DNAM)?;
+        let blur_radius: FloatCurve = FloatCurve::parse_optional(parser, BNAM)?;
+        let double_vision_strength: FloatCurve = FloatCurve::parse_optional(parser, VNAM)?;
+        let tint_color: ColorCurve = parser.try_parse(TNAM)?.unwrap_or_default();
+        let fade_color: ColorCurve = parser.try_parse(NAM3)?.unwrap_or_default();
+        let radial_blur = RadialBlurCurves {
+            strength: FloatCurve::parse_optional(parser, RNAM)?,
+            ramp_up: FloatCurve::parse_optional(parser, SNAM)?,
+            start: FloatCurve::parse_optional(parser, UNAM)?,
+            ramp_down: FloatCurve::parse_optional(parser, NAM1)?,
+            down_start: FloatCurve::parse_optional(parser, NAM2)?,
+        };
+        let depth_of_field = DepthOfFieldCurves {
+            strength: FloatCurve::parse_optional(parser, WNAM)?,
+            distance: FloatCurve::parse_optional(parser, XNAM)?,
+            range: FloatCurve::parse_optional(parser, YNAM)?,
+        };
+        let motion_blur_strength: FloatCurve = FloatCurve::parse_optional(parser, NAM4)?;
+        let hdr = HdrModifierCurves {
+            eye_adapt_speed: MultAddCurve::parse(parser, 0)?,
+            blur_radius: MultAddCurve::parse(parser, 1)?,
+            skin_dimmer: MultAddCurve::parse(parser, 2)?,
+            emissive_mult: MultAddCurve::parse(parser, 3)?,
+            target_lum: MultAddCurve::parse(parser, 4)?,
+            upper_lum_clamp: MultAddCurve::parse(parser, 5)?,
+            bright_scale: MultAddCurve::parse(parser, 6)?,
+            bright_clamp: MultAddCurve::parse(parser, 7)?,
+            lum_ramp_no_tex: MultAddCurve::parse(parser, 8)?,
+            lum_ramp_min: MultAddCurve::parse(parser, 9)?,
+            lum_ramp_max: MultAddCurve::parse(parser, 10)?,
+            sunlight_dimmer: MultAddCurve::parse(parser, 11)?,
+            grass_dimmer: MultAddCurve::parse(parser, 12)?,
+            tree_dimmer: MultAddCurve::parse(parser, 13)?,
+        };
+        let bloom = BloomModifierCurves {
+            blur_radius: MultAddCurve::parse(parser, 14)?,
+            alpha_mult_interior: MultAddCurve::parse(parser, 15)?,
+            alpha_mult_exterior: MultAddCurve::parse(parser, 16)?,
+        };
+        let cinematic = CinematicModifierCurves {
+            saturation: MultAddCurve::parse(parser, 17)?,
+            contrast: MultAddCurve::parse(parser, 18)?,
+            contrast_avg_lum: MultAddCurve::parse(parser, 19)?,
+            brightness: MultAddCurve::parse(parser, 20)?,
+        };
+        let sound_intro: Option<TypedFormId<SOUN>> = parser.try_parse(RDSD)?;
+        let sound_outro: Option<TypedFormId<SOUN>> = parser.try_parse(RDSI)?;
+
+        Ok(Self {
+            editor_id,
+            data,
+            blur_radius,
+            double_vision_strength,
+            tint_color,
+            fade_color,
+            radial_blur,
+            depth_of_field,
+            motion_blur_strength,
+            hdr,
+            bloom,
+            cinematic,
+            sound_intro,
+            sound_outro,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ImageSpaceModifierData {
+    pub flags: ImageSpaceModifierFlags,
+    /// Duration of the modifier in seconds
+    pub duration: f32,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct ImageSpaceModifierFlags: u32 {
+        const ANIMATABLE = 0x00000001;
+    }
+}
+
+impl FromRecordBytes for ImageSpaceModifierData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            // The remaining data is the number of keys within each of
+            // the curves which is already known from the curves themselves
+            tuple((le_u32, le_f32, rest)),
+            |(flags, duration, _)| Self {
+                flags: ImageSpaceModifierFlags::from_bits_retain(flags),
+                duration,
+            },
+        )(input)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FloatKey {
+    /// Time of the key relative to the duration of the modifier (0-1)
+    pub time: f32,
+    pub value: f32,
+}
+
+impl FromRecordBytes for FloatKey {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(tuple((le_f32, le_f32)), |(time, value)| Self {
+            time,
+            value,
+        })(input)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ColorKey {
+    /// Time of the key relative to the duration of the modifier (0-1)
+    pub time: f32,
+    /// RGBA color, the alpha being the strength of the color
+    pub color: Vector4<f32>,
+}
+
+impl FromRecordBytes for ColorKey {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32, le_f32, le_f32)),
+            |(time, r, g, b, a)| Self {
+                time,
+                color: Vector4::new(r, g, b, a),
+            },
+        )(input)
+    }
+}
+
+/// Keyframed value linearly interpolated between its keys
+#[derive(Debug, Default)]
+pub struct FloatCurve(pub Vec<FloatKey>);
+
+impl FloatCurve {
+    fn parse_optional<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+        ty: RecordType,
+    ) -> Result<Self, RecordParseError<'b>> {
+        Ok(parser.try_parse(ty)?.unwrap_or_default())
     }
+
+    /// Samples the curve at the provided time, returns [`None`]
+    /// when the curve has no keys
+    pub fn sample(&self, time: f32) -> Option<f32> {
+        sample_keys(&self.0, time, |key| key.time, |key| key.value, lerp)
+    }
+}
+
+impl FromRecordBytes for FloatCurve {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(Repeated::parse, |value: Repeated<FloatKey>| {
+            Self(value.into_inner())
+        })(input)
+    }
+}
+
+/// Keyframed color linearly interpolated between its keys
+#[derive(Debug, Default)]
+pub struct ColorCurve(pub Vec<ColorKey>);
+
+impl ColorCurve {
+    /// Samples the curve at the provided time, returns [`None`]
+    /// when the curve has no keys
+    pub fn sample(&self, time: f32) -> Option<Vector4<f32>> {
+        sample_keys(
+            &self.0,
+            time,
+            |key| key.time,
+            |key| key.color,
+            |a, b, amount| a.lerp(&b, amount),
+        )
+    }
+}
+
+impl FromRecordBytes for ColorCurve {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(Repeated::parse, |value: Repeated<ColorKey>| {
+            Self(value.into_inner())
+        })(input)
+    }
+}
+
+/// Samples a set of keys sorted by time, times outside of the
+/// keys are clamped to the first and last keys
+fn sample_keys<K, V, T, G, L>(
+    keys: &[K],
+    time: f32,
+    key_time: T,
+    key_value: G,
+    lerp: L,
+) -> Option<V>
+where
+    T: Fn(&K) -> f32,
+    G: Fn(&K) -> V,
+    L: Fn(V, V, f32) -> V,
+{
+    let first = keys.first()?;
+    if time <= key_time(first) {
+        return Some(key_value(first));
+    }
+
+    for window in keys.windows(2) {
+        let (start, end) = (&window[0], &window[1]);
+        let (start_time, end_time) = (key_time(start), key_time(end));
+
+        if time <= end_time {
+            let span = end_time - start_time;
+            let amount = if span > 0.0 {
+                (time - start_time) / span
+            } else {
+                1.0
+            };
+
+            return Some(lerp(key_value(start), key_value(end), amount));
+        }
+    }
+
+    keys.last().map(key_value)
+}
+
+fn lerp(a: f32, b: f32, amount: f32) -> f32 {
+    a + (b - a) * amount
+}
+
+/// Pair of curves multiplying and adding to an image space value
+#[derive(Debug, Default)]
+pub struct MultAddCurve {
+    pub multiply: FloatCurve,
+    pub add: FloatCurve,
+}
+
+impl MultAddCurve {
+    /// Parses the multiply and add curves for the provided parameter index,
+    /// the curves are stored in the `\x{index}IAD` and `\x{index + 0x40}IAD`
+    /// sub records
+    fn parse<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+        index: u8,
+    ) -> Result<Self, RecordParseError<'b>> {
+        let multiply =
+            FloatCurve::parse_optional(parser, RecordType::new(&[index, b'I', b'A', b'D']))?;
+        let add =
+            FloatCurve::parse_optional(parser, RecordType::new(&[index + 0x40, b'I', b'A', b'D']))?;
+        Ok(Self { multiply, add })
+    }
+
+    /// Applies the curves to `value` at the provided time
+    pub fn apply(&self, value: f32, time: f32) -> f32 {
+        let multiply = self.multiply.sample(time).unwrap_or(1.0);
+        let add = self.add.sample(time).unwrap_or(0.0);
+        value * multiply + add
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RadialBlurCurves {
+    pub strength: FloatCurve,
+    pub ramp_up: FloatCurve,
+    pub start: FloatCurve,
+    pub ramp_down: FloatCurve,
+    pub down_start: FloatCurve,
+}
+
+#[derive(Debug, Default)]
+pub struct DepthOfFieldCurves {
+    pub strength: FloatCurve,
+    pub distance: FloatCurve,
+    pub range: FloatCurve,
+}
+
+#[derive(Debug, Default)]
+pub struct HdrModifierCurves {
+    pub eye_adapt_speed: MultAddCurve,
+    pub blur_radius: MultAddCurve,
+    pub skin_dimmer: MultAddCurve,
+    pub emissive_mult: MultAddCurve,
+    pub target_lum: MultAddCurve,
+    pub upper_lum_clamp: MultAddCurve,
+    pub bright_scale: MultAddCurve,
+    pub bright_clamp: MultAddCurve,
+    pub lum_ramp_no_tex: MultAddCurve,
+    pub lum_ramp_min: MultAddCurve,
+    pub lum_ramp_max: MultAddCurve,
+    pub sunlight_dimmer: MultAddCurve,
+    pub grass_dimmer: MultAddCurve,
+    pub tree_dimmer: MultAddCurve,
+}
+
+#[derive(Debug, Default)]
+pub struct BloomModifierCurves {
+    pub blur_radius: MultAddCurve,
+    pub alpha_mult_interior: MultAddCurve,
+    pub alpha_mult_exterior: MultAddCurve,
+}
+
+#[derive(Debug, Default)]
+pub struct CinematicModifierCurves {
+    pub saturation: MultAddCurve,
+    pub contrast: MultAddCurve,
+    pub contrast_avg_lum: MultAddCurve,
+    pub brightness: MultAddCurve,
 }
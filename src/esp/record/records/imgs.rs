@@ -1,17 +1,225 @@
 use super::prelude::*;
+use nom::combinator::opt;
 
 /// Image Space
 #[derive(Debug)]
 pub struct IMGS {
     pub editor_id: EditorId,
-    pub dnam: (),
-    // TODO:
+    pub data: ImageSpaceData,
 }
 
 impl Record for IMGS {
     const TYPE: RecordType = RecordType::new(b"IMGS");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let data: ImageSpaceData = parser.parse(DNAM)?;
+        Ok(Self { editor_id, data })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImageSpaceData {
+    pub hdr: ImageSpaceHdr,
+    pub bloom: ImageSpaceBloom,
+    pub get_hit: ImageSpaceGetHit,
+    pub night_eye: ImageSpaceNightEye,
+    pub cinematic: ImageSpaceCinematic,
+    pub tint: ImageSpaceTint,
+    pub flags: ImageSpaceFlags,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageSpaceHdr {
+    pub eye_adapt_speed: f32,
+    pub blur_radius: f32,
+    pub blur_passes: f32,
+    pub emissive_mult: f32,
+    pub target_lum: f32,
+    pub upper_lum_clamp: f32,
+    pub bright_scale: f32,
+    pub bright_clamp: f32,
+    pub lum_ramp_no_tex: f32,
+    pub lum_ramp_min: f32,
+    pub lum_ramp_max: f32,
+    pub sunlight_dimmer: f32,
+    pub grass_dimmer: f32,
+    pub tree_dimmer: f32,
+    pub skin_dimmer: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageSpaceBloom {
+    pub blur_radius: f32,
+    pub alpha_mult_interior: f32,
+    pub alpha_mult_exterior: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageSpaceGetHit {
+    pub blur_radius: f32,
+    pub blur_damping_mult: f32,
+    pub damping_mult: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageSpaceNightEye {
+    pub tint_color: Vector3<f32>,
+    pub brightness: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageSpaceCinematic {
+    pub saturation: f32,
+    pub contrast_avg_lum: f32,
+    pub contrast: f32,
+    pub brightness: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageSpaceTint {
+    pub color: Vector3<f32>,
+    /// Amount of the tint color applied (0-1)
+    pub value: f32,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct ImageSpaceFlags: u8 {
+        const SATURATION = 0x01;
+        const CONTRAST   = 0x02;
+        const TINT       = 0x04;
+        const BRIGHTNESS = 0x08;
+    }
+}
+
+impl FromRecordBytes for ImageSpaceData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, hdr) = ImageSpaceHdr::parse(input)?;
+        let (input, bloom) = ImageSpaceBloom::parse(input)?;
+        let (input, get_hit) = ImageSpaceGetHit::parse(input)?;
+        let (input, night_eye) = ImageSpaceNightEye::parse(input)?;
+        let (input, cinematic) = ImageSpaceCinematic::parse(input)?;
+        let (input, tint) = ImageSpaceTint::parse(input)?;
+        // Unknown and unused data, the flags are missing in older records
+        let (input, flags) = opt(map(tuple((take(16usize), u8)), |(_, flags)| {
+            ImageSpaceFlags::from_bits_retain(flags)
+        }))(input)?;
+        let (input, _unused) = rest(input)?;
+
+        Ok((
+            input,
+            Self {
+                hdr,
+                bloom,
+                get_hit,
+                night_eye,
+                cinematic,
+                tint,
+                flags: flags.unwrap_or_else(ImageSpaceFlags::empty),
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for ImageSpaceHdr {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, eye_adapt_speed) = le_f32(input)?;
+        let (input, blur_radius) = le_f32(input)?;
+        let (input, blur_passes) = le_f32(input)?;
+        let (input, emissive_mult) = le_f32(input)?;
+        let (input, target_lum) = le_f32(input)?;
+        let (input, upper_lum_clamp) = le_f32(input)?;
+        let (input, bright_scale) = le_f32(input)?;
+        let (input, bright_clamp) = le_f32(input)?;
+        let (input, lum_ramp_no_tex) = le_f32(input)?;
+        let (input, lum_ramp_min) = le_f32(input)?;
+        let (input, lum_ramp_max) = le_f32(input)?;
+        let (input, sunlight_dimmer) = le_f32(input)?;
+        let (input, grass_dimmer) = le_f32(input)?;
+        let (input, tree_dimmer) = le_f32(input)?;
+        let (input, skin_dimmer) = le_f32(input)?;
+
+        Ok((
+            input,
+            Self {
+                eye_adapt_speed,
+                blur_radius,
+                blur_passes,
+                emissive_mult,
+                target_lum,
+                upper_lum_clamp,
+                bright_scale,
+                bright_clamp,
+                lum_ramp_no_tex,
+                lum_ramp_min,
+                lum_ramp_max,
+                sunlight_dimmer,
+                grass_dimmer,
+                tree_dimmer,
+                skin_dimmer,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for ImageSpaceBloom {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32)),
+            |(blur_radius, alpha_mult_interior, alpha_mult_exterior)| Self {
+                blur_radius,
+                alpha_mult_interior,
+                alpha_mult_exterior,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for ImageSpaceGetHit {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32)),
+            |(blur_radius, blur_damping_mult, damping_mult)| Self {
+                blur_radius,
+                blur_damping_mult,
+                damping_mult,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for ImageSpaceNightEye {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((Vector3::parse, le_f32)),
+            |(tint_color, brightness)| Self {
+                tint_color,
+                brightness,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for ImageSpaceCinematic {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32, le_f32)),
+            |(saturation, contrast_avg_lum, contrast, brightness)| Self {
+                saturation,
+                contrast_avg_lum,
+                contrast,
+                brightness,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for ImageSpaceTint {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(tuple((Vector3::parse, le_f32)), |(color, value)| Self {
+            color,
+            value,
+        })(input)
     }
 }
@@ -1,15 +1,103 @@
-use super::prelude::*;
+use super::{
+    prelude::*,
+    soun::SOUN,
+    txst::{DODT, TXST},
+};
+use crate::esp::record::sub::{model::ModelData, sound_level::SoundLevel};
 
 /// Impact
 #[derive(Debug)]
 pub struct IPCT {
-    // TODO:
+    pub editor_id: EditorId,
+    pub model_data: Option<ModelData>,
+    pub data: ImpactData,
+    pub decal_data: Option<DODT>,
+    pub texture_set: Option<TypedFormId<TXST>>,
+    pub sound_1: Option<TypedFormId<SOUN>>,
+    pub sound_2: Option<TypedFormId<SOUN>>,
 }
 
 impl Record for IPCT {
     const TYPE: RecordType = RecordType::new(b"IPCT");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let model_data: Option<ModelData> = ModelData::parse_first(parser)?;
+        let data: ImpactData = parser.parse(DATA)?;
+        let decal_data: Option<DODT> = parser.try_parse(DODT)?;
+        let texture_set: Option<TypedFormId<TXST>> = parser.try_parse(DNAM)?;
+        let sound_1: Option<TypedFormId<SOUN>> = parser.try_parse(SNAM)?;
+        let sound_2: Option<TypedFormId<SOUN>> = parser.try_parse(NAM1)?;
+
+        Ok(Self {
+            editor_id,
+            model_data,
+            data,
+            decal_data,
+            texture_set,
+            sound_1,
+            sound_2,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ImpactData {
+    pub effect_duration: f32,
+    pub effect_orientation: EffectOrientation,
+    pub angle_threshold: f32,
+    pub placement_radius: f32,
+    pub sound_level: SoundLevel,
+    pub flags: ImpactFlags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum EffectOrientation {
+    SurfaceNormal = 0,
+    ProjectileVector = 1,
+    ProjectileReflection = 2,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct ImpactFlags: u32 {
+        const NO_DECAL_DATA = 0x00000001;
+    }
+}
+
+impl FromRecordBytes for ImpactData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                le_f32,
+                enum_value::<EffectOrientation>,
+                le_f32,
+                le_f32,
+                SoundLevel::parse,
+                ImpactFlags::parse,
+            )),
+            |(
+                effect_duration,
+                effect_orientation,
+                angle_threshold,
+                placement_radius,
+                sound_level,
+                flags,
+            )| Self {
+                effect_duration,
+                effect_orientation,
+                angle_threshold,
+                placement_radius,
+                sound_level,
+                flags,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for ImpactFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u32, Self::from_bits_retain)(input)
     }
 }
@@ -33,6 +33,43 @@ pub struct Impacts {
     pub organic_glow: NTypedFormId<IPCT>,
 }
 
+/// Material of the surface that was impacted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactMaterial {
+    Stone,
+    Dirt,
+    Grass,
+    Glass,
+    Metal,
+    Wood,
+    Organic,
+    Cloth,
+    Water,
+    HollowMetal,
+    OrganicBug,
+    OrganicGlow,
+}
+
+impl Impacts {
+    /// Gets the impact to use for the provided material
+    pub fn get(&self, material: ImpactMaterial) -> &NTypedFormId<IPCT> {
+        match material {
+            ImpactMaterial::Stone => &self.stone,
+            ImpactMaterial::Dirt => &self.dirt,
+            ImpactMaterial::Grass => &self.grass,
+            ImpactMaterial::Glass => &self.glass,
+            ImpactMaterial::Metal => &self.metal,
+            ImpactMaterial::Wood => &self.wood,
+            ImpactMaterial::Organic => &self.organic,
+            ImpactMaterial::Cloth => &self.cloth,
+            ImpactMaterial::Water => &self.water,
+            ImpactMaterial::HollowMetal => &self.hollow_metal,
+            ImpactMaterial::OrganicBug => &self.organic_bug,
+            ImpactMaterial::OrganicGlow => &self.organic_glow,
+        }
+    }
+}
+
 impl FromRecordBytes for Impacts {
     fn parse(input: &[u8]) -> IResult<&[u8], Self> {
         map(
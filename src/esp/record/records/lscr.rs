@@ -1,13 +1,57 @@
-use super::prelude::*;
+use super::{lsct::LSCT, prelude::*};
 
 /// Load Screen
 #[derive(Debug)]
-pub struct LSCR {}
+pub struct LSCR {
+    pub editor_id: EditorId,
+    pub texture: Option<String>,
+    pub small_icon_file_name: Option<String>,
+    pub description: Option<String>,
+    pub locations: Vec<LoadScreenLocation>,
+    pub load_screen_type: Option<TypedFormId<LSCT>>,
+}
 
 impl Record for LSCR {
     const TYPE: RecordType = RecordType::new(b"LSCR");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let texture: Option<String> = parser.try_parse(ICON)?;
+        let small_icon_file_name: Option<String> = parser.try_parse(MICO)?;
+        let description: Option<String> = parser.try_parse(DESC)?;
+        let locations: Vec<LoadScreenLocation> = parser.try_parse_many(LNAM)?;
+        let load_screen_type: Option<TypedFormId<LSCT>> = parser.try_parse(WMI1)?;
+
+        Ok(Self {
+            editor_id,
+            texture,
+            small_icon_file_name,
+            description,
+            locations,
+            load_screen_type,
+        })
+    }
+}
+
+/// Location the load screen can be displayed when travelling to
+#[derive(Debug)]
+pub struct LoadScreenLocation {
+    /// FormID of a CELL or WRLD record
+    pub direct: FormId,
+    /// Worldspace containing the cell at `grid`
+    pub indirect_worldspace: FormId,
+    pub grid: Vector2<i16>,
+}
+
+impl FromRecordBytes for LoadScreenLocation {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((FormId::parse, FormId::parse, le_i16, le_i16)),
+            |(direct, indirect_worldspace, y, x)| Self {
+                direct,
+                indirect_worldspace,
+                grid: Vector2::new(x, y),
+            },
+        )(input)
     }
 }
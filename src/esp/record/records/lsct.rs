@@ -2,12 +2,125 @@ use super::prelude::*;
 
 /// Load Screen Type
 #[derive(Debug)]
-pub struct LSCT {}
+pub struct LSCT {
+    pub editor_id: EditorId,
+    pub data: LoadScreenTypeData,
+}
 
 impl Record for LSCT {
     const TYPE: RecordType = RecordType::new(b"LSCT");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let data: LoadScreenTypeData = parser.parse(DATA)?;
+
+        Ok(Self { editor_id, data })
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadScreenTypeData {
+    pub ty: LoadScreenType,
+    pub layout: LoadScreenLayout,
+    pub stats_font: u32,
+    pub stats_font_color: Vector3<f32>,
+    pub stats: LoadScreenStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum LoadScreenType {
+    None = 0,
+    XpProgress = 1,
+    Objective = 2,
+    Tip = 3,
+    Stats = 4,
+}
+
+/// Placement of the text displayed by the load screen type
+#[derive(Debug)]
+pub struct LoadScreenLayout {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Rotation in degrees
+    pub orientation: f32,
+    pub font: u32,
+    pub font_color: Vector3<f32>,
+    pub font_alignment: FontAlignment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum FontAlignment {
+    Left = 1,
+    Center = 2,
+    Right = 4,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum LoadScreenStats {
+    All = 0,
+    U1 = 1,
+    U2 = 2,
+    U3 = 3,
+    U4 = 4,
+    U5 = 5,
+    U6 = 6,
+    U7 = 7,
+    U8 = 8,
+    U9 = 9,
+    U10 = 10,
+}
+
+impl FromRecordBytes for LoadScreenTypeData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, ty) = enum_value::<LoadScreenType>(input)?;
+        let (input, layout) = LoadScreenLayout::parse(input)?;
+        let (input, _unknown) = take(20usize)(input)?;
+        let (input, stats_font) = le_u32(input)?;
+        let (input, stats_font_color) = Vector3::parse(input)?;
+        let (input, _unknown) = take(4usize)(input)?;
+        let (input, stats) = enum_value::<LoadScreenStats>(input)?;
+
+        Ok((
+            input,
+            Self {
+                ty,
+                layout,
+                stats_font,
+                stats_font_color,
+                stats,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for LoadScreenLayout {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                le_u32,
+                le_u32,
+                le_u32,
+                le_u32,
+                le_f32,
+                le_u32,
+                Vector3::parse,
+                enum_value::<FontAlignment>,
+            )),
+            |(x, y, width, height, orientation, font, font_color, font_alignment)| Self {
+                x,
+                y,
+                width,
+                height,
+                orientation,
+                font,
+                font_color,
+                font_alignment,
+            },
+        )(input)
     }
 }
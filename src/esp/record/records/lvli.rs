@@ -1,13 +1,44 @@
-use super::prelude::*;
+use super::{
+    glob::GLOB,
+    prelude::{
+        leveled_list::{LeveledEntry, LeveledFlags},
+        object_bounds::ObjectBounds,
+        *,
+    },
+};
 
 /// Leveled Item
 #[derive(Debug)]
-pub struct LVLI {}
+pub struct LVLI {
+    pub editor_id: EditorId,
+    pub object_bounds: ObjectBounds,
+    pub chance_none: u8,
+    pub flags: LeveledFlags,
+    /// Global variable providing the chance none, overrides `chance_none`
+    pub global: Option<TypedFormId<GLOB>>,
+    /// Leveled entries, the reference is a FormID of a ARMO, AMMO, MISC, WEAP,
+    /// BOOK, LVLI, KEYM, ALCH, NOTE, IMOD, CMNY, CCRD, LIGH, CHIP or MSTT record
+    pub entries: Vec<LeveledEntry>,
+}
 
 impl Record for LVLI {
-    const TYPE: RecordType = RecordType::new(b"LVLI");
+    const TYPE: RecordType = LVLI;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let object_bounds: ObjectBounds = parser.parse(OBND)?;
+        let chance_none: u8 = parser.parse(LVLD)?;
+        let flags: LeveledFlags = parser.try_parse(LVLF)?.unwrap_or_else(LeveledFlags::empty);
+        let global: Option<TypedFormId<GLOB>> = parser.try_parse(LVLG)?;
+        let entries: Vec<LeveledEntry> = parser.parse_collection()?;
+
+        Ok(Self {
+            editor_id,
+            object_bounds,
+            chance_none,
+            flags,
+            global,
+            entries,
+        })
     }
 }
@@ -1,13 +1,40 @@
-use super::prelude::*;
+use super::prelude::{
+    leveled_list::{LeveledEntry, LeveledFlags},
+    model::ModelData,
+    object_bounds::ObjectBounds,
+    *,
+};
 
 /// Leveled NPC
 #[derive(Debug)]
-pub struct LVLN {}
+pub struct LVLN {
+    pub editor_id: EditorId,
+    pub object_bounds: ObjectBounds,
+    pub chance_none: u8,
+    pub flags: LeveledFlags,
+    /// Leveled entries, the reference is a FormID of a NPC_ or LVLN record
+    pub entries: Vec<LeveledEntry>,
+    pub model_data: Option<ModelData>,
+}
 
 impl Record for LVLN {
-    const TYPE: RecordType = RecordType::new(b"LVLN");
+    const TYPE: RecordType = LVLN;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let object_bounds: ObjectBounds = parser.parse(OBND)?;
+        let chance_none: u8 = parser.parse(LVLD)?;
+        let flags: LeveledFlags = parser.try_parse(LVLF)?.unwrap_or_else(LeveledFlags::empty);
+        let entries: Vec<LeveledEntry> = parser.parse_collection()?;
+        let model_data: Option<ModelData> = ModelData::parse_first(parser)?;
+
+        Ok(Self {
+            editor_id,
+            object_bounds,
+            chance_none,
+            flags,
+            entries,
+            model_data,
+        })
     }
 }
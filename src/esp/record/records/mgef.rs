@@ -1,4 +1,4 @@
-use super::{prelude::*, soun::SOUN};
+use super::{efsh::EFSH, prelude::*, soun::SOUN};
 use crate::esp::record::sub::{actor_values::ActorValue, model::ModelData};
 
 /// Magic effect
@@ -45,8 +45,8 @@ pub struct MagicEffectData {
     pub resistance_type: ActorValue,
     pub light: TypedFormId<() /* LIGH */>,
     pub projectile_speed: f32,
-    pub effect_shader: TypedFormId<() /* EFSH */>,
-    pub object_display_shader: TypedFormId<() /* EFSH */>,
+    pub effect_shader: TypedFormId<EFSH>,
+    pub object_display_shader: TypedFormId<EFSH>,
     pub effect_sound: TypedFormId<SOUN>,
     pub bold_sound: TypedFormId<SOUN>,
     pub hit_sound: TypedFormId<SOUN>,
@@ -146,8 +146,8 @@ impl FromRecordBytes for MagicEffectData {
         let (input, _unused) = le_u16(input)?;
         let (input, light) = <TypedFormId<()>>::parse(input)?;
         let (input, projectile_speed) = le_f32(input)?;
-        let (input, effect_shader) = <TypedFormId<()>>::parse(input)?;
-        let (input, object_display_shader) = <TypedFormId<()>>::parse(input)?;
+        let (input, effect_shader) = TypedFormId::parse(input)?;
+        let (input, object_display_shader) = TypedFormId::parse(input)?;
         let (input, effect_sound) = <TypedFormId<SOUN>>::parse(input)?;
         let (input, bold_sound) = <TypedFormId<SOUN>>::parse(input)?;
         let (input, hit_sound) = <TypedFormId<SOUN>>::parse(input)?;
@@ -1,13 +1,183 @@
-use super::prelude::*;
+use super::{prelude::*, soun::SOUN};
 
 /// Media Set
 #[derive(Debug)]
-pub struct MSET {}
+pub struct MSET {
+    pub editor_id: EditorId,
+    pub name: Option<String>,
+    pub ty: MediaSetType,
+    /// Tracks of the set, for location sets these are the day outer, middle
+    /// and inner layers followed by the night outer, middle and inner layers.
+    /// Battle sets only use the first track, dungeon sets use the first three
+    /// as the loop, explore and suspense tracks
+    pub tracks: [MediaSetTrack; 6],
+    pub enabled_layers: MediaSetLayerFlags,
+    /// Wait time for suspense tracks, minimum time on for dungeon
+    /// and location sets, minimum daytime delay for incidental sets
+    pub min_time_on: Option<f32>,
+    /// Fade out time of the loop for battle sets, recovery time for
+    /// suspense tracks and minimum nighttime delay for incidental sets
+    pub fade_out: Option<f32>,
+    /// Maximum time on for dungeon and location sets, maximum daytime
+    /// delay for incidental sets
+    pub max_time_on: Option<f32>,
+    /// Maximum nighttime delay for incidental sets
+    pub night_time_max: Option<f32>,
+    /// Intro sound, daytime sound for incidental sets
+    pub intro: Option<TypedFormId<SOUN>>,
+    /// Outro sound, nighttime sound for incidental sets
+    pub outro: Option<TypedFormId<SOUN>>,
+}
 
 impl Record for MSET {
     const TYPE: RecordType = RecordType::new(b"MSET");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let name: Option<String> = parser.try_parse(FULL)?;
+        let ty: MediaSetType = parser.parse(NAM1)?;
+
+        let mut file_names: [Option<String>; 6] = [
+            parser.try_parse(NAM2)?,
+            parser.try_parse(NAM3)?,
+            parser.try_parse(NAM4)?,
+            parser.try_parse(NAM5)?,
+            parser.try_parse(NAM6)?,
+            parser.try_parse(NAM7)?,
+        ];
+        let dbs: [Option<f32>; 6] = [
+            parser.try_parse(NAM8)?,
+            parser.try_parse(NAM9)?,
+            parser.try_parse(NAM0)?,
+            parser.try_parse(ANAM)?,
+            parser.try_parse(BNAM)?,
+            parser.try_parse(CNAM)?,
+        ];
+        let boundaries: [Option<f32>; 6] = [
+            parser.try_parse(JNAM)?,
+            parser.try_parse(KNAM)?,
+            parser.try_parse(LNAM)?,
+            parser.try_parse(MNAM)?,
+            parser.try_parse(NNAM)?,
+            parser.try_parse(ONAM)?,
+        ];
+
+        let tracks: [MediaSetTrack; 6] = std::array::from_fn(|index| MediaSetTrack {
+            file_name: file_names[index].take(),
+            db: dbs[index],
+            boundary: boundaries[index],
+        });
+
+        let enabled_layers: MediaSetLayerFlags = parser
+            .try_parse(PNAM)?
+            .unwrap_or(MediaSetLayerFlags::empty());
+        let min_time_on: Option<f32> = parser.try_parse(DNAM)?;
+        let fade_out: Option<f32> = parser.try_parse(ENAM)?;
+        let max_time_on: Option<f32> = parser.try_parse(FNAM)?;
+        let night_time_max: Option<f32> = parser.try_parse(GNAM)?;
+        let intro: Option<TypedFormId<SOUN>> = parser.try_parse(HNAM)?;
+        let outro: Option<TypedFormId<SOUN>> = parser.try_parse(INAM)?;
+
+        // Unused data
+        parser.skip_type(DATA);
+
+        Ok(Self {
+            editor_id,
+            name,
+            ty,
+            tracks,
+            enabled_layers,
+            min_time_on,
+            fade_out,
+            max_time_on,
+            night_time_max,
+            intro,
+            outro,
+        })
+    }
+}
+
+impl MSET {
+    /// Enabled layers of a location set for the time of day
+    pub fn layers(&self, night: bool) -> impl Iterator<Item = (MediaSetLayer, &MediaSetTrack)> {
+        let layers = if night {
+            [
+                MediaSetLayer::NightOuter,
+                MediaSetLayer::NightMiddle,
+                MediaSetLayer::NightInner,
+            ]
+        } else {
+            [
+                MediaSetLayer::DayOuter,
+                MediaSetLayer::DayMiddle,
+                MediaSetLayer::DayInner,
+            ]
+        };
+
+        layers
+            .into_iter()
+            .filter(|layer| self.enabled_layers.contains(layer.flag()))
+            .map(|layer| (layer, &self.tracks[layer as usize]))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(i32)]
+pub enum MediaSetType {
+    NoSet = -1,
+    Battle = 0,
+    Location = 1,
+    Dungeon = 2,
+    Incidental = 3,
+}
+
+impl FromRecordBytes for MediaSetType {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        enum_value(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct MediaSetTrack {
+    pub file_name: Option<String>,
+    /// Volume of the track in decibels
+    pub db: Option<f32>,
+    /// Percentage of the distance into the location at which
+    /// the layer starts playing
+    pub boundary: Option<f32>,
+}
+
+/// Layers of a location media set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaSetLayer {
+    DayOuter = 0,
+    DayMiddle = 1,
+    DayInner = 2,
+    NightOuter = 3,
+    NightMiddle = 4,
+    NightInner = 5,
+}
+
+impl MediaSetLayer {
+    pub fn flag(self) -> MediaSetLayerFlags {
+        MediaSetLayerFlags::from_bits_retain(1 << self as u8)
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct MediaSetLayerFlags: u8 {
+        const DAY_OUTER    = 0x01;
+        const DAY_MIDDLE   = 0x02;
+        const DAY_INNER    = 0x04;
+        const NIGHT_OUTER  = 0x08;
+        const NIGHT_MIDDLE = 0x10;
+        const NIGHT_INNER  = 0x20;
+    }
+}
+
+impl FromRecordBytes for MediaSetLayerFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
     }
 }
@@ -60,6 +60,18 @@ impl Record for NOTE {
     }
 }
 
+impl NOTE {
+    /// Contents of text notes, other types store a topic in the same field
+    pub fn text(&self) -> Option<String> {
+        if self.ty != Some(NoteType::Text) {
+            return None;
+        }
+        let NoteTopic(RawBytes(bytes)) = self.text_topic.as_ref()?;
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        Some(String::from_utf8_lossy(bytes).to_string())
+    }
+}
+
 /// Could be either a String or TypedFormId<DIAL>
 #[derive(Debug)]
 pub struct NoteTopic(RawBytes);
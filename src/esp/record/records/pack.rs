@@ -1,13 +1,645 @@
-use super::prelude::*;
+use super::{
+    cell::CELL,
+    csty::CSTY,
+    dial::DIAL,
+    idle::IDLE,
+    prelude::{condition::CTDA, idle_animations::IdleAnimations, script::Script, *},
+};
+use nom::combinator::opt;
 
-/// Package (AI Package?)
+/// Package (AI Package)
 #[derive(Debug)]
-pub struct PACK {}
+pub struct PACK {
+    pub editor_id: EditorId,
+    pub data: PackageData,
+    pub location: Option<PackageLocation>,
+    pub location_2: Option<PackageLocation>,
+    pub schedule: PackageSchedule,
+    pub target: Option<PackageTarget>,
+    pub conditions: Vec<CTDA>,
+    pub idle_animations: Option<IdleAnimations>,
+    pub combat_style: Option<TypedFormId<CSTY>>,
+    pub eat_marker: bool,
+    pub escort_distance: Option<u32>,
+    pub follow_trigger_radius: Option<f32>,
+    pub patrol_repeatable: Option<bool>,
+    pub use_weapon_data: Option<UseWeaponData>,
+    pub target_2: Option<PackageTarget>,
+    pub use_item_marker: bool,
+    pub ambush_marker: bool,
+    pub dialogue_data: Option<DialogueData>,
+    pub on_begin: Option<PackageEvent>,
+    pub on_end: Option<PackageEvent>,
+    pub on_change: Option<PackageEvent>,
+}
 
 impl Record for PACK {
-    const TYPE: RecordType = RecordType::new(b"PACK");
+    const TYPE: RecordType = PACK;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let data: PackageData = parser.parse(PKDT)?;
+        let location: Option<PackageLocation> = parser.try_parse(PLDT)?;
+        let mut location_2: Option<PackageLocation> = parser.try_parse(PLD2)?;
+        let schedule: PackageSchedule = parser.parse(PSDT)?;
+        let target: Option<PackageTarget> = parser.try_parse(PTDT)?;
+        let conditions: Vec<CTDA> = parser.try_parse_many(CTDA)?;
+        let idle_animations: Option<IdleAnimations> = IdleAnimations::parse_next(parser)?;
+        let combat_style: Option<TypedFormId<CSTY>> = parser.try_parse(CNAM)?;
+        let eat_marker: bool = parser.next_if(PKED).is_some();
+        let escort_distance: Option<u32> = parser.try_parse(PKE2)?;
+        let follow_trigger_radius: Option<f32> = parser.try_parse(PKFD)?;
+        let patrol_repeatable: Option<bool> =
+            parser.try_parse::<PatrolFlags>(PKPT)?.map(|value| value.0);
+        let use_weapon_data: Option<UseWeaponData> = parser.try_parse(PKW3)?;
+        let target_2: Option<PackageTarget> = parser.try_parse(PTD2)?;
+        let use_item_marker: bool = parser.next_if(PUID).is_some();
+        let ambush_marker: bool = parser.next_if(PKAM).is_some();
+        let dialogue_data: Option<DialogueData> = parser.try_parse(PKDD)?;
+
+        // Dialogue packages store their second location after the dialogue data
+        if location_2.is_none() {
+            location_2 = parser.try_parse(PLD2)?;
+        }
+
+        let on_begin: Option<PackageEvent> = PackageEvent::parse_event(parser, POBA)?;
+        let on_end: Option<PackageEvent> = PackageEvent::parse_event(parser, POEA)?;
+        let on_change: Option<PackageEvent> = PackageEvent::parse_event(parser, POCA)?;
+
+        Ok(Self {
+            editor_id,
+            data,
+            location,
+            location_2,
+            schedule,
+            target,
+            conditions,
+            idle_animations,
+            combat_style,
+            eat_marker,
+            escort_distance,
+            follow_trigger_radius,
+            patrol_repeatable,
+            use_weapon_data,
+            target_2,
+            use_item_marker,
+            ambush_marker,
+            dialogue_data,
+            on_begin,
+            on_end,
+            on_change,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct PackageData {
+    pub flags: PackageFlags,
+    pub ty: PackageType,
+    pub behavior_flags: PackageBehaviorFlags,
+    /// Flags specific to the package type
+    pub type_specific_flags: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum PackageType {
+    Find = 0,
+    Follow = 1,
+    Escort = 2,
+    Eat = 3,
+    Sleep = 4,
+    Wander = 5,
+    Travel = 6,
+    Accompany = 7,
+    UseItemAt = 8,
+    Ambush = 9,
+    FleeNotCombat = 10,
+    U11 = 11,
+    Sandbox = 12,
+    Patrol = 13,
+    Guard = 14,
+    Dialogue = 15,
+    UseWeapon = 16,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct PackageFlags: u32 {
+        const OFFERS_SERVICES               = 0x00000001;
+        const MUST_REACH_LOCATION           = 0x00000002;
+        const MUST_COMPLETE                 = 0x00000004;
+        const LOCK_DOORS_AT_PACKAGE_START   = 0x00000008;
+        const LOCK_DOORS_AT_PACKAGE_END     = 0x00000010;
+        const LOCK_DOORS_AT_LOCATION        = 0x00000020;
+        const UNLOCK_DOORS_AT_PACKAGE_START = 0x00000040;
+        const UNLOCK_DOORS_AT_PACKAGE_END   = 0x00000080;
+        const UNLOCK_DOORS_AT_LOCATION      = 0x00000100;
+        const CONTINUE_IF_PC_NEAR           = 0x00000200;
+        const ONCE_PER_DAY                  = 0x00000400;
+        const U1                            = 0x00000800;
+        const SKIP_FALLOUT_BEHAVIOR         = 0x00001000;
+        const ALWAYS_RUN                    = 0x00002000;
+        const U2                            = 0x00004000;
+        const U3                            = 0x00008000;
+        const U4                            = 0x00010000;
+        const ALWAYS_SNEAK                  = 0x00020000;
+        const ALLOW_SWIMMING                = 0x00040000;
+        const ALLOW_FALLS                   = 0x00080000;
+        const HEAD_TRACKING_OFF             = 0x00100000;
+        const WEAPONS_UNEQUIPPED            = 0x00200000;
+        const DEFENSIVE_COMBAT              = 0x00400000;
+        const WEAPON_DRAWN                  = 0x00800000;
+        const NO_IDLE_ANIMS                 = 0x01000000;
+        const PRETEND_IN_COMBAT             = 0x02000000;
+        const CONTINUE_DURING_COMBAT        = 0x04000000;
+        const NO_COMBAT_ALERT               = 0x08000000;
+        const NO_WARN_ATTACK_BEHAVIOUR      = 0x10000000;
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct PackageBehaviorFlags: u16 {
+        const HELLOS_TO_PLAYER           = 0x0001;
+        const RANDOM_CONVERSATIONS       = 0x0002;
+        const OBSERVE_COMBAT_BEHAVIOR    = 0x0004;
+        const U1                         = 0x0008;
+        const REACTION_TO_PLAYER_ACTIONS = 0x0010;
+        const FRIENDLY_FIRE_COMMENTS     = 0x0020;
+        const AGGRO_RADIUS_BEHAVIOR      = 0x0040;
+        const ALLOW_IDLE_CHATTER         = 0x0080;
+        const AVOID_RADIATION            = 0x0100;
+    }
+}
+
+#[derive(Debug)]
+pub struct PackageLocation {
+    pub location: LocationType,
+    pub radius: i32,
+}
+
+#[derive(Debug)]
+pub enum LocationType {
+    /// FormID of a REFR, ACHR, ACRE, PGRE or PMIS record
+    NearReference(FormId),
+    InCell(TypedFormId<CELL>),
+    NearCurrentLocation,
+    NearEditorLocation,
+    /// FormID of a base object
+    ObjectId(FormId),
+    ObjectType(u32),
+    NearLinkedReference,
+    AtPackageLocation,
+}
+
+#[derive(Debug)]
+pub struct PackageSchedule {
+    /// Month of the year (0-11) or -1 for any month
+    pub month: i8,
+    pub day_of_week: DayOfWeek,
+    /// Day of the month (1-31) or 0 for any date
+    pub date: u8,
+    /// Hour of the day the package starts (0-23) or -1 for any time
+    pub time: i8,
+    /// Duration of the package in hours, zero runs the package from its
+    /// start time until the end of the day
+    pub duration: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(i8)]
+pub enum DayOfWeek {
+    Any = -1,
+    Sunday = 0,
+    Monday = 1,
+    Tuesday = 2,
+    Wednesday = 3,
+    Thursday = 4,
+    Friday = 5,
+    Saturday = 6,
+    Weekdays = 7,
+    Weekends = 8,
+    MondayWednesdayFriday = 9,
+    TuesdayThursday = 10,
+}
+
+impl DayOfWeek {
+    /// Checks if the provided day of the week (0 = Sunday, 6 = Saturday)
+    /// is covered by this value
+    pub fn contains(&self, day: u8) -> bool {
+        match self {
+            DayOfWeek::Any => true,
+            DayOfWeek::Weekdays => (1..=5).contains(&day),
+            DayOfWeek::Weekends => day == 0 || day == 6,
+            DayOfWeek::MondayWednesdayFriday => matches!(day, 1 | 3 | 5),
+            DayOfWeek::TuesdayThursday => matches!(day, 2 | 4),
+            value => *value as i8 == day as i8,
+        }
+    }
+}
+
+impl PackageSchedule {
+    /// Checks whether the schedule allows the package to run at the provided
+    /// game time
+    ///
+    /// * `month` - Month of the year (0-11)
+    /// * `day_of_week` - Day of the week (0 = Sunday, 6 = Saturday)
+    /// * `date` - Day of the month (1-31)
+    /// * `hour` - Hour of the day (0-24)
+    pub fn is_active(&self, month: u8, day_of_week: u8, date: u8, hour: f32) -> bool {
+        if self.month >= 0 && self.month as u8 != month {
+            return false;
+        }
+
+        if !self.day_of_week.contains(day_of_week) {
+            return false;
+        }
+
+        if self.date != 0 && self.date != date {
+            return false;
+        }
+
+        // Packages without a time run all day
+        if self.time < 0 {
+            return true;
+        }
+
+        let start = self.time as f32;
+        let end = match self.duration {
+            // Without a duration the package runs until midnight
+            ..=0 => 24.0,
+            duration => start + duration as f32,
+        };
+
+        // Schedules can wrap around midnight
+        if end > 24.0 {
+            hour >= start || hour < end - 24.0
+        } else {
+            hour >= start && hour < end
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PackageTarget {
+    pub target: TargetType,
+    /// Count or distance depending on the package type
+    pub count_distance: i32,
+    pub unknown: Option<f32>,
+}
+
+#[derive(Debug)]
+pub enum TargetType {
+    /// FormID of a REFR, ACHR, ACRE, PGRE or PMIS record
+    SpecificReference(FormId),
+    /// FormID of a base object or FLST
+    ObjectId(FormId),
+    ObjectType(u32),
+    LinkedReference,
+}
+
+/// Patrol repeatable flag followed by optional unused bytes
+struct PatrolFlags(bool);
+
+#[derive(Debug)]
+pub struct UseWeaponData {
+    pub flags: UseWeaponFlags,
+    pub fire_rate: FireRate,
+    pub fire_count: FireCount,
+    pub number_of_bursts: u16,
+    pub shoots_per_volley_min: u16,
+    pub shoots_per_volley_max: u16,
+    pub pause_between_volleys_min: f32,
+    pub pause_between_volleys_max: f32,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct UseWeaponFlags: u32 {
+        const ALWAYS_HIT               = 0x00000001;
+        const DO_NO_DAMAGE             = 0x00000100;
+        const CROUCH_TO_RELOAD         = 0x00010000;
+        const HOLD_FIRE_WHEN_BLOCKED   = 0x01000000;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum FireRate {
+    AutoFire = 0,
+    VolleyFire = 1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum FireCount {
+    NumberOfBursts = 0,
+    RepeatFire = 1,
+}
+
+#[derive(Debug)]
+pub struct DialogueData {
+    pub fov: f32,
+    pub topic: NTypedFormId<DIAL>,
+    pub flags: DialogueFlags,
+    pub ty: Option<DialogueType>,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct DialogueFlags: u32 {
+        const NO_HEADTRACKING  = 0x00000001;
+        const DONT_CONTROL_TARGET_MOVEMENT = 0x00000100;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum DialogueType {
+    Conversation = 0,
+    SayTo = 1,
+}
+
+/// Idle, script and topic run when a package begins, ends or changes
+#[derive(Debug)]
+pub struct PackageEvent {
+    pub idle: NTypedFormId<IDLE>,
+    pub embedded_script: Option<Script>,
+    pub topic: NTypedFormId<DIAL>,
+}
+
+impl PackageEvent {
+    /// Parses the package event that starts with the provided marker type
+    fn parse_event<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+        marker: RecordType,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        if parser.next_if(marker).is_none() {
+            return Ok(None);
+        }
+
+        let idle: NTypedFormId<IDLE> = parser.parse(INAM)?;
+        let embedded_script: Option<Script> = Script::parse_next(parser)?;
+        let topic: NTypedFormId<DIAL> = parser.parse(TNAM)?;
+
+        Ok(Some(Self {
+            idle,
+            embedded_script,
+            topic,
+        }))
+    }
+}
+
+impl FromRecordBytes for PackageData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                PackageFlags::parse,
+                enum_value::<PackageType>,
+                u8,
+                PackageBehaviorFlags::parse,
+                le_u16,
+                take(2usize),
+            )),
+            |(flags, ty, _, behavior_flags, type_specific_flags, _)| Self {
+                flags,
+                ty,
+                behavior_flags,
+                type_specific_flags,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for PackageFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u32, Self::from_bits_retain)(input)
+    }
+}
+
+impl FromRecordBytes for PackageBehaviorFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u16, Self::from_bits_retain)(input)
+    }
+}
+
+impl FromRecordBytes for PackageLocation {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, ty) = le_i32(input)?;
+        let (value_input, value) = le_u32(input)?;
+        let (input, radius) = le_i32(value_input)?;
+
+        let location = match ty {
+            0 => LocationType::NearReference(FormId(value)),
+            1 => LocationType::InCell(FormId(value).into_typed()),
+            2 => LocationType::NearCurrentLocation,
+            3 => LocationType::NearEditorLocation,
+            4 => LocationType::ObjectId(FormId(value)),
+            5 => LocationType::ObjectType(value),
+            6 => LocationType::NearLinkedReference,
+            7 => LocationType::AtPackageLocation,
+            _ => {
+                return Err(nom::Err::Error(nom::error::Error::new(
+                    value_input,
+                    nom::error::ErrorKind::Switch,
+                )))
+            }
+        };
+
+        Ok((input, Self { location, radius }))
+    }
+}
+
+impl FromRecordBytes for PackageSchedule {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((i8, enum_value::<DayOfWeek>, u8, i8, le_i32)),
+            |(month, day_of_week, date, time, duration)| Self {
+                month,
+                day_of_week,
+                date,
+                time,
+                duration,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for PackageTarget {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, ty) = le_i32(input)?;
+        let (value_input, value) = le_u32(input)?;
+        let (input, count_distance) = le_i32(value_input)?;
+        let (input, unknown) = opt(le_f32)(input)?;
+
+        let target = match ty {
+            0 => TargetType::SpecificReference(FormId(value)),
+            1 => TargetType::ObjectId(FormId(value)),
+            2 => TargetType::ObjectType(value),
+            3 => TargetType::LinkedReference,
+            _ => {
+                return Err(nom::Err::Error(nom::error::Error::new(
+                    value_input,
+                    nom::error::ErrorKind::Switch,
+                )))
+            }
+        };
+
+        Ok((
+            input,
+            Self {
+                target,
+                count_distance,
+                unknown,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for PatrolFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(tuple((bool::parse, rest)), |(repeatable, _)| {
+            Self(repeatable)
+        })(input)
+    }
+}
+
+impl FromRecordBytes for UseWeaponData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                UseWeaponFlags::parse,
+                enum_value::<FireRate>,
+                enum_value::<FireCount>,
+                le_u16,
+                le_u16,
+                le_u16,
+                le_f32,
+                le_f32,
+                rest,
+            )),
+            |(
+                flags,
+                fire_rate,
+                fire_count,
+                number_of_bursts,
+                shoots_per_volley_min,
+                shoots_per_volley_max,
+                pause_between_volleys_min,
+                pause_between_volleys_max,
+                _,
+            )| Self {
+                flags,
+                fire_rate,
+                fire_count,
+                number_of_bursts,
+                shoots_per_volley_min,
+                shoots_per_volley_max,
+                pause_between_volleys_min,
+                pause_between_volleys_max,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for UseWeaponFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u32, Self::from_bits_retain)(input)
+    }
+}
+
+impl FromRecordBytes for DialogueData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, fov) = le_f32(input)?;
+        let (input, topic) = NTypedFormId::parse(input)?;
+        let (input, flags) = DialogueFlags::parse(input)?;
+        // Unused and the dialogue type are absent in older records
+        let (input, ty) = opt(map(
+            tuple((take(4usize), enum_value::<DialogueType>)),
+            |(_, ty)| ty,
+        ))(input)?;
+        let (input, _unknown) = rest(input)?;
+
+        Ok((
+            input,
+            Self {
+                fov,
+                topic,
+                flags,
+                ty,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for DialogueFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u32, Self::from_bits_retain)(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DayOfWeek, PackageSchedule};
+
+    fn schedule(time: i8, duration: i32) -> PackageSchedule {
+        PackageSchedule {
+            month: -1,
+            day_of_week: DayOfWeek::Any,
+            date: 0,
+            time,
+            duration,
+        }
+    }
+
+    #[test]
+    fn test_hours() {
+        let schedule = schedule(8, 4);
+        assert!(!schedule.is_active(0, 0, 1, 7.9));
+        assert!(schedule.is_active(0, 0, 1, 8.0));
+        assert!(schedule.is_active(0, 0, 1, 11.9));
+        assert!(!schedule.is_active(0, 0, 1, 12.0));
+
+        // Any time
+        assert!(self::schedule(-1, 0).is_active(0, 0, 1, 3.0));
+    }
+
+    #[test]
+    fn test_midnight_wrap() {
+        let schedule = schedule(22, 8);
+        assert!(!schedule.is_active(0, 0, 1, 21.9));
+        assert!(schedule.is_active(0, 0, 1, 22.0));
+        assert!(schedule.is_active(0, 0, 1, 0.0));
+        assert!(schedule.is_active(0, 0, 1, 5.9));
+        assert!(!schedule.is_active(0, 0, 1, 6.0));
+    }
+
+    #[test]
+    fn test_zero_duration() {
+        let schedule = schedule(18, 0);
+        assert!(!schedule.is_active(0, 0, 1, 17.9));
+        assert!(schedule.is_active(0, 0, 1, 18.0));
+        assert!(schedule.is_active(0, 0, 1, 23.9));
+        assert!(!schedule.is_active(0, 0, 1, 0.0));
+    }
+
+    #[test]
+    fn test_date_filters() {
+        let schedule = PackageSchedule {
+            month: 3,
+            day_of_week: DayOfWeek::Weekends,
+            date: 12,
+            time: -1,
+            duration: 0,
+        };
+        assert!(schedule.is_active(3, 6, 12, 10.0));
+        assert!(!schedule.is_active(4, 6, 12, 10.0));
+        assert!(!schedule.is_active(3, 3, 12, 10.0));
+        assert!(!schedule.is_active(3, 6, 13, 10.0));
+
+        assert!(DayOfWeek::TuesdayThursday.contains(4));
+        assert!(!DayOfWeek::Weekdays.contains(0));
+        assert!(DayOfWeek::Friday.contains(5));
     }
 }
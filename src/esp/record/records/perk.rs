@@ -1,13 +1,551 @@
-use super::prelude::*;
+use super::{
+    lvli::LVLI,
+    prelude::{actor_values::ActorValue, condition::CTDA, script::Script, *},
+    qust::QUST,
+    spel::SPEL,
+};
+use nom::combinator::opt;
 
 /// Perk
 #[derive(Debug)]
-pub struct PERK {}
+pub struct PERK {
+    pub editor_id: EditorId,
+    pub name: Option<String>,
+    pub description: String,
+    pub large_icon_file_name: Option<String>,
+    pub small_icon_file_name: Option<String>,
+    /// Conditions the player must meet to take the perk
+    pub conditions: Vec<CTDA>,
+    pub data: Option<PerkData>,
+    pub effects: Vec<PerkEffect>,
+}
 
 impl Record for PERK {
     const TYPE: RecordType = RecordType::new(b"PERK");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let name: Option<String> = parser.try_parse(FULL)?;
+        let description: String = parser.parse(DESC)?;
+        let large_icon_file_name: Option<String> = parser.try_parse(ICON)?;
+        let small_icon_file_name: Option<String> = parser.try_parse(MICO)?;
+        let conditions: Vec<CTDA> = parser.try_parse_many(CTDA)?;
+        let data: Option<PerkData> = parser.try_parse(DATA)?;
+        let effects: Vec<PerkEffect> = parser.parse_collection()?;
+
+        Ok(Self {
+            editor_id,
+            name,
+            description,
+            large_icon_file_name,
+            small_icon_file_name,
+            conditions,
+            data,
+            effects,
+        })
+    }
+}
+
+impl PERK {
+    /// Finds the entry point effects for the provided entry point that apply
+    /// to a perk at `rank` (zero based), effects of lower ranks are included
+    /// as they stack. The effects are ordered by their priority
+    pub fn entry_point_effects(
+        &self,
+        entry_point: EntryPoint,
+        rank: u8,
+    ) -> Vec<(&PerkEffect, &PerkEntryPointEffect)> {
+        let mut effects: Vec<(&PerkEffect, &PerkEntryPointEffect)> = self
+            .effects
+            .iter()
+            .filter(|effect| effect.rank <= rank)
+            .filter_map(|effect| match &effect.kind {
+                PerkEffectKind::EntryPoint(value) if value.entry_point == entry_point => {
+                    Some((effect, value))
+                }
+                _ => None,
+            })
+            .collect();
+
+        effects.sort_by_key(|(effect, _)| effect.priority);
+        effects
+    }
+}
+
+#[derive(Debug)]
+pub struct PerkData {
+    pub is_trait: bool,
+    pub min_level: u8,
+    pub ranks: u8,
+    pub playable: bool,
+    pub hidden: bool,
+}
+
+impl FromRecordBytes for PerkData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((bool::parse, u8, u8, bool::parse, opt(bool::parse))),
+            |(is_trait, min_level, ranks, playable, hidden)| Self {
+                is_trait,
+                min_level,
+                ranks,
+                playable,
+                // Older records don't include the hidden flag
+                hidden: hidden.unwrap_or_default(),
+            },
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct PerkEffect {
+    /// Zero based rank of the perk the effect applies at
+    pub rank: u8,
+    pub priority: u8,
+    pub kind: PerkEffectKind,
+}
+
+#[derive(Debug)]
+pub enum PerkEffectKind {
+    /// Sets the stage of a quest when the perk is added
+    QuestStage {
+        quest: TypedFormId<QUST>,
+        stage: u8,
+    },
+    /// Ability applied to the player while they have the perk
+    Ability(TypedFormId<SPEL>),
+    EntryPoint(PerkEntryPointEffect),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum PerkEffectType {
+    QuestStage = 0,
+    Ability = 1,
+    EntryPoint = 2,
+}
+
+impl RecordCollection for PerkEffect {
+    fn parse_next<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        let header: PerkEffectHeader = match parser.try_parse(PRKE)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let kind: PerkEffectKind = match header.ty {
+            PerkEffectType::QuestStage => {
+                let data: QuestStageData = parser.parse(DATA)?;
+                PerkEffectKind::QuestStage {
+                    quest: data.quest,
+                    stage: data.stage,
+                }
+            }
+            PerkEffectType::Ability => PerkEffectKind::Ability(parser.parse(DATA)?),
+            PerkEffectType::EntryPoint => {
+                PerkEffectKind::EntryPoint(PerkEntryPointEffect::parse(parser)?)
+            }
+        };
+
+        // Quest stage and ability effects still include the entry point
+        // function fields, they're empty and can be discarded
+        if !matches!(kind, PerkEffectKind::EntryPoint(_)) {
+            parser.skip_while_type(PRKC);
+            parser.skip_while_type(CTDA);
+            parser.skip_type(EPFT);
+            parser.skip_type(EPFD);
+        }
+
+        parser.require_type(PRKF)?;
+
+        Ok(Some(Self {
+            rank: header.rank,
+            priority: header.priority,
+            kind,
+        }))
+    }
+}
+
+struct PerkEffectHeader {
+    ty: PerkEffectType,
+    rank: u8,
+    priority: u8,
+}
+
+impl FromRecordBytes for PerkEffectHeader {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((enum_value::<PerkEffectType>, u8, u8)),
+            |(ty, rank, priority)| Self { ty, rank, priority },
+        )(input)
+    }
+}
+
+struct QuestStageData {
+    quest: TypedFormId<QUST>,
+    stage: u8,
+}
+
+impl FromRecordBytes for QuestStageData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((TypedFormId::parse, u8, rest)),
+            |(quest, stage, _)| Self { quest, stage },
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct PerkEntryPointEffect {
+    pub entry_point: EntryPoint,
+    pub function: EntryPointFunction,
+    /// Number of condition tabs available for the entry point
+    pub condition_tab_count: u8,
+    pub conditions: Vec<PerkConditions>,
+    pub parameters: EntryPointParameters,
+}
+
+impl PerkEntryPointEffect {
+    fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
+        let data: EntryPointData = parser.parse(DATA)?;
+        let conditions: Vec<PerkConditions> = parser.parse_collection()?;
+        let parameter_type: Option<EntryPointParameterType> = parser.try_parse(EPFT)?;
+        let parameters: EntryPointParameters = match parameter_type {
+            None => EntryPointParameters::None,
+            Some(EntryPointParameterType::None) => {
+                parser.skip_type(EPFD);
+                EntryPointParameters::None
+            }
+            Some(EntryPointParameterType::Float) => {
+                EntryPointParameters::Float(parser.parse(EPFD)?)
+            }
+            Some(EntryPointParameterType::FloatFloat) => {
+                let values: FloatPair = parser.parse(EPFD)?;
+                EntryPointParameters::FloatFloat(values.0, values.1)
+            }
+            Some(EntryPointParameterType::LeveledItem) => {
+                EntryPointParameters::LeveledItem(parser.parse(EPFD)?)
+            }
+            Some(EntryPointParameterType::Script) => {
+                let button_label: Option<String> = parser.try_parse(EPF2)?;
+                let script_flags: EntryPointScriptFlags = parser
+                    .try_parse(EPF3)?
+                    .unwrap_or_else(EntryPointScriptFlags::empty);
+                let embedded_script: Option<Script> = Script::parse_next(parser)?;
+                parser.skip_type(EPFD);
+                EntryPointParameters::Script {
+                    button_label,
+                    script_flags,
+                    embedded_script,
+                }
+            }
+        };
+
+        Ok(Self {
+            entry_point: data.entry_point,
+            function: data.function,
+            condition_tab_count: data.condition_tab_count,
+            conditions,
+            parameters,
+        })
+    }
+
+    /// Applies the entry point function to `value` returning the new value,
+    /// functions that don't modify a value return it unchanged
+    ///
+    /// * `roll` - Random value in the range 0..1 used by [`EntryPointFunction::AddRangeToValue`]
+    /// * `actor_value` - Provides the current value of an actor value of the perk owner
+    pub fn apply<A>(&self, value: f32, roll: f32, actor_value: A) -> f32
+    where
+        A: Fn(ActorValue) -> f32,
+    {
+        let (first, second) = match self.parameters {
+            EntryPointParameters::Float(first) => (first, 0.0),
+            EntryPointParameters::FloatFloat(first, second) => (first, second),
+            _ => (0.0, 0.0),
+        };
+
+        match self.function {
+            EntryPointFunction::SetValue => first,
+            EntryPointFunction::AddValue => value + first,
+            EntryPointFunction::MultiplyValue => value * first,
+            EntryPointFunction::AddRangeToValue => value + first + (second - first) * roll,
+            EntryPointFunction::AddActorValueMult => ActorValue::try_from(first as i8)
+                .map(|actor_value_type| value + actor_value(actor_value_type) * second)
+                .unwrap_or(value),
+            EntryPointFunction::AbsoluteValue => value.abs(),
+            EntryPointFunction::NegativeAbsoluteValue => -value.abs(),
+            EntryPointFunction::AddLeveledList | EntryPointFunction::AddActivateChoice => value,
+        }
+    }
+}
+
+struct EntryPointData {
+    entry_point: EntryPoint,
+    function: EntryPointFunction,
+    condition_tab_count: u8,
+}
+
+impl FromRecordBytes for EntryPointData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                enum_value::<EntryPoint>,
+                enum_value::<EntryPointFunction>,
+                u8,
+            )),
+            |(entry_point, function, condition_tab_count)| Self {
+                entry_point,
+                function,
+                condition_tab_count,
+            },
+        )(input)
+    }
+}
+
+/// Conditions for a single condition tab of an entry point
+#[derive(Debug)]
+pub struct PerkConditions {
+    /// Index of the condition tab, what the conditions are run on
+    /// depends on the entry point (e.g. Perk Owner, Weapon, Target)
+    pub run_on: i8,
+    pub conditions: Vec<CTDA>,
+}
+
+impl RecordCollection for PerkConditions {
+    fn parse_next<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        let run_on: i8 = match parser.try_parse(PRKC)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let conditions: Vec<CTDA> = parser.try_parse_many(CTDA)?;
+        Ok(Some(Self { run_on, conditions }))
+    }
+}
+
+#[derive(Debug)]
+pub enum EntryPointParameters {
+    None,
+    Float(f32),
+    FloatFloat(f32, f32),
+    LeveledItem(TypedFormId<LVLI>),
+    Script {
+        button_label: Option<String>,
+        script_flags: EntryPointScriptFlags,
+        embedded_script: Option<Script>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum EntryPointParameterType {
+    None = 0,
+    Float = 1,
+    FloatFloat = 2,
+    LeveledItem = 3,
+    Script = 4,
+}
+
+impl FromRecordBytes for EntryPointParameterType {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        enum_value::<Self>(input)
+    }
+}
+
+struct FloatPair(f32, f32);
+
+impl FromRecordBytes for FloatPair {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(tuple((le_f32, le_f32)), |(first, second)| {
+            Self(first, second)
+        })(input)
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct EntryPointScriptFlags: u16 {
+        const RUN_IMMEDIATELY = 0x0001;
+    }
+}
+
+impl FromRecordBytes for EntryPointScriptFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u16, Self::from_bits_retain)(input)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum EntryPointFunction {
+    SetValue = 1,
+    AddValue = 2,
+    MultiplyValue = 3,
+    AddRangeToValue = 4,
+    AddActorValueMult = 5,
+    AbsoluteValue = 6,
+    NegativeAbsoluteValue = 7,
+    AddLeveledList = 8,
+    AddActivateChoice = 9,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum EntryPoint {
+    CalculateWeaponDamage = 0,
+    CalculateMyCriticalHitChance = 1,
+    CalculateMyCriticalHitDamage = 2,
+    CalculateWeaponAttackApCost = 3,
+    CalculateMineExplodeChance = 4,
+    AdjustRangePenalty = 5,
+    AdjustLimbDamage = 6,
+    CalculateWeaponRange = 7,
+    CalculateToHitChance = 8,
+    AdjustExperiencePoints = 9,
+    AdjustGainedSkillPoints = 10,
+    AdjustBookSkillPoints = 11,
+    ModifyRecoveredHealth = 12,
+    CalculateInventoryApCost = 13,
+    GetDisposition = 14,
+    GetShouldAttack = 15,
+    GetShouldAssist = 16,
+    CalculateBuyPrice = 17,
+    GetBadKarma = 18,
+    GetGoodKarma = 19,
+    IgnoreLockedTerminal = 20,
+    AddLeveledListOnDeath = 21,
+    GetMaxCarryWeight = 22,
+    ModifyAddictionChance = 23,
+    ModifyAddictionDuration = 24,
+    ModifyPositiveChemDuration = 25,
+    AdjustDrinkingRadiation = 26,
+    Activate = 27,
+    MysteriousStranger = 28,
+    HasParalyzingPalm = 29,
+    HackingScienceBonus = 30,
+    IgnoreRunningDuringDetection = 31,
+    IgnoreBrokenLock = 32,
+    HasConcentratedFire = 33,
+    CalculateGunSpread = 34,
+    PlayerKillApReward = 35,
+    ModifyEnemyCriticalHitChance = 36,
+    ReloadSpeed = 37,
+    EquipSpeed = 38,
+    ActionPointRegen = 39,
+    ActionPointCost = 40,
+    MissFortune = 41,
+    ModifyRunSpeed = 42,
+    ModifyAttackSpeed = 43,
+    ModifyRadiationConsumed = 44,
+    HasPipHacker = 45,
+    HasMeltdown = 46,
+    SeeEnemyHealth = 47,
+    HasJuryRigging = 48,
+    ModifyThreatRange = 49,
+    ModifyThread = 50,
+    HasFastTravelAlways = 51,
+    KnockdownChance = 52,
+    ModifyWeaponStrengthReq = 53,
+    ModifyAimingMoveSpeed = 54,
+    ModifyLightItems = 55,
+    ModifyDamageThresholdDefender = 56,
+    ModifyChanceForAmmoItem = 57,
+    ModifyDamageThresholdAttacker = 58,
+    ModifyThrowingVelocity = 59,
+    ChanceForItemOnFire = 60,
+    HasUnarmedForwardPowerAttack = 61,
+    HasUnarmedBackPowerAttack = 62,
+    HasUnarmedCrouchedPowerAttack = 63,
+    HasUnarmedCounterAttack = 64,
+    HasUnarmedLeftPowerAttack = 65,
+    HasUnarmedRightPowerAttack = 66,
+    VatsHelperChance = 67,
+    ModifyItemDamage = 68,
+    HasImprovedDetection = 69,
+    HasImprovedSpotting = 70,
+    HasImprovedItemDetection = 71,
+    AdjustExplosionRadius = 72,
+    U1 = 73,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EntryPoint, EntryPointFunction, EntryPointParameters, PerkEntryPointEffect};
+    use crate::esp::record::sub::actor_values::ActorValue;
+
+    fn effect(
+        function: EntryPointFunction,
+        parameters: EntryPointParameters,
+    ) -> PerkEntryPointEffect {
+        PerkEntryPointEffect {
+            entry_point: EntryPoint::CalculateWeaponDamage,
+            function,
+            condition_tab_count: 0,
+            conditions: Vec::new(),
+            parameters,
+        }
+    }
+
+    fn no_actor_value(_: ActorValue) -> f32 {
+        panic!("actor value should not be requested")
+    }
+
+    #[test]
+    fn test_apply() {
+        let set = effect(
+            EntryPointFunction::SetValue,
+            EntryPointParameters::Float(3.0),
+        );
+        assert_eq!(set.apply(10.0, 0.5, no_actor_value), 3.0);
+
+        let add = effect(
+            EntryPointFunction::AddValue,
+            EntryPointParameters::Float(3.0),
+        );
+        assert_eq!(add.apply(10.0, 0.5, no_actor_value), 13.0);
+
+        let multiply = effect(
+            EntryPointFunction::MultiplyValue,
+            EntryPointParameters::Float(1.5),
+        );
+        assert_eq!(multiply.apply(10.0, 0.5, no_actor_value), 15.0);
+
+        // Functions without a parameter treat it as zero
+        let add = effect(EntryPointFunction::AddValue, EntryPointParameters::None);
+        assert_eq!(add.apply(10.0, 0.5, no_actor_value), 10.0);
+
+        let absolute = effect(
+            EntryPointFunction::NegativeAbsoluteValue,
+            EntryPointParameters::None,
+        );
+        assert_eq!(absolute.apply(4.0, 0.5, no_actor_value), -4.0);
+    }
+
+    /// The roll picks a value between the two parameters
+    #[test]
+    fn test_apply_range() {
+        let range = effect(
+            EntryPointFunction::AddRangeToValue,
+            EntryPointParameters::FloatFloat(2.0, 6.0),
+        );
+        assert_eq!(range.apply(10.0, 0.0, no_actor_value), 12.0);
+        assert_eq!(range.apply(10.0, 0.5, no_actor_value), 14.0);
+        assert_eq!(range.apply(10.0, 1.0, no_actor_value), 16.0);
+    }
+
+    #[test]
+    fn test_apply_actor_value() {
+        let effect = effect(
+            EntryPointFunction::AddActorValueMult,
+            EntryPointParameters::FloatFloat(ActorValue::Strength as i8 as f32, 0.5),
+        );
+        let actor_value = |actor_value: ActorValue| {
+            assert_eq!(actor_value, ActorValue::Strength);
+            8.0
+        };
+        assert_eq!(effect.apply(10.0, 0.5, actor_value), 14.0);
     }
 }
@@ -1,13 +1,13 @@
-use super::prelude::*;
+use super::{pgre::PlacedProjectile, prelude::*};
 
 /// Placed Missile
 #[derive(Debug)]
-pub struct PMIS {}
+pub struct PMIS(pub PlacedProjectile);
 
 impl Record for PMIS {
     const TYPE: RecordType = RecordType::new(b"PMIS");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        PlacedProjectile::parse(parser).map(Self)
     }
 }
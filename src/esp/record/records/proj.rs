@@ -1,13 +1,157 @@
-use super::prelude::*;
+use super::{expl::EXPL, ligh::LIGH, prelude::*, soun::SOUN, weap::WEAP};
+use crate::esp::record::sub::{
+    destruction::DestructionData, model::ModelData, object_bounds::ObjectBounds,
+    sound_level::SoundLevel,
+};
+use nom::combinator::opt;
 
 /// Projectile
 #[derive(Debug)]
-pub struct PROJ {}
+pub struct PROJ {
+    pub editor_id: EditorId,
+    pub object_bounds: ObjectBounds,
+    pub name: Option<String>,
+    pub model_data: Option<ModelData>,
+    pub destruction_data: Option<DestructionData>,
+    pub data: ProjectileData,
+    pub muzzle_flash_model_file_name: Option<String>,
+    pub sound_level: SoundLevel,
+}
 
 impl Record for PROJ {
     const TYPE: RecordType = RecordType::new(b"PROJ");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let object_bounds: ObjectBounds = parser.parse(OBND)?;
+        let name: Option<String> = parser.try_parse(FULL)?;
+        let model_data: Option<ModelData> = ModelData::parse_first(parser)?;
+        let destruction_data: Option<DestructionData> = DestructionData::parse_next(parser)?;
+        let data: ProjectileData = parser.parse(DATA)?;
+        let muzzle_flash_model_file_name: Option<String> = parser.try_parse(NAM1)?;
+        // Muzzle flash model texture hashes
+        parser.skip_type(NAM2);
+        let sound_level: SoundLevel = parser.parse(VNAM)?;
+
+        Ok(Self {
+            editor_id,
+            object_bounds,
+            name,
+            model_data,
+            destruction_data,
+            data,
+            muzzle_flash_model_file_name,
+            sound_level,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ProjectileData {
+    pub flags: ProjectileFlags,
+    pub ty: ProjectileType,
+    pub gravity: f32,
+    pub speed: f32,
+    pub range: f32,
+    pub light: NTypedFormId<LIGH>,
+    pub muzzle_flash_light: NTypedFormId<LIGH>,
+    pub tracer_chance: f32,
+    pub explosion_alt_trigger_proximity: f32,
+    pub explosion_alt_trigger_timer: f32,
+    pub explosion: NTypedFormId<EXPL>,
+    pub sound: NTypedFormId<SOUN>,
+    pub muzzle_flash_duration: f32,
+    pub fade_duration: f32,
+    pub impact_force: f32,
+    pub sound_countdown: NTypedFormId<SOUN>,
+    pub sound_disable: NTypedFormId<SOUN>,
+    pub default_weapon_source: NTypedFormId<WEAP>,
+    pub rotation: Vector3<f32>,
+    /// Only present in records from Fallout: New Vegas
+    pub bouncy_mult: Option<f32>,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct ProjectileFlags: u16 {
+        const HITSCAN                          = 0x0001;
+        const EXPLOSION                        = 0x0002;
+        const ALT_TRIGGER                      = 0x0004;
+        const MUZZLE_FLASH                     = 0x0008;
+        const U1                               = 0x0010;
+        const CAN_BE_DISABLED                  = 0x0020;
+        const CAN_BE_PICKED_UP                 = 0x0040;
+        const SUPERSONIC                       = 0x0080;
+        const PINS_LIMBS                       = 0x0100;
+        const PASS_THROUGH_SMALL_TRANSPARENT   = 0x0200;
+        const DETONATES                        = 0x0400;
+        const ROTATION                         = 0x0800;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u16)]
+pub enum ProjectileType {
+    Missile = 0x01,
+    Lobber = 0x02,
+    Beam = 0x04,
+    Flame = 0x08,
+    ContinuousBeam = 0x10,
+}
+
+impl FromRecordBytes for ProjectileData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, flags) = ProjectileFlags::parse(input)?;
+        let (input, ty) = enum_value::<ProjectileType>(input)?;
+        let (input, gravity) = le_f32(input)?;
+        let (input, speed) = le_f32(input)?;
+        let (input, range) = le_f32(input)?;
+        let (input, light) = NTypedFormId::parse(input)?;
+        let (input, muzzle_flash_light) = NTypedFormId::parse(input)?;
+        let (input, tracer_chance) = le_f32(input)?;
+        let (input, explosion_alt_trigger_proximity) = le_f32(input)?;
+        let (input, explosion_alt_trigger_timer) = le_f32(input)?;
+        let (input, explosion) = NTypedFormId::parse(input)?;
+        let (input, sound) = NTypedFormId::parse(input)?;
+        let (input, muzzle_flash_duration) = le_f32(input)?;
+        let (input, fade_duration) = le_f32(input)?;
+        let (input, impact_force) = le_f32(input)?;
+        let (input, sound_countdown) = NTypedFormId::parse(input)?;
+        let (input, sound_disable) = NTypedFormId::parse(input)?;
+        let (input, default_weapon_source) = NTypedFormId::parse(input)?;
+        let (input, rotation) = Vector3::parse(input)?;
+        let (input, bouncy_mult) = opt(le_f32)(input)?;
+
+        Ok((
+            input,
+            Self {
+                flags,
+                ty,
+                gravity,
+                speed,
+                range,
+                light,
+                muzzle_flash_light,
+                tracer_chance,
+                explosion_alt_trigger_proximity,
+                explosion_alt_trigger_timer,
+                explosion,
+                sound,
+                muzzle_flash_duration,
+                fade_duration,
+                impact_force,
+                sound_countdown,
+                sound_disable,
+                default_weapon_source,
+                rotation,
+                bouncy_mult,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for ProjectileFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(le_u16, Self::from_bits_retain)(input)
     }
 }
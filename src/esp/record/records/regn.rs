@@ -1,13 +1,462 @@
-use super::prelude::*;
+use super::{
+    glob::GLOB, gras::GRAS, mset::MSET, musc::MUSC, prelude::*, soun::SOUN, wrld::WRLD, wthr::WTHR,
+};
 
 /// Region
 #[derive(Debug)]
-pub struct REGN {}
+pub struct REGN {
+    pub editor_id: EditorId,
+    pub large_icon_file_name: Option<String>,
+    pub small_icon_file_name: Option<String>,
+    pub map_color: RGBA,
+    pub worldspace: Option<TypedFormId<WRLD>>,
+    pub areas: Vec<RegionArea>,
+    pub entries: Vec<RegionDataEntry>,
+}
 
 impl Record for REGN {
     const TYPE: RecordType = RecordType::new(b"REGN");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let large_icon_file_name: Option<String> = parser.try_parse(ICON)?;
+        let small_icon_file_name: Option<String> = parser.try_parse(MICO)?;
+        let map_color: RGBA = parser.parse(RCLR)?;
+        let worldspace: Option<TypedFormId<WRLD>> = parser.try_parse(WNAM)?;
+        let areas: Vec<RegionArea> = parser.parse_collection()?;
+        let entries: Vec<RegionDataEntry> = parser.parse_collection()?;
+
+        Ok(Self {
+            editor_id,
+            large_icon_file_name,
+            small_icon_file_name,
+            map_color,
+            worldspace,
+            areas,
+            entries,
+        })
+    }
+}
+
+impl REGN {
+    /// Checks if the provided worldspace coordinates are within
+    /// any of the areas of the region
+    pub fn contains(&self, point: Vector2<f32>) -> bool {
+        self.areas.iter().any(|area| area.contains(point))
+    }
+
+    /// Finds the regions of the worldspace that cover the provided
+    /// worldspace coordinates
+    pub fn covering<'a, I>(
+        regions: I,
+        worldspace: &'a FormId,
+        point: Vector2<f32>,
+    ) -> impl Iterator<Item = &'a REGN>
+    where
+        I: IntoIterator<Item = &'a REGN>,
+    {
+        regions.into_iter().filter(move |region| {
+            let in_worldspace = match &region.worldspace {
+                Some(value) => value.id == *worldspace,
+                None => false,
+            };
+            in_worldspace && region.contains(point)
+        })
+    }
+
+    /// Finds the data entry of the provided type
+    pub fn entry(&self, ty: RegionDataType) -> Option<&RegionDataEntry> {
+        self.entries.iter().find(|entry| entry.header.ty == ty)
+    }
+}
+
+/// Polygon covering part of the region
+#[derive(Debug)]
+pub struct RegionArea {
+    pub edge_fall_off: u32,
+    /// Points of the polygon in worldspace coordinates
+    pub points: Vec<Vector2<f32>>,
+}
+
+impl RegionArea {
+    /// Checks if the point lies within the polygon of the area
+    /// using the even-odd rule
+    pub fn contains(&self, point: Vector2<f32>) -> bool {
+        let points = &self.points;
+        if points.len() < 3 {
+            return false;
+        }
+
+        let mut inside = false;
+        let mut previous = &points[points.len() - 1];
+
+        for current in points {
+            // Edge crosses the horizontal line through the point
+            if (current.y > point.y) != (previous.y > point.y) {
+                let intersect_x = current.x
+                    + (point.y - current.y) * (previous.x - current.x) / (previous.y - current.y);
+
+                if point.x < intersect_x {
+                    inside = !inside;
+                }
+            }
+
+            previous = current;
+        }
+
+        inside
+    }
+}
+
+impl RecordCollection for RegionArea {
+    fn parse_next<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        let edge_fall_off: u32 = match parser.try_parse(RPLI)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let points: Vec<Vector2<f32>> = parser
+            .try_parse::<Repeated<Vector2<f32>>>(RPLD)?
+            .map(|value| value.into_inner())
+            .unwrap_or_default();
+
+        Ok(Some(Self {
+            edge_fall_off,
+            points,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct RegionDataEntry {
+    pub header: RegionDataHeader,
+    pub data: RegionData,
+}
+
+#[derive(Debug)]
+pub struct RegionDataHeader {
+    pub ty: RegionDataType,
+    pub flags: RegionDataFlags,
+    /// Priority of the entry when multiple regions provide the same type
+    pub priority: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum RegionDataType {
+    Objects = 2,
+    Weather = 3,
+    Map = 4,
+    Land = 5,
+    Grass = 6,
+    Sound = 7,
+    Imposter = 8,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct RegionDataFlags: u8 {
+        /// Overrides the data from regions with a lower priority
+        const OVERRIDE = 0x01;
+    }
+}
+
+impl FromRecordBytes for RegionDataHeader {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                enum_value::<RegionDataType>,
+                RegionDataFlags::parse,
+                u8,
+                rest,
+            )),
+            |(ty, flags, priority, _)| Self {
+                ty,
+                flags,
+                priority,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for RegionDataFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
+    }
+}
+
+#[derive(Debug)]
+pub enum RegionData {
+    Objects(Vec<RegionObject>),
+    Weather(Vec<RegionWeather>),
+    Map {
+        name: Option<String>,
+    },
+    Land,
+    Grass(Vec<TypedFormId<GRAS>>),
+    Sound(RegionSounds),
+    /// FormIDs of the REFR records used as imposters
+    Imposter(Vec<FormId>),
+}
+
+impl RecordCollection for RegionDataEntry {
+    fn parse_next<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        let header: RegionDataHeader = match parser.try_parse(RDAT)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let data: RegionData = match header.ty {
+            RegionDataType::Objects => RegionData::Objects(parse_repeated(parser, RDOT)?),
+            RegionDataType::Weather => RegionData::Weather(parse_repeated(parser, RDWT)?),
+            RegionDataType::Map => RegionData::Map {
+                name: parser.try_parse(RDMP)?,
+            },
+            RegionDataType::Land => RegionData::Land,
+            RegionDataType::Grass => RegionData::Grass(
+                parse_repeated::<RegionGrass>(parser, RDGS)?
+                    .into_iter()
+                    .map(|value| value.grass)
+                    .collect(),
+            ),
+            RegionDataType::Sound => RegionData::Sound(RegionSounds::parse(parser)?),
+            RegionDataType::Imposter => RegionData::Imposter(parse_repeated(parser, RDID)?),
+        };
+
+        Ok(Some(Self { header, data }))
+    }
+}
+
+/// Parses an optional sub record containing an array of values
+fn parse_repeated<'b, T: FromRecordBytes>(
+    parser: &mut RecordParser<'_, 'b>,
+    ty: RecordType,
+) -> Result<Vec<T>, RecordParseError<'b>> {
+    Ok(parser
+        .try_parse::<Repeated<T>>(ty)?
+        .map(|value| value.into_inner())
+        .unwrap_or_default())
+}
+
+/// Object placed randomly throughout the region
+#[derive(Debug)]
+pub struct RegionObject {
+    /// FormID of a TREE, STAT or LTEX record
+    pub object: FormId,
+    pub parent_index: u16,
+    pub density: f32,
+    pub clustering: u8,
+    pub min_slope: u8,
+    pub max_slope: u8,
+    pub flags: RegionObjectFlags,
+    pub radius_wrt_parent: u16,
+    pub radius: u16,
+    pub min_height: f32,
+    pub max_height: f32,
+    pub sink: f32,
+    pub sink_variance: f32,
+    pub size_variance: f32,
+    pub angle_variance: Vector3<u16>,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct RegionObjectFlags: u8 {
+        const CONFORM_TO_SLOPE    = 0x01;
+        const PAINT_VERTICES      = 0x02;
+        const SIZE_VARIANCE       = 0x04;
+        const X_VARIANCE          = 0x08;
+        const Y_VARIANCE          = 0x10;
+        const Z_VARIANCE          = 0x20;
+        const TREE                = 0x40;
+        const HUGE_ROCK           = 0x80;
+    }
+}
+
+impl FromRecordBytes for RegionObject {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, object) = FormId::parse(input)?;
+        let (input, parent_index) = le_u16(input)?;
+        let (input, _unused) = take(2usize)(input)?;
+        let (input, density) = le_f32(input)?;
+        let (input, clustering) = u8(input)?;
+        let (input, min_slope) = u8(input)?;
+        let (input, max_slope) = u8(input)?;
+        let (input, flags) = RegionObjectFlags::parse(input)?;
+        let (input, radius_wrt_parent) = le_u16(input)?;
+        let (input, radius) = le_u16(input)?;
+        let (input, min_height) = le_f32(input)?;
+        let (input, max_height) = le_f32(input)?;
+        let (input, sink) = le_f32(input)?;
+        let (input, sink_variance) = le_f32(input)?;
+        let (input, size_variance) = le_f32(input)?;
+        let (input, angle_variance) = Vector3::parse(input)?;
+        let (input, _unused) = take(2usize)(input)?;
+        let (input, _unknown) = take(4usize)(input)?;
+
+        Ok((
+            input,
+            Self {
+                object,
+                parent_index,
+                density,
+                clustering,
+                min_slope,
+                max_slope,
+                flags,
+                radius_wrt_parent,
+                radius,
+                min_height,
+                max_height,
+                sink,
+                sink_variance,
+                size_variance,
+                angle_variance,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for RegionObjectFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct RegionWeather {
+    pub weather: TypedFormId<WTHR>,
+    pub chance: u32,
+    /// Global variable that overrides the chance when present
+    pub global: NTypedFormId<GLOB>,
+}
+
+impl FromRecordBytes for RegionWeather {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((TypedFormId::parse, le_u32, NTypedFormId::parse)),
+            |(weather, chance, global)| Self {
+                weather,
+                chance,
+                global,
+            },
+        )(input)
+    }
+}
+
+struct RegionGrass {
+    grass: TypedFormId<GRAS>,
+}
+
+impl FromRecordBytes for RegionGrass {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(tuple((TypedFormId::parse, take(4usize))), |(grass, _)| {
+            Self { grass }
+        })(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct RegionSounds {
+    pub music_type: Option<RegionMusicType>,
+    pub music: Option<TypedFormId<MUSC>>,
+    pub incidental_media_set: Option<TypedFormId<MSET>>,
+    pub battle_media_sets: Vec<TypedFormId<MSET>>,
+    pub sounds: Vec<RegionSound>,
+}
+
+impl RegionSounds {
+    fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
+        let music_type: Option<RegionMusicType> = parser.try_parse(RDMD)?;
+        let music: Option<TypedFormId<MUSC>> = parser.try_parse(RDMO)?;
+        let incidental_media_set: Option<TypedFormId<MSET>> = parser.try_parse(RDSI)?;
+        let battle_media_sets: Vec<TypedFormId<MSET>> = parser.try_parse_many(RDSB)?;
+        let sounds: Vec<RegionSound> = parse_repeated(parser, RDSD)?;
+
+        Ok(Self {
+            music_type,
+            music,
+            incidental_media_set,
+            battle_media_sets,
+            sounds,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum RegionMusicType {
+    Default = 0,
+    Public = 1,
+    Dungeon = 2,
+}
+
+impl FromRecordBytes for RegionMusicType {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        enum_value(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct RegionSound {
+    pub sound: TypedFormId<SOUN>,
+    pub flags: RegionSoundFlags,
+    pub chance: u32,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct RegionSoundFlags: u32 {
+        const PLEASANT = 0x00000001;
+        const CLOUDY   = 0x00000002;
+        const RAINY    = 0x00000004;
+        const SNOWY    = 0x00000008;
+    }
+}
+
+impl FromRecordBytes for RegionSound {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((TypedFormId::parse, le_u32, le_u32)),
+            |(sound, flags, chance)| Self {
+                sound,
+                flags: RegionSoundFlags::from_bits_retain(flags),
+                chance,
+            },
+        )(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RegionArea;
+    use nalgebra::Vector2;
+
+    /// Points inside a concave polygon should be contained while
+    /// points in its notch or outside should not
+    #[test]
+    fn test_area_contains() {
+        // U shaped polygon with a notch between x 1..2 above y 1
+        let area = RegionArea {
+            edge_fall_off: 0,
+            points: vec![
+                Vector2::new(0.0, 0.0),
+                Vector2::new(3.0, 0.0),
+                Vector2::new(3.0, 3.0),
+                Vector2::new(2.0, 3.0),
+                Vector2::new(2.0, 1.0),
+                Vector2::new(1.0, 1.0),
+                Vector2::new(1.0, 3.0),
+                Vector2::new(0.0, 3.0),
+            ],
+        };
+
+        assert!(area.contains(Vector2::new(0.5, 2.0)));
+        assert!(area.contains(Vector2::new(1.5, 0.5)));
+        assert!(!area.contains(Vector2::new(1.5, 2.0)));
+        assert!(!area.contains(Vector2::new(4.0, 1.0)));
     }
 }
@@ -1,13 +1,214 @@
-use super::prelude::*;
+use super::{bptd::BPTD, prelude::*};
 
 /// Ragdoll
 #[derive(Debug)]
-pub struct RGDL {}
+pub struct RGDL {
+    pub editor_id: EditorId,
+    pub version: u32,
+    pub data: RagdollData,
+    /// FormID of either a CREA or NPC_ record
+    pub actor_base: Option<FormId>,
+    pub body_part_data: Option<TypedFormId<BPTD>>,
+    pub feedback_data: Option<RagdollFeedbackData>,
+    /// Indices of the skeleton bones driven by feedback
+    pub feedback_dynamic_bones: Vec<u16>,
+    pub pose_matching_data: Option<RagdollPoseMatchingData>,
+    pub death_pose: Option<String>,
+}
 
 impl Record for RGDL {
     const TYPE: RecordType = RecordType::new(b"RGDL");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let version: u32 = parser.parse(NVER)?;
+        let data: RagdollData = parser.parse(DATA)?;
+        let actor_base: Option<FormId> = parser.try_parse(XNAM)?;
+        let body_part_data: Option<TypedFormId<BPTD>> = parser.try_parse(TNAM)?;
+        let feedback_data: Option<RagdollFeedbackData> = parser.try_parse(RAFD)?;
+        let feedback_dynamic_bones: Vec<u16> = parser
+            .try_parse::<Repeated<u16>>(RAFB)?
+            .map(|value| value.into_inner())
+            .unwrap_or_default();
+        let pose_matching_data: Option<RagdollPoseMatchingData> = parser.try_parse(RAPS)?;
+        let death_pose: Option<String> = parser.try_parse(ANAM)?;
+
+        Ok(Self {
+            editor_id,
+            version,
+            data,
+            actor_base,
+            body_part_data,
+            feedback_data,
+            feedback_dynamic_bones,
+            pose_matching_data,
+            death_pose,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct RagdollData {
+    pub dynamic_bone_count: u32,
+    pub feedback_enabled: bool,
+    pub foot_ik_enabled: bool,
+    pub look_ik_enabled: bool,
+    pub grab_transfer_enabled: bool,
+    pub pose_matching_enabled: bool,
+}
+
+impl FromRecordBytes for RagdollData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                le_u32,
+                take(4usize),
+                bool::parse,
+                bool::parse,
+                bool::parse,
+                bool::parse,
+                bool::parse,
+                rest,
+            )),
+            |(
+                dynamic_bone_count,
+                _,
+                feedback_enabled,
+                foot_ik_enabled,
+                look_ik_enabled,
+                grab_transfer_enabled,
+                pose_matching_enabled,
+                _,
+            )| Self {
+                dynamic_bone_count,
+                feedback_enabled,
+                foot_ik_enabled,
+                look_ik_enabled,
+                grab_transfer_enabled,
+                pose_matching_enabled,
+            },
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct RagdollFeedbackData {
+    /// Blend between the keyframed animation (0) and the dynamic simulation (1)
+    pub dynamic_keyframe_blend_amount: f32,
+    pub hierarchy_gain: f32,
+    pub position_gain: f32,
+    pub velocity_gain: f32,
+    pub acceleration_gain: f32,
+    pub snap_gain: f32,
+    pub velocity_damping: f32,
+    pub snap_max_linear_velocity: f32,
+    pub snap_max_angular_velocity: f32,
+    pub snap_max_linear_distance: f32,
+    pub snap_max_angular_distance: f32,
+    pub position_max_linear_velocity: f32,
+    pub position_max_angular_velocity: f32,
+    pub projectile_impulse: f32,
+    pub melee_impulse: f32,
+}
+
+impl FromRecordBytes for RagdollFeedbackData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, dynamic_keyframe_blend_amount) = le_f32(input)?;
+        let (input, hierarchy_gain) = le_f32(input)?;
+        let (input, position_gain) = le_f32(input)?;
+        let (input, velocity_gain) = le_f32(input)?;
+        let (input, acceleration_gain) = le_f32(input)?;
+        let (input, snap_gain) = le_f32(input)?;
+        let (input, velocity_damping) = le_f32(input)?;
+        let (input, snap_max_linear_velocity) = le_f32(input)?;
+        let (input, snap_max_angular_velocity) = le_f32(input)?;
+        let (input, snap_max_linear_distance) = le_f32(input)?;
+        let (input, snap_max_angular_distance) = le_f32(input)?;
+        let (input, position_max_linear_velocity) = le_f32(input)?;
+        let (input, position_max_angular_velocity) = le_f32(input)?;
+        // Impulses are stored as thousandths
+        let (input, projectile_impulse) = map(le_i32, |value| value as f32 / 1000.0)(input)?;
+        let (input, melee_impulse) = map(le_i32, |value| value as f32 / 1000.0)(input)?;
+
+        Ok((
+            input,
+            Self {
+                dynamic_keyframe_blend_amount,
+                hierarchy_gain,
+                position_gain,
+                velocity_gain,
+                acceleration_gain,
+                snap_gain,
+                velocity_damping,
+                snap_max_linear_velocity,
+                snap_max_angular_velocity,
+                snap_max_linear_distance,
+                snap_max_angular_distance,
+                position_max_linear_velocity,
+                position_max_angular_velocity,
+                projectile_impulse,
+                melee_impulse,
+            },
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct RagdollPoseMatchingData {
+    /// Indices of the skeleton bones used for matching the pose
+    pub match_bones: [u16; 3],
+    pub flags: PoseMatchingFlags,
+    pub motors_strength: f32,
+    pub pose_activation_delay_time: f32,
+    pub match_error_allowance: f32,
+    pub displacement_to_disable: f32,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct PoseMatchingFlags: u8 {
+        const DISABLE_ON_MOVE = 0x01;
+    }
+}
+
+impl FromRecordBytes for RagdollPoseMatchingData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                le_u16,
+                le_u16,
+                le_u16,
+                PoseMatchingFlags::parse,
+                u8,
+                le_f32,
+                le_f32,
+                le_f32,
+                le_f32,
+            )),
+            |(
+                bone_1,
+                bone_2,
+                bone_3,
+                flags,
+                _,
+                motors_strength,
+                pose_activation_delay_time,
+                match_error_allowance,
+                displacement_to_disable,
+            )| Self {
+                match_bones: [bone_1, bone_2, bone_3],
+                flags,
+                motors_strength,
+                pose_activation_delay_time,
+                match_error_allowance,
+                displacement_to_disable,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for PoseMatchingFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
     }
 }
@@ -98,7 +98,9 @@ pub struct MenuItem {
     pub item_text: Option<String>,
     pub result_text: String,
     pub flags: ANAMFlags,
+    /// Note displayed when the item is selected
     pub display_note: Option<TypedFormId<NOTE>>,
+    /// Terminal opened when the item is selected
     pub sub_menu: Option<TypedFormId<TERM>>,
     pub embedded_script: Script,
     pub conditions: Vec<CTDA>,
@@ -107,10 +109,9 @@ pub struct MenuItem {
 bitflags! {
     #[derive(Debug, Clone)]
     pub struct ANAMFlags: u8 {
-        const LEVELED = 0x01;
-        const UNLOCKED = 0x02;
-        const ALTERNATE_COLORS = 0x04;
-        const HIDE_WELCOME_TEXT_WHEN_DISPLAYING_TEXT =0x08;
+        /// The display note is added to the player's Pip-Boy
+        const ADD_NOTE = 0x01;
+        const FORCE_REDRAW = 0x02;
     }
 }
 
@@ -149,7 +150,7 @@ impl RecordCollection for MenuItem {
         };
         let flags: ANAMFlags = parser.parse(ANAM)?;
         let display_note: Option<TypedFormId<NOTE>> = parser.try_parse(INAM)?;
-        let sub_menu: Option<TypedFormId<TERM>> = parser.try_parse(INAM)?;
+        let sub_menu: Option<TypedFormId<TERM>> = parser.try_parse(TNAM)?;
         let embedded_script = Script::require_parse_next(parser)?;
         let conditions: Vec<CTDA> = parser.try_parse_many(CTDA)?;
         Ok(Some(Self {
@@ -169,3 +170,69 @@ impl FromRecordBytes for ANAMFlags {
         map(u8, Self::from_bits_retain)(input)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ANAMFlags, MenuItem};
+    use crate::esp::record::{
+        sub::{ANAM, INAM, ITXT, RNAM, SCDA, SCHR, SCTX, TERM, TNAM},
+        RawRecord, RecordFlags, RecordParser, RecordType,
+    };
+
+    fn subrecord(ty: RecordType, data: &[u8]) -> Vec<u8> {
+        [
+            ty.as_str().as_bytes(),
+            &(data.len() as u16).to_le_bytes(),
+            data,
+        ]
+        .concat()
+    }
+
+    /// Menu item sub-records with an empty embedded result script
+    fn menu_item(text: &str, flags: u8, link: (RecordType, u32)) -> Vec<u8> {
+        let script_header = [[0; 12].as_slice(), &[0, 0, 0, 0, 0, 0, 0, 0]].concat();
+        [
+            subrecord(ITXT, format!("{text}\0").as_bytes()),
+            subrecord(RNAM, b"\0"),
+            subrecord(ANAM, &[flags]),
+            subrecord(link.0, &link.1.to_le_bytes()),
+            subrecord(SCHR, &script_header),
+            subrecord(SCDA, &[]),
+            subrecord(SCTX, &[]),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_menu_items() {
+        let data = [
+            menu_item("Personal Log", 0x01, (INAM, 0x30)),
+            menu_item("Maintenance", 0x02, (TNAM, 0x40)),
+        ]
+        .concat();
+        let record = RawRecord {
+            ty: TERM,
+            flags: RecordFlags::empty(),
+            form_id: 0x20,
+            revision: 0,
+            version: 15,
+            data: &data,
+        };
+
+        let mut parser = RecordParser::new(&record).unwrap();
+        let items: Vec<MenuItem> = parser.parse_collection().unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].item_text.as_deref(), Some("Personal Log"));
+        assert!(items[0].flags.contains(ANAMFlags::ADD_NOTE));
+        assert_eq!(
+            items[0].display_note.as_ref().map(|note| note.id.0),
+            Some(0x30)
+        );
+        assert!(items[0].sub_menu.is_none());
+
+        assert!(items[1].flags.contains(ANAMFlags::FORCE_REDRAW));
+        assert!(items[1].display_note.is_none());
+        assert_eq!(items[1].sub_menu.as_ref().map(|term| term.id.0), Some(0x40));
+    }
+}
@@ -1,12 +1,271 @@
-use super::prelude::*;
+use super::{prelude::*, soun::SOUN, spel::SPEL};
 
+/// Water
 #[derive(Debug)]
-pub struct WATR {}
+pub struct WATR {
+    pub editor_id: EditorId,
+    pub name: Option<String>,
+    pub noise_texture: Option<String>,
+    /// Opacity of the water (0-100)
+    pub opacity: u8,
+    pub flags: WaterFlags,
+    pub material_id: Option<String>,
+    pub sound: Option<TypedFormId<SOUN>>,
+    pub actor_effect: Option<TypedFormId<SPEL>>,
+    /// Damage dealt while in the water when [`WaterFlags::CAUSES_DAMAGE`] is set
+    pub damage: Option<u16>,
+    pub visual_data: Option<WaterVisualData>,
+    pub related_waters: Option<RelatedWaters>,
+}
 
 impl Record for WATR {
     const TYPE: RecordType = RecordType::new(b"WATR");
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let name: Option<String> = parser.try_parse(FULL)?;
+        let noise_texture: Option<String> = parser.try_parse(NNAM)?;
+        let opacity: u8 = parser.parse(ANAM)?;
+        let flags: WaterFlags = parser.parse(FNAM)?;
+        let material_id: Option<String> = parser.try_parse(MNAM)?;
+        let sound: Option<TypedFormId<SOUN>> = parser.try_parse(SNAM)?;
+        let actor_effect: Option<TypedFormId<SPEL>> = parser.try_parse(XNAM)?;
+        let damage: Option<u16> = parser.try_parse(DATA)?;
+        let visual_data: Option<WaterVisualData> = parser.try_parse(DNAM)?;
+        let related_waters: Option<RelatedWaters> = parser.try_parse(GNAM)?;
+
+        Ok(Self {
+            editor_id,
+            name,
+            noise_texture,
+            opacity,
+            flags,
+            material_id,
+            sound,
+            actor_effect,
+            damage,
+            visual_data,
+            related_waters,
+        })
+    }
+}
+
+impl WATR {
+    /// Finds the variant of the water to use for the time of day or while
+    /// underwater, falling back to the water itself when not set
+    pub fn variant(&self, night: bool, underwater: bool) -> Option<&FormId> {
+        let related = self.related_waters.as_ref()?;
+        let variant = if underwater {
+            &related.underwater
+        } else if night {
+            &related.nighttime
+        } else {
+            &related.daytime
+        };
+
+        if variant.is_null() {
+            None
+        } else {
+            Some(&variant.id)
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct WaterFlags: u8 {
+        const CAUSES_DAMAGE = 0x01;
+        const REFLECTIVE    = 0x02;
+    }
+}
+
+impl FromRecordBytes for WaterFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct WaterVisualData {
+    pub sun_power: f32,
+    pub reflectivity_amount: f32,
+    pub fresnel_amount: f32,
+    pub above_water_fog: WaterFog,
+    pub shallow_color: RGBA,
+    pub deep_color: RGBA,
+    pub reflection_color: RGBA,
+    pub rain_simulator: WaterSimulator,
+    pub displacement_simulator: WaterSimulator,
+    pub noise_scale: f32,
+    pub noise_layers: [WaterNoiseLayer; 3],
+    pub depth_falloff_start: f32,
+    pub depth_falloff_end: f32,
+    pub normals_uv_scale: f32,
+    pub under_water_fog: WaterFog,
+    pub distortion_amount: f32,
+    pub shininess: f32,
+    pub reflection_hdr_mult: f32,
+    pub light_radius: f32,
+    pub light_brightness: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WaterFog {
+    pub amount: f32,
+    pub near_plane: f32,
+    pub far_plane: f32,
+}
+
+/// Settings of the simulation used for the ripples caused
+/// by rain or objects displacing the water
+#[derive(Debug, Clone, Copy)]
+pub struct WaterSimulator {
+    pub starting_size: f32,
+    pub force: f32,
+    pub velocity: f32,
+    pub falloff: f32,
+    pub dampener: f32,
+}
+
+/// Layer of noise making up the waves on the surface
+#[derive(Debug, Clone, Copy)]
+pub struct WaterNoiseLayer {
+    /// Direction of the wind in degrees
+    pub wind_direction: f32,
+    pub wind_speed: f32,
+    pub uv_scale: f32,
+    pub amplitude_scale: f32,
+}
+
+impl FromRecordBytes for WaterVisualData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, _unknown) = take(16usize)(input)?;
+        let (input, sun_power) = le_f32(input)?;
+        let (input, reflectivity_amount) = le_f32(input)?;
+        let (input, fresnel_amount) = le_f32(input)?;
+        let (input, _unused) = take(4usize)(input)?;
+        let (input, above_water_fog_near_plane) = le_f32(input)?;
+        let (input, above_water_fog_far_plane) = le_f32(input)?;
+        let (input, shallow_color) = RGBA::parse(input)?;
+        let (input, deep_color) = RGBA::parse(input)?;
+        let (input, reflection_color) = RGBA::parse(input)?;
+        let (input, _unused) = take(4usize)(input)?;
+        let (input, rain_force) = le_f32(input)?;
+        let (input, rain_velocity) = le_f32(input)?;
+        let (input, rain_falloff) = le_f32(input)?;
+        let (input, rain_dampener) = le_f32(input)?;
+        let (input, displacement_simulator) = WaterSimulator::parse(input)?;
+        let (input, rain_starting_size) = le_f32(input)?;
+        let (input, noise_scale) = le_f32(input)?;
+        let (input, wind_directions) = Vector3::<f32>::parse(input)?;
+        let (input, wind_speeds) = Vector3::<f32>::parse(input)?;
+        let (input, depth_falloff_start) = le_f32(input)?;
+        let (input, depth_falloff_end) = le_f32(input)?;
+        let (input, above_water_fog_amount) = le_f32(input)?;
+        let (input, normals_uv_scale) = le_f32(input)?;
+        let (input, under_water_fog) = WaterFog::parse(input)?;
+        let (input, distortion_amount) = le_f32(input)?;
+        let (input, shininess) = le_f32(input)?;
+        let (input, reflection_hdr_mult) = le_f32(input)?;
+        let (input, light_radius) = le_f32(input)?;
+        let (input, light_brightness) = le_f32(input)?;
+        let (input, uv_scales) = Vector3::<f32>::parse(input)?;
+        let (input, amplitude_scales) = Vector3::<f32>::parse(input)?;
+
+        let noise_layers = std::array::from_fn(|index| WaterNoiseLayer {
+            wind_direction: wind_directions[index],
+            wind_speed: wind_speeds[index],
+            uv_scale: uv_scales[index],
+            amplitude_scale: amplitude_scales[index],
+        });
+
+        Ok((
+            input,
+            Self {
+                sun_power,
+                reflectivity_amount,
+                fresnel_amount,
+                above_water_fog: WaterFog {
+                    amount: above_water_fog_amount,
+                    near_plane: above_water_fog_near_plane,
+                    far_plane: above_water_fog_far_plane,
+                },
+                shallow_color,
+                deep_color,
+                reflection_color,
+                rain_simulator: WaterSimulator {
+                    starting_size: rain_starting_size,
+                    force: rain_force,
+                    velocity: rain_velocity,
+                    falloff: rain_falloff,
+                    dampener: rain_dampener,
+                },
+                displacement_simulator,
+                noise_scale,
+                noise_layers,
+                depth_falloff_start,
+                depth_falloff_end,
+                normals_uv_scale,
+                under_water_fog,
+                distortion_amount,
+                shininess,
+                reflection_hdr_mult,
+                light_radius,
+                light_brightness,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for WaterFog {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32)),
+            |(amount, near_plane, far_plane)| Self {
+                amount,
+                near_plane,
+                far_plane,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for WaterSimulator {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32, le_f32, le_f32)),
+            |(starting_size, force, velocity, falloff, dampener)| Self {
+                starting_size,
+                force,
+                velocity,
+                falloff,
+                dampener,
+            },
+        )(input)
+    }
+}
+
+/// Waters used in place of the water at different times
+#[derive(Debug)]
+pub struct RelatedWaters {
+    pub daytime: NTypedFormId<WATR>,
+    pub nighttime: NTypedFormId<WATR>,
+    pub underwater: NTypedFormId<WATR>,
+}
+
+impl FromRecordBytes for RelatedWaters {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                NTypedFormId::parse,
+                NTypedFormId::parse,
+                NTypedFormId::parse,
+            )),
+            |(daytime, nighttime, underwater)| Self {
+                daytime,
+                nighttime,
+                underwater,
+            },
+        )(input)
     }
 }
@@ -1,15 +1,432 @@
-use super::prelude::*;
+use super::{
+    imad::IMAD,
+    prelude::{model::ModelData, *},
+    soun::SOUN,
+};
 
 /// Weather
 #[derive(Debug)]
 pub struct WTHR {
-    // TODO:
+    pub editor_id: EditorId,
+    pub image_space_modifiers: WeatherImageSpaceModifiers,
+    pub cloud_texture_layer_0: Option<String>,
+    pub cloud_texture_layer_1: Option<String>,
+    pub cloud_texture_layer_2: Option<String>,
+    pub cloud_texture_layer_3: Option<String>,
+    pub model_data: Option<ModelData>,
+    pub max_cloud_layers: Option<u32>,
+    pub cloud_speeds: Option<CloudSpeeds>,
+    pub cloud_layer_colors: Option<CloudLayerColors>,
+    pub colors: WeatherColors,
+    pub fog_distance: FogDistance,
+    pub hdr: Option<WeatherHdr>,
+    pub data: WeatherData,
+    pub sounds: Vec<WeatherSound>,
 }
 
 impl Record for WTHR {
-    const TYPE: RecordType = RecordType::new(b"WTHR");
+    const TYPE: RecordType = WTHR;
 
     fn parse<'b>(parser: &mut RecordParser<'_, 'b>) -> Result<Self, RecordParseError<'b>> {
-        todo!()
+        let editor_id: EditorId = parser.parse(EDID)?;
+        let image_space_modifiers = WeatherImageSpaceModifiers {
+            sunrise: parser.try_parse(IAD0)?,
+            day: parser.try_parse(IAD1)?,
+            sunset: parser.try_parse(IAD2)?,
+            night: parser.try_parse(IAD3)?,
+            high_noon: parser.try_parse(IAD4)?,
+            midnight: parser.try_parse(IAD5)?,
+        };
+        let cloud_texture_layer_0: Option<String> = parser.try_parse(DNAM)?;
+        let cloud_texture_layer_1: Option<String> = parser.try_parse(CNAM)?;
+        let cloud_texture_layer_2: Option<String> = parser.try_parse(ANAM)?;
+        let cloud_texture_layer_3: Option<String> = parser.try_parse(BNAM)?;
+        let model_data: Option<ModelData> = ModelData::parse_first(parser)?;
+        let max_cloud_layers: Option<u32> = parser.try_parse(LNAM)?;
+        let cloud_speeds: Option<CloudSpeeds> = parser.try_parse(ONAM)?;
+        let cloud_layer_colors: Option<CloudLayerColors> = parser.try_parse(PNAM)?;
+        let colors: WeatherColors = parser.parse(NAM0)?;
+        let fog_distance: FogDistance = parser.parse(FNAM)?;
+        let hdr: Option<WeatherHdr> = parser.try_parse(HNAM)?;
+
+        // Unused
+        parser.skip_type(INAM);
+
+        let data: WeatherData = parser.parse(DATA)?;
+        let sounds: Vec<WeatherSound> = parser.try_parse_many(SNAM)?;
+
+        Ok(Self {
+            editor_id,
+            image_space_modifiers,
+            cloud_texture_layer_0,
+            cloud_texture_layer_1,
+            cloud_texture_layer_2,
+            cloud_texture_layer_3,
+            model_data,
+            max_cloud_layers,
+            cloud_speeds,
+            cloud_layer_colors,
+            colors,
+            fog_distance,
+            hdr,
+            data,
+            sounds,
+        })
+    }
+}
+
+/// The times of day that weather values are provided for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Sunrise,
+    Day,
+    Sunset,
+    Night,
+    HighNoon,
+    Midnight,
+}
+
+/// Image space modifiers applied by the weather at each time of day
+#[derive(Debug)]
+pub struct WeatherImageSpaceModifiers {
+    pub sunrise: Option<TypedFormId<IMAD>>,
+    pub day: Option<TypedFormId<IMAD>>,
+    pub sunset: Option<TypedFormId<IMAD>>,
+    pub night: Option<TypedFormId<IMAD>>,
+    pub high_noon: Option<TypedFormId<IMAD>>,
+    pub midnight: Option<TypedFormId<IMAD>>,
+}
+
+impl WeatherImageSpaceModifiers {
+    /// Gets the image space modifier for the provided time of day
+    pub fn get(&self, time: TimeOfDay) -> Option<&TypedFormId<IMAD>> {
+        match time {
+            TimeOfDay::Sunrise => self.sunrise.as_ref(),
+            TimeOfDay::Day => self.day.as_ref(),
+            TimeOfDay::Sunset => self.sunset.as_ref(),
+            TimeOfDay::Night => self.night.as_ref(),
+            TimeOfDay::HighNoon => self.high_noon.as_ref(),
+            TimeOfDay::Midnight => self.midnight.as_ref(),
+        }
+    }
+}
+
+/// Colour of a single value at each time of day
+#[derive(Debug, Clone, Copy)]
+pub struct TimeOfDayColors {
+    pub sunrise: RGBA,
+    pub day: RGBA,
+    pub sunset: RGBA,
+    pub night: RGBA,
+    pub high_noon: RGBA,
+    pub midnight: RGBA,
+}
+
+impl TimeOfDayColors {
+    /// Gets the colour for the provided time of day
+    pub fn get(&self, time: TimeOfDay) -> RGBA {
+        match time {
+            TimeOfDay::Sunrise => self.sunrise,
+            TimeOfDay::Day => self.day,
+            TimeOfDay::Sunset => self.sunset,
+            TimeOfDay::Night => self.night,
+            TimeOfDay::HighNoon => self.high_noon,
+            TimeOfDay::Midnight => self.midnight,
+        }
+    }
+}
+
+/// Speed of each cloud layer
+#[derive(Debug)]
+pub struct CloudSpeeds {
+    pub layer_0: u8,
+    pub layer_1: u8,
+    pub layer_2: u8,
+    pub layer_3: u8,
+}
+
+/// Colour of each cloud layer at each time of day
+#[derive(Debug)]
+pub struct CloudLayerColors {
+    pub layer_0: TimeOfDayColors,
+    pub layer_1: TimeOfDayColors,
+    pub layer_2: TimeOfDayColors,
+    pub layer_3: TimeOfDayColors,
+}
+
+/// Colours of the different sky elements at each time of day
+#[derive(Debug)]
+pub struct WeatherColors {
+    pub sky_upper: TimeOfDayColors,
+    pub fog: TimeOfDayColors,
+    pub ambient: TimeOfDayColors,
+    pub sunlight: TimeOfDayColors,
+    pub sun: TimeOfDayColors,
+    pub stars: TimeOfDayColors,
+    pub sky_lower: TimeOfDayColors,
+    pub horizon: TimeOfDayColors,
+}
+
+#[derive(Debug)]
+pub struct FogDistance {
+    pub day_near: f32,
+    pub day_far: f32,
+    pub night_near: f32,
+    pub night_far: f32,
+    pub day_power: f32,
+    pub night_power: f32,
+}
+
+#[derive(Debug)]
+pub struct WeatherHdr {
+    pub eye_adapt_speed: f32,
+    pub blur_radius: f32,
+    pub blur_passes: f32,
+    pub emissive_multiplier: f32,
+    pub target_lum: f32,
+    pub upper_lum_clamp: f32,
+    pub bright_scale: f32,
+    pub bright_clamp: f32,
+    pub lum_ramp_no_texture: f32,
+    pub lum_ramp_min: f32,
+    pub lum_ramp_max: f32,
+    pub sunlight_dimmer: f32,
+    pub grass_dimmer: f32,
+    pub tree_dimmer: f32,
+}
+
+#[derive(Debug)]
+pub struct WeatherData {
+    pub wind_speed: u8,
+    pub cloud_speed_lower: u8,
+    pub cloud_speed_upper: u8,
+    pub trans_delta: u8,
+    pub sun_glare: u8,
+    pub sun_damage: u8,
+    pub precipitation_begin_fade_in: u8,
+    pub precipitation_end_fade_out: u8,
+    pub lightning_begin_fade_in: u8,
+    pub lightning_end_fade_out: u8,
+    pub lightning_frequency: u8,
+    pub classification: WeatherClassification,
+    pub lightning_color: Vector3<u8>,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct WeatherClassification: u8 {
+        const PLEASANT = 0x01;
+        const CLOUDY   = 0x02;
+        const RAINY    = 0x04;
+        const SNOW     = 0x08;
+    }
+}
+
+#[derive(Debug)]
+pub struct WeatherSound {
+    pub sound: TypedFormId<SOUN>,
+    pub ty: WeatherSoundType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum WeatherSoundType {
+    Default = 0,
+    Precipitation = 1,
+    Wind = 2,
+    Thunder = 3,
+}
+
+impl FromRecordBytes for TimeOfDayColors {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                RGBA::parse,
+                RGBA::parse,
+                RGBA::parse,
+                RGBA::parse,
+                RGBA::parse,
+                RGBA::parse,
+            )),
+            |(sunrise, day, sunset, night, high_noon, midnight)| Self {
+                sunrise,
+                day,
+                sunset,
+                night,
+                high_noon,
+                midnight,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for CloudSpeeds {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((u8, u8, u8, u8)),
+            |(layer_0, layer_1, layer_2, layer_3)| Self {
+                layer_0,
+                layer_1,
+                layer_2,
+                layer_3,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for CloudLayerColors {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((
+                TimeOfDayColors::parse,
+                TimeOfDayColors::parse,
+                TimeOfDayColors::parse,
+                TimeOfDayColors::parse,
+            )),
+            |(layer_0, layer_1, layer_2, layer_3)| Self {
+                layer_0,
+                layer_1,
+                layer_2,
+                layer_3,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for WeatherColors {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, sky_upper) = TimeOfDayColors::parse(input)?;
+        let (input, fog) = TimeOfDayColors::parse(input)?;
+        // Unused (Clouds lower)
+        let (input, _unused) = TimeOfDayColors::parse(input)?;
+        let (input, ambient) = TimeOfDayColors::parse(input)?;
+        let (input, sunlight) = TimeOfDayColors::parse(input)?;
+        let (input, sun) = TimeOfDayColors::parse(input)?;
+        let (input, stars) = TimeOfDayColors::parse(input)?;
+        let (input, sky_lower) = TimeOfDayColors::parse(input)?;
+        let (input, horizon) = TimeOfDayColors::parse(input)?;
+        // Unused (Clouds upper)
+        let (input, _unused) = TimeOfDayColors::parse(input)?;
+
+        Ok((
+            input,
+            Self {
+                sky_upper,
+                fog,
+                ambient,
+                sunlight,
+                sun,
+                stars,
+                sky_lower,
+                horizon,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for FogDistance {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_f32, le_f32, le_f32, le_f32, le_f32, le_f32)),
+            |(day_near, day_far, night_near, night_far, day_power, night_power)| Self {
+                day_near,
+                day_far,
+                night_near,
+                night_far,
+                day_power,
+                night_power,
+            },
+        )(input)
+    }
+}
+
+impl FromRecordBytes for WeatherHdr {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, eye_adapt_speed) = le_f32(input)?;
+        let (input, blur_radius) = le_f32(input)?;
+        let (input, blur_passes) = le_f32(input)?;
+        let (input, emissive_multiplier) = le_f32(input)?;
+        let (input, target_lum) = le_f32(input)?;
+        let (input, upper_lum_clamp) = le_f32(input)?;
+        let (input, bright_scale) = le_f32(input)?;
+        let (input, bright_clamp) = le_f32(input)?;
+        let (input, lum_ramp_no_texture) = le_f32(input)?;
+        let (input, lum_ramp_min) = le_f32(input)?;
+        let (input, lum_ramp_max) = le_f32(input)?;
+        let (input, sunlight_dimmer) = le_f32(input)?;
+        let (input, grass_dimmer) = le_f32(input)?;
+        let (input, tree_dimmer) = le_f32(input)?;
+
+        Ok((
+            input,
+            Self {
+                eye_adapt_speed,
+                blur_radius,
+                blur_passes,
+                emissive_multiplier,
+                target_lum,
+                upper_lum_clamp,
+                bright_scale,
+                bright_clamp,
+                lum_ramp_no_texture,
+                lum_ramp_min,
+                lum_ramp_max,
+                sunlight_dimmer,
+                grass_dimmer,
+                tree_dimmer,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for WeatherData {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, wind_speed) = u8(input)?;
+        let (input, cloud_speed_lower) = u8(input)?;
+        let (input, cloud_speed_upper) = u8(input)?;
+        let (input, trans_delta) = u8(input)?;
+        let (input, sun_glare) = u8(input)?;
+        let (input, sun_damage) = u8(input)?;
+        let (input, precipitation_begin_fade_in) = u8(input)?;
+        let (input, precipitation_end_fade_out) = u8(input)?;
+        let (input, lightning_begin_fade_in) = u8(input)?;
+        let (input, lightning_end_fade_out) = u8(input)?;
+        let (input, lightning_frequency) = u8(input)?;
+        let (input, classification) = WeatherClassification::parse(input)?;
+        let (input, lightning_color) = Vector3::parse(input)?;
+
+        Ok((
+            input,
+            Self {
+                wind_speed,
+                cloud_speed_lower,
+                cloud_speed_upper,
+                trans_delta,
+                sun_glare,
+                sun_damage,
+                precipitation_begin_fade_in,
+                precipitation_end_fade_out,
+                lightning_begin_fade_in,
+                lightning_end_fade_out,
+                lightning_frequency,
+                classification,
+                lightning_color,
+            },
+        ))
+    }
+}
+
+impl FromRecordBytes for WeatherClassification {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
+    }
+}
+
+impl FromRecordBytes for WeatherSound {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((TypedFormId::parse, enum_value::<WeatherSoundType>)),
+            |(sound, ty)| Self { sound, ty },
+        )(input)
     }
 }
@@ -1,4 +1,10 @@
-use nom::{bytes::complete::take, combinator::map, number::complete::le_u32, sequence::tuple};
+use bitflags::bitflags;
+use nom::{
+    bytes::complete::take,
+    combinator::{map, map_res},
+    number::complete::{le_u16, u8},
+    sequence::tuple,
+};
 use num_enum::TryFromPrimitive;
 
 use crate::esp::{
@@ -9,9 +15,11 @@ use crate::esp::{
 /// CTDA
 #[derive(Debug)]
 pub struct CTDA {
-    pub ty: ConditionType,
+    pub operator: ComparisonOperator,
+    pub flags: ConditionFlags,
     pub comparison_value: RawComparisonValue,
-    pub func_index: u32,
+    /// Index of the condition function
+    pub func_index: u16,
     pub param_1: [u8; 4],
     pub param_2: [u8; 4],
     pub run_on: RunOn,
@@ -23,18 +31,34 @@ impl FromRecordBytes for CTDA {
     fn parse(input: &[u8]) -> nom::IResult<&[u8], Self> {
         map(
             tuple((
-                enum_value::<ConditionType>,
-                // This might be take 3 instead (Docs seem incorrect, changed to 2 because ty is u16 not u8 like doc suggests)
-                take(2usize),
+                // Operator is stored in the upper 3 bits of the type
+                map_res(u8, |ty| {
+                    ComparisonOperator::try_from(ty >> 5)
+                        .map(|operator| (operator, ConditionFlags::from_bits_retain(ty & 0x1F)))
+                }),
+                take(3usize),
                 RawComparisonValue::parse,
-                le_u32,
+                le_u16,
+                // Unused
+                take(2usize),
                 take4,
                 take4,
                 enum_value::<RunOn>,
                 FormId::parse,
             )),
-            |(ty, _, comparison_value, func_index, param_1, param_2, run_on, reference)| Self {
-                ty,
+            |(
+                (operator, flags),
+                _,
+                comparison_value,
+                func_index,
+                _,
+                param_1,
+                param_2,
+                run_on,
+                reference,
+            )| Self {
+                operator,
+                flags,
                 comparison_value,
                 func_index,
                 param_1,
@@ -46,10 +70,21 @@ impl FromRecordBytes for CTDA {
     }
 }
 
+impl CTDA {
+    /// Value the result of the function is compared against
+    pub fn comparison(&self) -> ComparisonValue {
+        if self.flags.contains(ConditionFlags::USE_GLOBAL) {
+            ComparisonValue::Global(FormId(u32::from_le_bytes(self.comparison_value.0)))
+        } else {
+            ComparisonValue::Value(f32::from_le_bytes(self.comparison_value.0))
+        }
+    }
+}
+
 /// 4 bytes which must either be a FormId for a GLOB record or a
 /// f32 value
 #[derive(Debug)]
-pub struct RawComparisonValue([u8; 4]);
+pub struct RawComparisonValue(pub [u8; 4]);
 
 impl FromRecordBytes for RawComparisonValue {
     fn parse(input: &[u8]) -> nom::IResult<&[u8], Self> {
@@ -57,23 +92,49 @@ impl FromRecordBytes for RawComparisonValue {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
-#[repr(u16)]
-pub enum ConditionType {
-    // Combine next condition using OR (default is to use AND)
-    CombineOr = 0x0001,
-    RunOnTarget = 0x0002,
-    UseGlobal = 0x0004,
-    EqualTo = 0x0000,
-    NotEqualTo = 0x2000,
-    GreaterThan = 0x4000,
-    GreaterThanOrEqual = 0x6000,
-    LessThan = 0x8000,
-    LessThanOrEqual = 0xA000,
+/// Decoded [`RawComparisonValue`] based on [`ConditionFlags::USE_GLOBAL`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComparisonValue {
+    Value(f32),
+    Global(FormId),
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ConditionFlags: u8 {
+        /// Combine next condition using OR (default is to use AND)
+        const COMBINE_OR    = 0x01;
+        const RUN_ON_TARGET = 0x02;
+        const USE_GLOBAL    = 0x04;
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u8)]
+pub enum ComparisonOperator {
+    EqualTo = 0,
+    NotEqualTo = 1,
+    GreaterThan = 2,
+    GreaterThanOrEqual = 3,
+    LessThan = 4,
+    LessThanOrEqual = 5,
+}
+
+impl ComparisonOperator {
+    pub fn compare(self, lhs: f32, rhs: f32) -> bool {
+        match self {
+            Self::EqualTo => lhs == rhs,
+            Self::NotEqualTo => lhs != rhs,
+            Self::GreaterThan => lhs > rhs,
+            Self::GreaterThanOrEqual => lhs >= rhs,
+            Self::LessThan => lhs < rhs,
+            Self::LessThanOrEqual => lhs <= rhs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
 pub enum RunOn {
     Subject = 0,
     Target = 1,
@@ -81,3 +142,27 @@ pub enum RunOn {
     CombatTarget = 3,
     LinkedReference = 4,
 }
+
+#[cfg(test)]
+mod test {
+    use crate::esp::record::FromRecordBytes;
+
+    use super::{ComparisonOperator, CTDA};
+
+    #[test]
+    fn test_parse() {
+        let mut data = vec![0x60, 0, 0, 0];
+        data.extend(10f32.to_le_bytes());
+        // GetStage followed by leftover bytes in the unused padding
+        data.extend([58, 0, 0xCD, 0xCD]);
+        data.extend([0; 16]);
+
+        let (_, ctda) = CTDA::parse(&data).unwrap();
+        assert_eq!(ctda.operator, ComparisonOperator::GreaterThanOrEqual);
+        assert_eq!(ctda.func_index, 58);
+
+        // Operators past LessThanOrEqual are invalid
+        data[0] = 0xE0;
+        assert!(CTDA::parse(&data).is_err());
+    }
+}
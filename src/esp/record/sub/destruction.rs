@@ -7,7 +7,10 @@ use nom::{
 };
 
 use crate::esp::{
-    record::{FromRecordBytes, RecordCollection},
+    record::{
+        records::{debr::DEBR, expl::EXPL},
+        FromRecordBytes, RecordCollection,
+    },
     shared::{FormId, TypedFormId},
 };
 
@@ -98,8 +101,8 @@ pub struct DSTD {
     pub damage_stage: u8,
     pub flags: DSTDFlags,
     pub self_damage_per_second: i32,
-    pub explosion: TypedFormId<() /* EXPL */>,
-    pub debris: TypedFormId<() /* DEBR */>,
+    pub explosion: TypedFormId<EXPL>,
+    pub debris: TypedFormId<DEBR>,
     pub debris_count: i32,
 }
 
@@ -0,0 +1,78 @@
+use bitflags::bitflags;
+use nom::{
+    combinator::{map, rest},
+    number::complete::u8,
+    sequence::tuple,
+    IResult,
+};
+
+use crate::esp::{
+    record::{
+        records::idle::IDLE, FromRecordBytes, RecordCollection, RecordParseError, RecordParser,
+        Repeated,
+    },
+    shared::TypedFormId,
+};
+
+use super::{IDLA, IDLB, IDLC, IDLF, IDLT};
+
+/// List of idle animations played by packages and idle markers
+#[derive(Debug)]
+pub struct IdleAnimations {
+    pub flags: IdleAnimationFlags,
+    pub animation_count: u8,
+    /// Time in seconds between each idle
+    pub idle_timer: f32,
+    pub animations: Vec<TypedFormId<IDLE>>,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct IdleAnimationFlags: u8 {
+        const RUN_IN_SEQUENCE = 0x01;
+        const U1              = 0x02;
+        const DO_ONCE         = 0x04;
+    }
+}
+
+impl RecordCollection for IdleAnimations {
+    fn parse_next<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        let flags: IdleAnimationFlags = match parser.try_parse(IDLF)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let animation_count: u8 = parser.parse::<IdleCount>(IDLC)?.0;
+        let idle_timer: f32 = parser.parse(IDLT)?;
+        let animations: Vec<TypedFormId<IDLE>> = parser
+            .try_parse::<Repeated<TypedFormId<IDLE>>>(IDLA)?
+            .map(|value| value.into_inner())
+            .unwrap_or_default();
+
+        // Unused
+        parser.skip_type(IDLB);
+
+        Ok(Some(Self {
+            flags,
+            animation_count,
+            idle_timer,
+            animations,
+        }))
+    }
+}
+
+impl FromRecordBytes for IdleAnimationFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
+    }
+}
+
+/// Animation count followed by optional unused bytes
+struct IdleCount(u8);
+
+impl FromRecordBytes for IdleCount {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(tuple((u8, rest)), |(count, _)| Self(count))(input)
+    }
+}
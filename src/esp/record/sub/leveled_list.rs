@@ -0,0 +1,76 @@
+use bitflags::bitflags;
+use nom::{
+    bytes::complete::take,
+    combinator::map,
+    number::complete::{le_i16, u8},
+    sequence::tuple,
+    IResult,
+};
+
+use crate::esp::{
+    record::{FromRecordBytes, RecordCollection, RecordParseError, RecordParser},
+    shared::FormId,
+};
+
+use super::{item::COED, COED, LVLO};
+
+/// Entry within a leveled list
+#[derive(Debug)]
+pub struct LeveledEntry {
+    pub data: LVLO,
+    pub extra_data: Option<COED>,
+}
+
+impl RecordCollection for LeveledEntry {
+    fn parse_next<'b>(
+        parser: &mut RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, RecordParseError<'b>> {
+        let data = match parser.try_parse::<LVLO>(LVLO)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let extra_data = parser.try_parse::<COED>(COED)?;
+
+        Ok(Some(Self { data, extra_data }))
+    }
+}
+
+#[derive(Debug)]
+pub struct LVLO {
+    pub level: i16,
+    /// FormID of the leveled reference, the allowed record types
+    /// depend on the type of leveled list
+    pub reference: FormId,
+    pub count: i16,
+}
+
+impl FromRecordBytes for LVLO {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((le_i16, take(2usize), FormId::parse, le_i16, take(2usize))),
+            |(level, _, reference, count, _)| Self {
+                level,
+                reference,
+                count,
+            },
+        )(input)
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct LeveledFlags: u8 {
+        /// Calculate from all levels <= player's level
+        const CALCULATE_FROM_ALL_LEVELS = 0x01;
+        /// Calculate for each item in count
+        const CALCULATE_FOR_EACH_ITEM   = 0x02;
+        /// Use all entries (Only present on LVLI)
+        const USE_ALL                   = 0x04;
+    }
+}
+
+impl FromRecordBytes for LeveledFlags {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(u8, Self::from_bits_retain)(input)
+    }
+}
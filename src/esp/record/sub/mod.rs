@@ -5,7 +5,9 @@ pub mod condition;
 pub mod destruction;
 pub mod effect;
 pub mod equipment_type;
+pub mod idle_animations;
 pub mod item;
+pub mod leveled_list;
 pub mod model;
 pub mod object_bounds;
 pub mod script;
@@ -279,3 +281,67 @@ pub const XCMT: RecordType = RecordType::new(b"XCMT");
 pub const XCMO: RecordType = RecordType::new(b"XCMO");
 pub const LTMP: RecordType = RecordType::new(b"LTMP");
 pub const WLST: RecordType = RecordType::new(b"WLST");
+pub const LVLD: RecordType = RecordType::new(b"LVLD");
+pub const LVLF: RecordType = RecordType::new(b"LVLF");
+pub const LVLG: RecordType = RecordType::new(b"LVLG");
+pub const LVLO: RecordType = RecordType::new(b"LVLO");
+// Weather image space modifiers (Sunrise, Day, Sunset, Night, High Noon, Midnight)
+pub const IAD0: RecordType = RecordType::new(b"\x00IAD");
+pub const IAD1: RecordType = RecordType::new(b"\x01IAD");
+pub const IAD2: RecordType = RecordType::new(b"\x02IAD");
+pub const IAD3: RecordType = RecordType::new(b"\x03IAD");
+pub const IAD4: RecordType = RecordType::new(b"\x04IAD");
+pub const IAD5: RecordType = RecordType::new(b"\x05IAD");
+pub const PKDT: RecordType = RecordType::new(b"PKDT");
+pub const PLDT: RecordType = RecordType::new(b"PLDT");
+pub const PLD2: RecordType = RecordType::new(b"PLD2");
+pub const PSDT: RecordType = RecordType::new(b"PSDT");
+pub const PTDT: RecordType = RecordType::new(b"PTDT");
+pub const PTD2: RecordType = RecordType::new(b"PTD2");
+pub const IDLF: RecordType = RecordType::new(b"IDLF");
+pub const IDLC: RecordType = RecordType::new(b"IDLC");
+pub const IDLT: RecordType = RecordType::new(b"IDLT");
+pub const IDLA: RecordType = RecordType::new(b"IDLA");
+pub const IDLB: RecordType = RecordType::new(b"IDLB");
+pub const PKED: RecordType = RecordType::new(b"PKED");
+pub const PKE2: RecordType = RecordType::new(b"PKE2");
+pub const PKFD: RecordType = RecordType::new(b"PKFD");
+pub const PKPT: RecordType = RecordType::new(b"PKPT");
+pub const PKW3: RecordType = RecordType::new(b"PKW3");
+pub const PUID: RecordType = RecordType::new(b"PUID");
+pub const PKAM: RecordType = RecordType::new(b"PKAM");
+pub const PKDD: RecordType = RecordType::new(b"PKDD");
+pub const POBA: RecordType = RecordType::new(b"POBA");
+pub const POEA: RecordType = RecordType::new(b"POEA");
+pub const POCA: RecordType = RecordType::new(b"POCA");
+pub const PRKE: RecordType = RecordType::new(b"PRKE");
+pub const PRKC: RecordType = RecordType::new(b"PRKC");
+pub const EPFT: RecordType = RecordType::new(b"EPFT");
+pub const EPF2: RecordType = RecordType::new(b"EPF2");
+pub const EPF3: RecordType = RecordType::new(b"EPF3");
+pub const EPFD: RecordType = RecordType::new(b"EPFD");
+pub const PRKF: RecordType = RecordType::new(b"PRKF");
+pub const RDSD: RecordType = RecordType::new(b"RDSD");
+pub const RDSI: RecordType = RecordType::new(b"RDSI");
+pub const CSTD: RecordType = RecordType::new(b"CSTD");
+pub const CSAD: RecordType = RecordType::new(b"CSAD");
+pub const CSSD: RecordType = RecordType::new(b"CSSD");
+pub const NVER: RecordType = RecordType::new(b"NVER");
+pub const RAFD: RecordType = RecordType::new(b"RAFD");
+pub const RAFB: RecordType = RecordType::new(b"RAFB");
+pub const RAPS: RecordType = RecordType::new(b"RAPS");
+pub const RCLR: RecordType = RecordType::new(b"RCLR");
+pub const RPLI: RecordType = RecordType::new(b"RPLI");
+pub const RPLD: RecordType = RecordType::new(b"RPLD");
+pub const RDOT: RecordType = RecordType::new(b"RDOT");
+pub const RDWT: RecordType = RecordType::new(b"RDWT");
+pub const RDMP: RecordType = RecordType::new(b"RDMP");
+pub const RDGS: RecordType = RecordType::new(b"RDGS");
+pub const RDMD: RecordType = RecordType::new(b"RDMD");
+pub const RDMO: RecordType = RecordType::new(b"RDMO");
+pub const RDSB: RecordType = RecordType::new(b"RDSB");
+pub const RDID: RecordType = RecordType::new(b"RDID");
+pub const JNAM: RecordType = RecordType::new(b"JNAM");
+pub const KNAM: RecordType = RecordType::new(b"KNAM");
+pub const NNAM: RecordType = RecordType::new(b"NNAM");
+pub const SCRV: RecordType = RecordType::new(b"SCRV");
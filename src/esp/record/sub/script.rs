@@ -8,11 +8,12 @@ use nom::{
 };
 use num_enum::TryFromPrimitive;
 
-use crate::esp::record::{
-    enum_value, take4, FromRecordBytes, FullString, RawBytes, RecordCollection,
+use crate::esp::{
+    record::{enum_value, take4, FromRecordBytes, FullString, RawBytes, RecordCollection},
+    shared::FormId,
 };
 
-use super::{SCDA, SCHR, SCRO, SCTX, SCVR, SLSD};
+use super::{SCDA, SCHR, SCRO, SCRV, SCTX, SCVR, SLSD};
 
 #[derive(Debug)]
 pub struct Script {
@@ -34,7 +35,7 @@ impl RecordCollection for Script {
         let compiled_source = parser.parse::<RawBytes>(SCDA)?.into_inner();
         let source = parser.parse::<FullString>(SCTX)?.into_inner();
         let local_variables = parser.parse_collection::<LocalVariable>()?;
-        let references = parser.try_parse_many::<Reference>(SCRO)?;
+        let references = parser.parse_collection::<Reference>()?;
         Ok(Some(Self {
             basic_data,
             compiled_source,
@@ -45,12 +46,25 @@ impl RecordCollection for Script {
     }
 }
 
+/// Object referenced by the script, compiled code refers to these
+/// using their 1-based index within the script references
 #[derive(Debug)]
-pub struct Reference(u32);
+pub enum Reference {
+    /// Reference to a form (SCRO)
+    Object(FormId),
+    /// Reference stored in the local variable with the index (SCRV)
+    Variable(u32),
+}
 
-impl FromRecordBytes for Reference {
-    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
-        map(le_u32, Reference)(input)
+impl RecordCollection for Reference {
+    fn parse_next<'b>(
+        parser: &mut crate::esp::record::RecordParser<'_, 'b>,
+    ) -> Result<Option<Self>, crate::esp::record::RecordParseError<'b>> {
+        if let Some(form_id) = parser.try_parse::<FormId>(SCRO)? {
+            return Ok(Some(Self::Object(form_id)));
+        }
+        let index = parser.try_parse::<u32>(SCRV)?;
+        Ok(index.map(Self::Variable))
     }
 }
 
@@ -91,6 +105,7 @@ impl FromRecordBytes for SLSD {
 bitflags! {
     #[derive(Debug, Clone, Copy)]
     pub struct SLSDFlags: u8 {
+        /// Set for integer (short, long and int) variables
         const ENABLED = 0x0001;
     }
 }
@@ -10,14 +10,15 @@ use super::record::{
     records::{
         acti::ACTI, alch::ALCH, arma::ARMA, armo::ARMO, book::BOOK, bptd::BPTD, cell::CELL,
         clas::CLAS, cont::CONT, crea::CREA, dial::DIAL, door::DOOR, ench::ENCH, fact::FACT,
-        glob::GLOB, gmst::GMST, ingr::INGR, land::LAND, ligh::LIGH, ltex::LTEX, lvlc::LVLC,
-        lvli::LVLI, mgef::MGEF, misc::MISC, npc::NPC_, prelude::FormId, race::RACE, regn::REGN,
-        scpt::SCPT, soun::SOUN, spel::SPEL, stat::STAT, weap::WEAP, RecordValue,
+        flst::FLST, glob::GLOB, gmst::GMST, ingr::INGR, land::LAND, ligh::LIGH, ltex::LTEX,
+        lvlc::LVLC, lvli::LVLI, mgef::MGEF, misc::MISC, npc::NPC_, prelude::FormId, race::RACE,
+        regn::REGN, scpt::SCPT, soun::SOUN, spel::SPEL, stat::STAT, weap::WEAP, RecordValue,
     },
     FromRecordBytes, Group, RawEsmEntry, Record, RecordType,
 };
-use bevy::log::warn;
+use bevy::{ecs::system::Resource, log::warn};
 
+#[derive(Resource)]
 pub struct EsmStore {
     activators: Store<ACTI>,
     ingestibles: Store<ALCH>,
@@ -31,6 +32,7 @@ pub struct EsmStore {
     doors: Store<DOOR>,
     enchants: Store<ENCH>,
     factions: Store<FACT>,
+    form_lists: Store<FLST>,
     globals: Store<GLOB>,
     ingredients: Store<INGR>,
     creature_lists: Store<LVLC>,
@@ -53,6 +55,16 @@ pub struct EsmStore {
     magic_effects: Store<MGEF>,
 }
 
+impl EsmStore {
+    pub fn globals(&self) -> &Store<GLOB> {
+        &self.globals
+    }
+
+    pub fn game_settings(&self) -> &Store<GMST> {
+        &self.game_settings
+    }
+}
+
 #[derive(Clone)]
 pub struct Store<R: Record> {
     inner: Arc<StoreInner<R>>,
@@ -66,3 +78,60 @@ pub struct StoreInner<R: Record> {
     /// Stores for dynamic values created at runtime
     values_dynamic: Mutex<HashMap<String, R>>,
 }
+
+impl<R: Record> Store<R> {
+    pub fn new(values: HashMap<String, R>) -> Self {
+        Self {
+            inner: Arc::new(StoreInner {
+                values_static: values,
+                values_dynamic: Default::default(),
+            }),
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.inner.values_static.contains_key(key)
+            || self.inner.values_dynamic.lock().contains_key(key)
+    }
+
+    /// Calls `action` with the value for the key, values created
+    /// at runtime take priority over the ones loaded from files
+    pub fn with<T>(&self, key: &str, action: impl FnOnce(&R) -> T) -> Option<T> {
+        if let Some(value) = self.inner.values_dynamic.lock().get(key) {
+            return Some(action(value));
+        }
+        self.inner.values_static.get(key).map(action)
+    }
+
+    /// Calls `action` with every value, values created at runtime
+    /// replace the ones loaded from files with the same key
+    pub fn with_values<T>(&self, action: impl FnOnce(&mut dyn Iterator<Item = &R>) -> T) -> T {
+        let values_dynamic = self.inner.values_dynamic.lock();
+        let mut values = values_dynamic.values().chain(
+            self.inner
+                .values_static
+                .iter()
+                .filter(|(key, _)| !values_dynamic.contains_key(*key))
+                .map(|(_, value)| value),
+        );
+        action(&mut values)
+    }
+
+    /// Stores a value created at runtime, replacing any previous value
+    pub fn insert_dynamic(&self, key: String, value: R) {
+        self.inner.values_dynamic.lock().insert(key, value);
+    }
+}
+
+impl<R: Record + Clone> Store<R> {
+    /// Calls `action` with a mutable runtime value for the key, the value
+    /// loaded from files is copied into the dynamic values on first change
+    pub fn modify<T>(&self, key: &str, action: impl FnOnce(&mut R) -> T) -> Option<T> {
+        let mut values_dynamic = self.inner.values_dynamic.lock();
+        if !values_dynamic.contains_key(key) {
+            let value = self.inner.values_static.get(key)?.clone();
+            values_dynamic.insert(key.to_string(), value);
+        }
+        values_dynamic.get_mut(key).map(action)
+    }
+}
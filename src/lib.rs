@@ -0,0 +1,8 @@
+//! Engine modules shared by the game and the command line tools
+
+pub mod assets;
+pub mod constants;
+pub mod esp;
+pub mod script;
+pub mod utils;
+pub mod world;
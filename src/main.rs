@@ -1,22 +1,20 @@
-use crate::constants::{WINDOW_DEFAULT_HEIGHT, WINDOW_DEFAULT_WIDTH};
-use assets::bsa::BsaPlugin;
 use bevy::{
     log::{Level, LogPlugin},
     prelude::*,
     window::{WindowResolution, WindowTheme},
 };
-use constants::VERSION;
-
-pub mod assets;
-pub mod constants;
-pub mod utils;
-pub mod esp;
+use open_mojave::{
+    assets::bsa::BsaPlugin,
+    constants::{VERSION, WINDOW_DEFAULT_HEIGHT, WINDOW_DEFAULT_WIDTH},
+    utils, world,
+};
 
 fn main() {
-    let config = utils::config::load_config();
+    let (config, ini_settings) = utils::config::load_config();
 
     App::new()
         .insert_resource(config)
+        .insert_resource(ini_settings)
         .add_plugins(
             DefaultPlugins
                 .build()
@@ -42,5 +40,6 @@ fn main() {
                         .to_string(),
                 }),
         )
+        .add_plugins(world::globals::GameVariablesPlugin)
         .run();
 }
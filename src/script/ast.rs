@@ -0,0 +1,370 @@
+use std::fmt::{self, Display, Write};
+
+/// Range of a syntax element within the script, byte offsets into the
+/// source text for parsed scripts and into the bytecode for decompiled ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Span covering both spans
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    /// 1-based line and column of the start of the span within the source
+    pub fn location(&self, source: &str) -> (usize, usize) {
+        let before = &source[..self.start.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |index| index + 1) + 1;
+        (line, column)
+    }
+}
+
+/// Parsed or decompiled script
+#[derive(Debug, Clone, Default)]
+pub struct ScriptAst {
+    /// Name from the `scn` header, [`None`] for decompiled scripts
+    /// which don't store it
+    pub name: Option<String>,
+    pub variables: Vec<VariableDecl>,
+    pub blocks: Vec<Block>,
+}
+
+impl ScriptAst {
+    /// Finds the declared variable with the name, ignoring case
+    pub fn variable(&self, name: &str) -> Option<&VariableDecl> {
+        self.variables
+            .iter()
+            .find(|variable| variable.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VariableDecl {
+    pub ty: VariableType,
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableType {
+    Short,
+    Int,
+    Long,
+    Float,
+    Ref,
+}
+
+impl VariableType {
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        Some(match keyword.to_ascii_lowercase().as_str() {
+            "short" => Self::Short,
+            "int" => Self::Int,
+            "long" => Self::Long,
+            "float" => Self::Float,
+            "ref" | "reference" => Self::Ref,
+            _ => return None,
+        })
+    }
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Short => "short",
+            Self::Int => "int",
+            Self::Long => "long",
+            Self::Float => "float",
+            Self::Ref => "ref",
+        }
+    }
+
+    /// Whether values stored in the variable are truncated to integers
+    pub fn is_integer(self) -> bool {
+        matches!(self, Self::Short | Self::Int | Self::Long)
+    }
+}
+
+/// `begin` / `end` block run in response to an event
+#[derive(Debug, Clone)]
+pub struct Block {
+    /// Name of the block type (e.g. `GameMode`)
+    pub name: String,
+    pub args: Vec<Expr>,
+    pub body: Vec<Statement>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum StatementKind {
+    Set {
+        target: VariableRef,
+        value: Expr,
+    },
+    If {
+        /// The `if` branch followed by any `elseif` branches
+        branches: Vec<Branch>,
+        otherwise: Option<Vec<Statement>>,
+    },
+    Call(Call),
+    Return,
+}
+
+#[derive(Debug, Clone)]
+pub struct Branch {
+    pub condition: Expr,
+    pub body: Vec<Statement>,
+}
+
+/// Variable of the script itself, a global or a variable of
+/// another script accessed through `reference.variable`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableRef {
+    pub reference: Option<String>,
+    pub name: String,
+}
+
+/// Call to a script function, optionally on a reference
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub reference: Option<String>,
+    pub function: String,
+    pub args: Vec<Expr>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Int(i32),
+    Float(f64),
+    String(String),
+    /// Bare name that isn't a local variable, such as an editor ID,
+    /// global or an enum style argument (e.g. `X` or `Strength`)
+    Identifier(String),
+    Variable(VariableRef),
+    Call(Call),
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+    },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinaryOp {
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        Some(match symbol {
+            "||" => Self::Or,
+            "&&" => Self::And,
+            "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "+" => Self::Add,
+            "-" => Self::Sub,
+            "*" => Self::Mul,
+            "/" => Self::Div,
+            _ => return None,
+        })
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Or => "||",
+            Self::And => "&&",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+        }
+    }
+
+    /// Binding strength of the operator, higher binds tighter
+    pub fn precedence(self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And => 2,
+            Self::Eq | Self::Ne => 3,
+            Self::Lt | Self::Le | Self::Gt | Self::Ge => 4,
+            Self::Add | Self::Sub => 5,
+            Self::Mul | Self::Div => 6,
+        }
+    }
+}
+
+/// Precedence of unary operators and operands
+const ATOM_PRECEDENCE: u8 = 7;
+
+impl Expr {
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Binary { op, .. } => op.precedence(),
+            _ => ATOM_PRECEDENCE,
+        }
+    }
+
+    /// Writes the expression wrapping it in parentheses when it binds
+    /// looser than `precedence`
+    fn write_operand(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "({self})")
+        } else {
+            write!(f, "{self}")
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Int(value) => write!(f, "{value}"),
+            Expr::Float(value) => write!(f, "{value:?}"),
+            Expr::String(value) => write!(f, "\"{value}\""),
+            Expr::Identifier(name) => f.write_str(name),
+            Expr::Variable(variable) => write!(f, "{variable}"),
+            Expr::Call(call) => write!(f, "{call}"),
+            Expr::Unary {
+                op: UnaryOp::Neg,
+                operand,
+            } => {
+                f.write_char('-')?;
+                operand.write_operand(f, ATOM_PRECEDENCE)
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                let precedence = op.precedence();
+                lhs.write_operand(f, precedence)?;
+                write!(f, " {} ", op.symbol())?;
+                // Operators are left associative
+                rhs.write_operand(f, precedence + 1)
+            }
+        }
+    }
+}
+
+impl Display for VariableRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(reference) = &self.reference {
+            write!(f, "{reference}.")?;
+        }
+        f.write_str(&self.name)
+    }
+}
+
+impl Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(reference) = &self.reference {
+            write!(f, "{reference}.")?;
+        }
+        f.write_str(&self.function)?;
+        for arg in &self.args {
+            f.write_char(' ')?;
+            arg.write_operand(f, ATOM_PRECEDENCE)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_statements(
+    f: &mut fmt::Formatter<'_>,
+    statements: &[Statement],
+    depth: usize,
+) -> fmt::Result {
+    for statement in statements {
+        write_statement(f, statement, depth)?;
+    }
+    Ok(())
+}
+
+fn write_statement(f: &mut fmt::Formatter<'_>, statement: &Statement, depth: usize) -> fmt::Result {
+    let indent = "\t".repeat(depth);
+    match &statement.kind {
+        StatementKind::Set { target, value } => writeln!(f, "{indent}set {target} to {value}"),
+        StatementKind::Call(call) => writeln!(f, "{indent}{call}"),
+        StatementKind::Return => writeln!(f, "{indent}return"),
+        StatementKind::If {
+            branches,
+            otherwise,
+        } => {
+            for (index, branch) in branches.iter().enumerate() {
+                let keyword = if index == 0 { "if" } else { "elseif" };
+                writeln!(f, "{indent}{keyword} {}", branch.condition)?;
+                write_statements(f, &branch.body, depth + 1)?;
+            }
+            if let Some(otherwise) = otherwise {
+                writeln!(f, "{indent}else")?;
+                write_statements(f, otherwise, depth + 1)?;
+            }
+            writeln!(f, "{indent}endif")
+        }
+    }
+}
+
+/// Pretty prints the script as source code
+impl Display for ScriptAst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "scn {name}")?;
+            writeln!(f)?;
+        }
+
+        for variable in &self.variables {
+            writeln!(f, "{} {}", variable.ty.keyword(), variable.name)?;
+        }
+
+        for block in &self.blocks {
+            writeln!(f)?;
+            write!(f, "begin {}", block.name)?;
+            for arg in &block.args {
+                write!(f, " {arg}")?;
+            }
+            writeln!(f)?;
+            write_statements(f, &block.body, 1)?;
+            writeln!(f, "end")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,869 @@
+//! Decompiler for the compiled script data (SCDA) stored alongside
+//! the script source
+
+use thiserror::Error;
+
+use crate::esp::{
+    record::sub::{
+        actor_values::ActorValue,
+        script::{Reference, SLSDFlags, Script},
+    },
+    shared::FormId,
+};
+
+use super::{
+    ast::{
+        BinaryOp, Block, Branch, Call, Expr, ScriptAst, Span, Statement, StatementKind, UnaryOp,
+        VariableDecl, VariableRef, VariableType,
+    },
+    functions::{self, FunctionDef, ParamType},
+};
+
+const OP_BEGIN: u16 = 0x10;
+const OP_END: u16 = 0x11;
+const OP_SET: u16 = 0x15;
+const OP_IF: u16 = 0x16;
+const OP_ELSE: u16 = 0x17;
+const OP_ELSE_IF: u16 = 0x18;
+const OP_END_IF: u16 = 0x19;
+/// Prefix of a function call made on a reference, unlike other
+/// statements this is followed by the reference index instead of a length
+const OP_REFERENCE: u16 = 0x1C;
+const OP_RETURN: u16 = 0x1E;
+
+/// Block types by their compiled value
+pub static BLOCK_TYPES: &[(u16, &str)] = &[
+    (0x00, "GameMode"),
+    (0x01, "MenuMode"),
+    (0x02, "OnActivate"),
+    (0x03, "OnAdd"),
+    (0x04, "OnEquip"),
+    (0x05, "OnUnequip"),
+    (0x06, "OnDrop"),
+    (0x07, "SayToDone"),
+    (0x08, "OnHit"),
+    (0x09, "OnHitWith"),
+    (0x0A, "OnDeath"),
+    (0x0B, "OnMurder"),
+    (0x0C, "OnCombatEnd"),
+    (0x0D, "Function"),
+    (0x0F, "OnPackageStart"),
+    (0x10, "OnPackageDone"),
+    (0x11, "ScriptEffectStart"),
+    (0x12, "ScriptEffectFinish"),
+    (0x13, "ScriptEffectUpdate"),
+    (0x14, "OnPackageChange"),
+    (0x15, "OnLoad"),
+    (0x16, "OnMagicEffectHit"),
+    (0x17, "OnSell"),
+    (0x18, "OnTrigger"),
+    (0x19, "OnStartCombat"),
+    (0x1A, "OnTriggerEnter"),
+    (0x1B, "OnTriggerLeave"),
+    (0x1C, "OnActorEquip"),
+    (0x1D, "OnActorUnequip"),
+    (0x1E, "OnReset"),
+    (0x1F, "OnOpen"),
+    (0x20, "OnClose"),
+    (0x21, "OnGrab"),
+    (0x22, "OnRelease"),
+    (0x23, "OnDestructionStageChange"),
+    (0x24, "OnFire"),
+    (0x25, "OnNPCActivate"),
+];
+
+/// Finds the name of the compiled block type
+pub fn block_name(ty: u16) -> Option<&'static str> {
+    BLOCK_TYPES
+        .iter()
+        .find(|(value, _)| *value == ty)
+        .map(|(_, name)| *name)
+}
+
+#[derive(Debug, Error)]
+pub enum DecompileError {
+    #[error("Unexpected end of bytecode at {0:#x}")]
+    UnexpectedEnd(usize),
+    #[error("Unknown opcode {opcode:#06x} at {offset:#x}")]
+    UnknownOpcode { opcode: u16, offset: usize },
+    #[error("Unknown block type {ty:#x} at {offset:#x}")]
+    UnknownBlockType { ty: u16, offset: usize },
+    #[error("Unknown reference index {index} at {offset:#x}")]
+    UnknownReference { index: u16, offset: usize },
+    #[error("Invalid token {token:#04x} at {offset:#x}")]
+    InvalidToken { token: u8, offset: usize },
+    #[error("Invalid expression at {0:#x}")]
+    InvalidExpression(usize),
+    #[error("Unexpected {what} at {offset:#x}")]
+    Unexpected { what: &'static str, offset: usize },
+}
+
+type DecompileResult<T> = Result<T, DecompileError>;
+
+/// Provides names for the forms referenced by scripts, implemented
+/// for closures looking up editor IDs
+pub trait NameResolver {
+    /// Editor ID of the form
+    fn form_name(&self, form_id: &FormId) -> Option<String>;
+
+    /// Name of the variable with the index in the script of the form
+    fn variable_name(&self, _form_id: &FormId, _index: u32) -> Option<String> {
+        None
+    }
+}
+
+impl<F> NameResolver for F
+where
+    F: Fn(&FormId) -> Option<String>,
+{
+    fn form_name(&self, form_id: &FormId) -> Option<String> {
+        self(form_id)
+    }
+}
+
+/// Decompiles the compiled data of the script
+pub fn decompile<R: NameResolver>(script: &Script, resolver: &R) -> DecompileResult<ScriptAst> {
+    Decompiler { script, resolver }.decompile()
+}
+
+/// Cursor over a section of the bytecode, keeping track of the
+/// absolute offset for error reporting
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    fn bytes(&mut self, length: usize) -> DecompileResult<&'a [u8]> {
+        if self.data.len() < length {
+            return Err(DecompileError::UnexpectedEnd(self.offset + self.data.len()));
+        }
+        let (bytes, data) = self.data.split_at(length);
+        self.data = data;
+        self.offset += length;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> DecompileResult<[u8; N]> {
+        let mut out = [0; N];
+        out.copy_from_slice(self.bytes(N)?);
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> DecompileResult<u8> {
+        self.array::<1>().map(|[value]| value)
+    }
+
+    fn u16(&mut self) -> DecompileResult<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> DecompileResult<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> DecompileResult<i32> {
+        self.array().map(i32::from_le_bytes)
+    }
+
+    fn f64(&mut self) -> DecompileResult<f64> {
+        self.array().map(f64::from_le_bytes)
+    }
+
+    /// Splits off a reader over the next `length` bytes
+    fn take(&mut self, length: usize) -> DecompileResult<Reader<'a>> {
+        let offset = self.offset;
+        let data = self.bytes(length)?;
+        Ok(Reader { data, offset })
+    }
+
+    /// Splits off a reader over the data of a u16 length prefixed section
+    fn section(&mut self) -> DecompileResult<Reader<'a>> {
+        let length = self.u16()?;
+        self.take(length as usize)
+    }
+}
+
+/// `if` statement being decompiled
+struct OpenIf {
+    branches: Vec<Branch>,
+    /// Condition of the current branch, [`None`] within the `else`
+    condition: Option<Expr>,
+    start: usize,
+}
+
+struct Decompiler<'a, R> {
+    script: &'a Script,
+    resolver: &'a R,
+}
+
+impl<'a, R: NameResolver> Decompiler<'a, R> {
+    fn decompile(&self) -> DecompileResult<ScriptAst> {
+        let mut ast = ScriptAst {
+            name: None,
+            variables: self.variables(),
+            blocks: Vec::new(),
+        };
+
+        let mut reader = Reader {
+            data: &self.script.compiled_source,
+            offset: 0,
+        };
+
+        // Block being decompiled with the statement lists of the if
+        // statements nested within it
+        let mut block: Option<Block> = None;
+        let mut bodies: Vec<Vec<Statement>> = Vec::new();
+        let mut ifs: Vec<OpenIf> = Vec::new();
+
+        while !reader.is_empty() {
+            let start = reader.offset;
+            let opcode = reader.u16()?;
+
+            let kind = match opcode {
+                OP_BEGIN => {
+                    if block.is_some() {
+                        return Err(unexpected("begin within a block", start));
+                    }
+                    let mut data = reader.section()?;
+                    let ty = data.u16()?;
+                    let name = block_name(ty)
+                        .ok_or(DecompileError::UnknownBlockType { ty, offset: start })?;
+                    let _block_length = data.u32()?;
+                    let args = self.params(&mut data, None)?;
+
+                    block = Some(Block {
+                        name: name.to_string(),
+                        args,
+                        body: Vec::new(),
+                        span: Span::new(start, start),
+                    });
+                    bodies.push(Vec::new());
+                    continue;
+                }
+                OP_END => {
+                    reader.section()?;
+                    let mut block = block
+                        .take()
+                        .ok_or_else(|| unexpected("end outside of a block", start))?;
+                    if !ifs.is_empty() {
+                        return Err(unexpected("end within an if", start));
+                    }
+                    block.body = bodies.pop().unwrap_or_default();
+                    block.span.end = reader.offset;
+                    ast.blocks.push(block);
+                    continue;
+                }
+                OP_IF => {
+                    let mut data = reader.section()?;
+                    let _jump = data.u16()?;
+                    let condition = self.expression(&mut data)?;
+                    ifs.push(OpenIf {
+                        branches: Vec::new(),
+                        condition: Some(condition),
+                        start,
+                    });
+                    bodies.push(Vec::new());
+                    continue;
+                }
+                OP_ELSE_IF | OP_ELSE => {
+                    let mut data = reader.section()?;
+                    let _jump = data.u16()?;
+                    let next = if opcode == OP_ELSE_IF {
+                        Some(self.expression(&mut data)?)
+                    } else {
+                        None
+                    };
+
+                    let open = ifs.last_mut().ok_or_else(|| unexpected("else", start))?;
+                    let condition = open
+                        .condition
+                        .take()
+                        .ok_or_else(|| unexpected("else after else", start))?;
+                    let body = bodies.pop().unwrap_or_default();
+                    open.branches.push(Branch { condition, body });
+                    open.condition = next;
+                    bodies.push(Vec::new());
+                    continue;
+                }
+                OP_END_IF => {
+                    reader.section()?;
+                    let mut open = ifs.pop().ok_or_else(|| unexpected("endif", start))?;
+                    let body = bodies.pop().unwrap_or_default();
+                    let otherwise = match open.condition.take() {
+                        Some(condition) => {
+                            open.branches.push(Branch { condition, body });
+                            None
+                        }
+                        None => Some(body),
+                    };
+
+                    let statement = Statement {
+                        kind: StatementKind::If {
+                            branches: open.branches,
+                            otherwise,
+                        },
+                        span: Span::new(open.start, reader.offset),
+                    };
+                    push_statement(&mut bodies, statement, start)?;
+                    continue;
+                }
+                OP_SET => {
+                    let mut data = reader.section()?;
+                    let target = self.set_target(&mut data)?;
+                    let value = self.expression(&mut data)?;
+                    StatementKind::Set { target, value }
+                }
+                OP_RETURN => {
+                    reader.section()?;
+                    StatementKind::Return
+                }
+                OP_REFERENCE => {
+                    let reference = self.reference_name(reader.u16()?, start)?;
+                    let opcode = reader.u16()?;
+                    StatementKind::Call(self.call(&mut reader, opcode, Some(reference), start)?)
+                }
+                opcode if opcode >= FunctionDef::OPCODE_BASE => {
+                    StatementKind::Call(self.call(&mut reader, opcode, None, start)?)
+                }
+                // Script name and variable declarations
+                0x12..=0x14 | 0x1D | 0x1F => {
+                    reader.section()?;
+                    continue;
+                }
+                opcode => {
+                    return Err(DecompileError::UnknownOpcode {
+                        opcode,
+                        offset: start,
+                    })
+                }
+            };
+
+            let statement = Statement {
+                kind,
+                span: Span::new(start, reader.offset),
+            };
+            push_statement(&mut bodies, statement, start)?;
+        }
+
+        if block.is_some() {
+            return Err(DecompileError::UnexpectedEnd(reader.offset));
+        }
+
+        Ok(ast)
+    }
+
+    /// Declarations for the local variables of the script
+    fn variables(&self) -> Vec<VariableDecl> {
+        let mut variables: Vec<_> = self.script.local_variables.iter().collect();
+        variables.sort_by_key(|variable| variable.data.index);
+
+        variables
+            .into_iter()
+            .map(|variable| {
+                let index = variable.data.index;
+                let is_reference = self.script.references.iter().any(
+                    |reference| matches!(reference, Reference::Variable(value) if *value == index),
+                );
+
+                let ty = if is_reference {
+                    VariableType::Ref
+                } else if variable.data.flags.contains(SLSDFlags::ENABLED) {
+                    VariableType::Short
+                } else {
+                    VariableType::Float
+                };
+
+                VariableDecl {
+                    ty,
+                    name: variable.name.clone(),
+                    span: Span::default(),
+                }
+            })
+            .collect()
+    }
+
+    fn local_name(&self, index: u32) -> String {
+        self.script
+            .local_variables
+            .iter()
+            .find(|variable| variable.data.index == index)
+            .map(|variable| variable.name.clone())
+            .unwrap_or_else(|| format!("var{index}"))
+    }
+
+    /// Name of the reference with the 1-based index within the script references
+    fn reference_name(&self, index: u16, offset: usize) -> DecompileResult<String> {
+        let reference = (index as usize)
+            .checked_sub(1)
+            .and_then(|index| self.script.references.get(index))
+            .ok_or(DecompileError::UnknownReference { index, offset })?;
+
+        Ok(match reference {
+            Reference::Object(form_id) => self
+                .resolver
+                .form_name(form_id)
+                .unwrap_or_else(|| format!("FormId_{:08X}", form_id.0)),
+            Reference::Variable(index) => self.local_name(*index),
+        })
+    }
+
+    /// Name of a variable within the script of another form
+    fn remote_variable_name(&self, reference: u16, index: u16) -> String {
+        let form_id = (reference as usize)
+            .checked_sub(1)
+            .and_then(|reference| self.script.references.get(reference));
+
+        match form_id {
+            Some(Reference::Object(form_id)) => self.resolver.variable_name(form_id, index as u32),
+            _ => None,
+        }
+        .unwrap_or_else(|| format!("var{index}"))
+    }
+
+    /// Variable assigned by a `set` statement
+    fn set_target(&self, data: &mut Reader) -> DecompileResult<VariableRef> {
+        let offset = data.offset;
+        match data.u8()? {
+            b's' | b'l' | b'f' => Ok(VariableRef {
+                reference: None,
+                name: self.local_name(data.u16()? as u32),
+            }),
+            b'G' => Ok(VariableRef {
+                reference: None,
+                name: self.reference_name(data.u16()?, offset)?,
+            }),
+            b'r' => {
+                let reference_index = data.u16()?;
+                let reference = self.reference_name(reference_index, offset)?;
+                let offset = data.offset;
+                match data.u8()? {
+                    b's' | b'l' | b'f' => Ok(VariableRef {
+                        reference: Some(reference),
+                        name: self.remote_variable_name(reference_index, data.u16()?),
+                    }),
+                    token => Err(DecompileError::InvalidToken { token, offset }),
+                }
+            }
+            token => Err(DecompileError::InvalidToken { token, offset }),
+        }
+    }
+
+    /// Function call following its opcode
+    fn call(
+        &self,
+        reader: &mut Reader,
+        opcode: u16,
+        reference: Option<String>,
+        start: usize,
+    ) -> DecompileResult<Call> {
+        let function = functions::by_opcode(opcode);
+        let mut data = reader.section()?;
+        let args = self.params(&mut data, function)?;
+
+        Ok(Call {
+            reference,
+            function: match function {
+                Some(function) => function.name.to_string(),
+                None => format!("Function{opcode:#06X}"),
+            },
+            args,
+            span: Span::new(start, reader.offset),
+        })
+    }
+
+    /// Count prefixed parameters, decoded using the parameter types of the
+    /// function when known
+    fn params(
+        &self,
+        data: &mut Reader,
+        function: Option<&FunctionDef>,
+    ) -> DecompileResult<Vec<Expr>> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let count = data.u16()?;
+        (0..count as usize)
+            .map(|index| {
+                let ty = function
+                    .and_then(|function| function.params.get(index))
+                    .map(|param| param.ty);
+                self.param(data, ty)
+            })
+            .collect()
+    }
+
+    fn param(&self, data: &mut Reader, ty: Option<ParamType>) -> DecompileResult<Expr> {
+        Ok(match ty {
+            Some(ParamType::String) => {
+                let length = data.u16()?;
+                let bytes = data.bytes(length as usize)?;
+                Expr::String(String::from_utf8_lossy(bytes).to_string())
+            }
+            Some(ParamType::Axis) => Expr::Identifier((data.u8()? as char).to_string()),
+            Some(ParamType::ActorValue) => {
+                let value = data.u16()?;
+                let actor_value = i8::try_from(value)
+                    .ok()
+                    .and_then(|value| ActorValue::try_from(value).ok());
+                match actor_value {
+                    Some(actor_value) => Expr::Identifier(format!("{actor_value:?}")),
+                    None => Expr::Int(value as i32),
+                }
+            }
+            Some(ParamType::Sex) => Expr::Identifier(
+                match data.u16()? {
+                    0 => "Male",
+                    _ => "Female",
+                }
+                .to_string(),
+            ),
+            Some(
+                ParamType::AnimationGroup
+                | ParamType::CrimeType
+                | ParamType::FormType
+                | ParamType::MiscStat
+                | ParamType::Alignment
+                | ParamType::EquipType
+                | ParamType::CreatureType
+                | ParamType::BodyLocation,
+            ) => Expr::Int(data.u16()? as i32),
+            _ => self.value(data)?,
+        })
+    }
+
+    /// Value prefixed by a byte identifying its type
+    fn value(&self, data: &mut Reader) -> DecompileResult<Expr> {
+        let offset = data.offset;
+        Ok(match data.u8()? {
+            b'n' => Expr::Int(data.i32()?),
+            b'z' => Expr::Float(data.f64()?),
+            b's' | b'l' | b'f' => Expr::Variable(VariableRef {
+                reference: None,
+                name: self.local_name(data.u16()? as u32),
+            }),
+            b'G' => Expr::Variable(VariableRef {
+                reference: None,
+                name: self.reference_name(data.u16()?, offset)?,
+            }),
+            b'r' | b'Z' => Expr::Identifier(self.reference_name(data.u16()?, offset)?),
+            token => return Err(DecompileError::InvalidToken { token, offset }),
+        })
+    }
+
+    /// Length prefixed expression stored in reverse polish notation
+    fn expression(&self, reader: &mut Reader) -> DecompileResult<Expr> {
+        let mut data = reader.section()?;
+        let start = data.offset;
+        let mut stack: Vec<Expr> = Vec::new();
+
+        while let Some(token) = data.peek() {
+            let offset = data.offset;
+            let next = data.data.get(1).copied();
+
+            match token {
+                b' ' => {
+                    data.u8()?;
+                }
+                b'0'..=b'9' | b'.' => stack.push(number(&mut data)?),
+                b'-' if next.is_some_and(|next| next.is_ascii_digit()) => {
+                    stack.push(number(&mut data)?)
+                }
+                b'r' => {
+                    data.u8()?;
+                    let reference_index = data.u16()?;
+                    let reference = self.reference_name(reference_index, offset)?;
+                    let expr = match data.peek() {
+                        Some(b's' | b'l' | b'f') => {
+                            data.u8()?;
+                            Expr::Variable(VariableRef {
+                                reference: Some(reference),
+                                name: self.remote_variable_name(reference_index, data.u16()?),
+                            })
+                        }
+                        Some(b'X') => {
+                            data.u8()?;
+                            let opcode = data.u16()?;
+                            Expr::Call(self.call(&mut data, opcode, Some(reference), offset)?)
+                        }
+                        _ => Expr::Identifier(reference),
+                    };
+                    stack.push(expr);
+                }
+                b'X' => {
+                    data.u8()?;
+                    let opcode = data.u16()?;
+                    stack.push(Expr::Call(self.call(&mut data, opcode, None, offset)?));
+                }
+                b'n' | b'z' | b's' | b'l' | b'f' | b'G' | b'Z' => {
+                    stack.push(self.value(&mut data)?)
+                }
+                _ => {
+                    let length = data
+                        .data
+                        .iter()
+                        .take_while(|byte| b"&|=!<>+-*/~".contains(byte))
+                        .count();
+                    if length == 0 {
+                        return Err(DecompileError::InvalidToken { token, offset });
+                    }
+                    let symbol = String::from_utf8_lossy(data.bytes(length)?).to_string();
+
+                    if symbol == "~" {
+                        let operand = stack
+                            .pop()
+                            .ok_or(DecompileError::InvalidExpression(offset))?;
+                        stack.push(Expr::Unary {
+                            op: UnaryOp::Neg,
+                            operand: Box::new(operand),
+                        });
+                        continue;
+                    }
+
+                    let op = BinaryOp::from_symbol(&symbol)
+                        .ok_or(DecompileError::InvalidToken { token, offset })?;
+                    let rhs = stack
+                        .pop()
+                        .ok_or(DecompileError::InvalidExpression(offset))?;
+                    let lhs = stack
+                        .pop()
+                        .ok_or(DecompileError::InvalidExpression(offset))?;
+                    stack.push(Expr::Binary {
+                        op,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    });
+                }
+            }
+        }
+
+        match (stack.pop(), stack.is_empty()) {
+            (Some(expr), true) => Ok(expr),
+            _ => Err(DecompileError::InvalidExpression(start)),
+        }
+    }
+}
+
+fn unexpected(what: &'static str, offset: usize) -> DecompileError {
+    DecompileError::Unexpected { what, offset }
+}
+
+/// Adds a statement to the innermost open statement list
+fn push_statement(
+    bodies: &mut [Vec<Statement>],
+    statement: Statement,
+    offset: usize,
+) -> DecompileResult<()> {
+    bodies
+        .last_mut()
+        .ok_or_else(|| unexpected("statement outside of a block", offset))?
+        .push(statement);
+    Ok(())
+}
+
+/// Number literal stored as text within an expression
+fn number(data: &mut Reader) -> DecompileResult<Expr> {
+    let offset = data.offset;
+    let length = data
+        .data
+        .iter()
+        .enumerate()
+        .take_while(|(index, byte)| {
+            byte.is_ascii_digit() || **byte == b'.' || (*index == 0 && **byte == b'-')
+        })
+        .count();
+    let text = String::from_utf8_lossy(data.bytes(length)?).to_string();
+
+    if let Ok(value) = text.parse::<i32>() {
+        return Ok(Expr::Int(value));
+    }
+    text.parse::<f64>()
+        .map(Expr::Float)
+        .map_err(|_| DecompileError::InvalidExpression(offset))
+}
+
+/// Whether the decompiled script matches the source text, ignoring formatting,
+/// case, comments, parentheses, declarations and function short names. Used to
+/// check decompiled scripts against the source embedded in vanilla scripts
+pub fn matches_source(ast: &ScriptAst, source: &str) -> bool {
+    normalize_source(&ast.to_string()) == normalize_source(source)
+}
+
+/// Splits the statements of the source into comparable tokens
+fn normalize_source(source: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+
+    for line in source.lines() {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        let first = line.split_whitespace().next().unwrap_or_default();
+        let is_header = ["scn", "scriptname"]
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(first));
+        if is_header || VariableType::from_keyword(first).is_some() {
+            continue;
+        }
+
+        let mut chars = line.char_indices().peekable();
+        while let Some((start, char)) = chars.next() {
+            let mut end = start + char.len_utf8();
+            let mut consume = |predicate: &dyn Fn(char) -> bool| {
+                while let Some(&(index, char)) = chars.peek() {
+                    if !predicate(char) {
+                        break;
+                    }
+                    end = index + char.len_utf8();
+                    chars.next();
+                }
+            };
+
+            if char.is_whitespace() || char == '(' || char == ')' {
+                continue;
+            } else if char.is_ascii_digit() {
+                consume(&|char| char.is_ascii_digit() || char == '.');
+            } else if char.is_alphanumeric() || char == '_' {
+                consume(&|char| char.is_alphanumeric() || char == '_');
+            } else if char == '"' {
+                consume(&|char| char != '"');
+                end += chars.next().map_or(0, |(_, char)| char.len_utf8());
+            } else {
+                consume(&|char| "&|=!<>".contains(char));
+            }
+
+            let token = &line[start..end];
+            let token = if let Ok(value) = token.parse::<f64>() {
+                value.to_string()
+            } else if let Some(function) = functions::by_name(token) {
+                function.name.to_ascii_lowercase()
+            } else {
+                token.to_ascii_lowercase()
+            };
+            tokens.push(token);
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decompile, matches_source};
+    use crate::esp::{
+        record::sub::script::{
+            LocalVariable, Reference, SCHRFlags, SCHRType, SLSDFlags, Script, SCHR, SLSD,
+        },
+        shared::FormId,
+    };
+
+    const SOURCE: &str = r#"scn TestScript
+
+short doOnce
+
+begin OnAdd player
+    if ( doOnce == 0 && GetStage MQ01 >= 10 )
+        player.additem Caps001 5 ; Reward
+        set doOnce to 1
+    else
+        return
+    endif
+end
+"#;
+
+    /// Statement with a u16 length prefixed body
+    fn statement(opcode: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = opcode.to_le_bytes().to_vec();
+        out.extend((body.len() as u16).to_le_bytes());
+        out.extend(body);
+        out
+    }
+
+    fn expression(tokens: &[&[u8]]) -> Vec<u8> {
+        let data: Vec<u8> = tokens
+            .iter()
+            .flat_map(|token| [b" ", *token].concat())
+            .collect();
+        [(data.len() as u16).to_le_bytes().to_vec(), data].concat()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let get_stage = statement(0x103A, &[1, 0, b'r', 2, 0]);
+        let condition = expression(&[
+            &[b's', 1, 0],
+            b"0",
+            b"==",
+            &[b"X", get_stage.as_slice()].concat(),
+            b"10",
+            b">=",
+            b"&&",
+        ]);
+
+        let bytecode = [
+            statement(0x1D, &[]),
+            statement(0x10, &[3, 0, 0, 0, 0, 0, 1, 0, b'r', 1, 0]),
+            statement(0x16, &[[0, 0].as_slice(), &condition].concat()),
+            vec![0x1C, 0, 1, 0],
+            statement(0x1002, &[2, 0, b'r', 3, 0, b'n', 5, 0, 0, 0]),
+            statement(
+                0x15,
+                &[[b's', 1, 0].as_slice(), &expression(&[b"1"])].concat(),
+            ),
+            statement(0x17, &[0, 0]),
+            statement(0x1E, &[]),
+            statement(0x19, &[]),
+            statement(0x11, &[]),
+        ]
+        .concat();
+
+        let script = Script {
+            basic_data: SCHR {
+                ref_count: 3,
+                compiled_size: bytecode.len() as u32,
+                variable_count: 1,
+                ty: SCHRType::Object,
+                flags: SCHRFlags::ENABLED,
+            },
+            compiled_source: bytecode,
+            source: SOURCE.to_string(),
+            local_variables: vec![LocalVariable {
+                data: SLSD {
+                    index: 1,
+                    flags: SLSDFlags::ENABLED,
+                },
+                name: "doOnce".to_string(),
+            }],
+            references: vec![
+                Reference::Object(FormId(0x14)),
+                Reference::Object(FormId(0x100)),
+                Reference::Object(FormId(0xF)),
+            ],
+        };
+
+        let resolver = |form_id: &FormId| {
+            Some(
+                match form_id.0 {
+                    0x14 => "player",
+                    0x100 => "MQ01",
+                    _ => "Caps001",
+                }
+                .to_string(),
+            )
+        };
+
+        let ast = decompile(&script, &resolver).unwrap();
+        assert_eq!(ast.variables.len(), 1);
+        assert_eq!(ast.blocks[0].name, "OnAdd");
+        assert!(
+            matches_source(&ast, &script.source),
+            "decompiled source differs:\n{ast}"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Evaluation of `CTDA` conditions used by dialogue, packages, perks,
+//! messages and terminals
+
+use crate::esp::{
+    record::sub::{
+        actor_values::ActorValue,
+        condition::{ComparisonValue, ConditionFlags, RunOn, CTDA},
+    },
+    shared::FormId,
+};
+
+use super::functions::{self, FunctionDef, ParamType};
+
+/// Decoded parameter of a condition function
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionParam {
+    Int(i32),
+    Float(f32),
+    Form(FormId),
+    ActorValue(ActorValue),
+    /// One of `X`, `Y` or `Z`
+    Axis(char),
+}
+
+impl ConditionParam {
+    /// Decodes the raw parameter based on the type expected by the function
+    pub fn decode(ty: ParamType, raw: [u8; 4]) -> Self {
+        let value = u32::from_le_bytes(raw);
+        match ty {
+            ty if ty.is_form() => Self::Form(FormId(value)),
+            ParamType::Float => Self::Float(f32::from_le_bytes(raw)),
+            ParamType::ActorValue => ActorValue::try_from(value as i8)
+                .map(Self::ActorValue)
+                .unwrap_or(Self::Int(value as i32)),
+            ParamType::Axis => char::from_u32(value)
+                .filter(char::is_ascii_alphabetic)
+                .map(Self::Axis)
+                .unwrap_or(Self::Int(value as i32)),
+            _ => Self::Int(value as i32),
+        }
+    }
+}
+
+/// Condition with its function and parameters resolved
+#[derive(Debug)]
+pub struct Condition<'a> {
+    pub ctda: &'a CTDA,
+    pub function: &'static FunctionDef,
+    pub params: Vec<ConditionParam>,
+}
+
+impl<'a> Condition<'a> {
+    /// Resolves the function of the condition, `None` if the index doesn't
+    /// refer to a known condition function
+    pub fn resolve(ctda: &'a CTDA) -> Option<Self> {
+        let function = functions::condition_by_index(ctda.func_index)?;
+
+        let params = function
+            .params
+            .iter()
+            .zip([ctda.param_1, ctda.param_2])
+            .map(|(param, raw)| ConditionParam::decode(param.ty, raw))
+            .collect();
+
+        Some(Self {
+            ctda,
+            function,
+            params,
+        })
+    }
+
+    /// The reference the function should run on, `None` for the subject
+    pub fn reference(&self) -> Option<&FormId> {
+        match self.ctda.run_on {
+            RunOn::Reference if !self.ctda.reference.is_null() => Some(&self.ctda.reference),
+            _ => None,
+        }
+    }
+}
+
+/// Game state conditions are evaluated against
+pub trait ConditionContext {
+    /// Calls the condition function on the object selected by `run_on`,
+    /// `None` if the function isn't supported or the object doesn't exist
+    fn call(&mut self, run_on: RunOn, condition: &Condition) -> Option<f32>;
+
+    /// Value of the GLOB record
+    fn global(&self, form_id: &FormId) -> Option<f32>;
+}
+
+/// Evaluates a single condition, conditions which can't be evaluated fail
+pub fn evaluate<C: ConditionContext + ?Sized>(ctda: &CTDA, context: &mut C) -> bool {
+    let Some(condition) = Condition::resolve(ctda) else {
+        return false;
+    };
+
+    let expected = match ctda.comparison() {
+        ComparisonValue::Value(value) => Some(value),
+        ComparisonValue::Global(form_id) => context.global(&form_id),
+    };
+    let Some(expected) = expected else {
+        return false;
+    };
+
+    context
+        .call(ctda.run_on, &condition)
+        .is_some_and(|value| ctda.operator.compare(value, expected))
+}
+
+/// Evaluates a list of conditions. Conditions flagged with OR are combined
+/// with the next condition before the AND, so `A OR B AND C` is `(A OR B) AND C`.
+/// An empty list always passes
+pub fn evaluate_all<C: ConditionContext + ?Sized>(conditions: &[CTDA], context: &mut C) -> bool {
+    let mut group = false;
+
+    for (index, ctda) in conditions.iter().enumerate() {
+        group = group || evaluate(ctda, context);
+
+        // An OR flag on the last condition has nothing to combine with
+        let combine_or = ctda.flags.contains(ConditionFlags::COMBINE_OR);
+        if !combine_or || index + 1 == conditions.len() {
+            if !group {
+                return false;
+            }
+            group = false;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use crate::esp::{
+        record::sub::condition::{
+            ComparisonOperator, ConditionFlags, RawComparisonValue, RunOn, CTDA,
+        },
+        shared::FormId,
+    };
+
+    use super::{evaluate_all, Condition, ConditionContext, ConditionParam};
+
+    /// Returns the first parameter of `GetGlobalValue` as the result
+    struct Context;
+
+    impl ConditionContext for Context {
+        fn call(&mut self, _run_on: RunOn, condition: &Condition) -> Option<f32> {
+            match condition.params.first() {
+                Some(ConditionParam::Form(FormId(value))) => Some(*value as f32),
+                _ => None,
+            }
+        }
+
+        fn global(&self, form_id: &FormId) -> Option<f32> {
+            Some(form_id.0 as f32 * 10.0)
+        }
+    }
+
+    fn ctda(value: u32, flags: ConditionFlags) -> CTDA {
+        CTDA {
+            operator: ComparisonOperator::EqualTo,
+            flags,
+            comparison_value: RawComparisonValue(1f32.to_le_bytes()),
+            func_index: 74,
+            param_1: value.to_le_bytes(),
+            param_2: [0; 4],
+            run_on: RunOn::Subject,
+            reference: FormId::NULL,
+        }
+    }
+
+    #[test]
+    fn test_precedence() {
+        let pass = || ctda(1, ConditionFlags::empty());
+        let fail = || ctda(0, ConditionFlags::empty());
+        let or = |ctda: CTDA| CTDA {
+            flags: ConditionFlags::COMBINE_OR,
+            ..ctda
+        };
+
+        assert!(evaluate_all(&[], &mut Context));
+        // (fail OR pass) AND pass
+        assert!(evaluate_all(&[or(fail()), pass(), pass()], &mut Context));
+        // fail AND (fail OR pass) would pass with left to right evaluation
+        assert!(!evaluate_all(&[fail(), or(fail()), pass()], &mut Context));
+        // pass AND (fail OR fail)
+        assert!(!evaluate_all(&[pass(), or(fail()), fail()], &mut Context));
+
+        let global = CTDA {
+            flags: ConditionFlags::USE_GLOBAL,
+            comparison_value: RawComparisonValue(2u32.to_le_bytes()),
+            ..ctda(20, ConditionFlags::empty())
+        };
+        assert!(evaluate_all(&[global], &mut Context));
+    }
+}
@@ -0,0 +1,533 @@
+/// Type of a parameter accepted by a script function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    String,
+    Integer,
+    Float,
+    /// Placed reference (e.g. `player`)
+    ObjectRef,
+    /// Placed reference to an actor
+    Actor,
+    /// Any other form such as items, quests or factions
+    Form,
+    ActorValue,
+    /// One of `X`, `Y` or `Z`
+    Axis,
+    Sex,
+    AnimationGroup,
+    CrimeType,
+    FormType,
+    MiscStat,
+    Alignment,
+    EquipType,
+    CreatureType,
+    BodyLocation,
+    /// Name of a variable within another script
+    VariableName,
+}
+
+impl ParamType {
+    /// Whether the parameter accepts a form or reference
+    pub fn is_form(self) -> bool {
+        matches!(self, Self::ObjectRef | Self::Actor | Self::Form)
+    }
+
+    /// Whether the parameter accepts a number
+    pub fn is_numeric(self) -> bool {
+        matches!(self, Self::Integer | Self::Float)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    pub ty: ParamType,
+    pub optional: bool,
+}
+
+impl Param {
+    pub const fn required(ty: ParamType) -> Self {
+        Self {
+            ty,
+            optional: false,
+        }
+    }
+
+    pub const fn optional(ty: ParamType) -> Self {
+        Self { ty, optional: true }
+    }
+}
+
+/// Definition of a function callable from scripts
+#[derive(Debug)]
+pub struct FunctionDef {
+    /// Index of the function, compiled scripts use `0x1000 + index` as
+    /// the opcode while conditions use the index directly
+    pub index: u16,
+    pub name: &'static str,
+    /// Alternative short name (e.g. `GetAV` for `GetActorValue`)
+    pub short_name: Option<&'static str>,
+    pub params: &'static [Param],
+    /// Whether the function can be used by conditions, commands with side
+    /// effects can only be called from scripts
+    pub condition: bool,
+}
+
+impl FunctionDef {
+    /// Opcode of the first function, opcodes below are statements
+    pub const OPCODE_BASE: u16 = 0x1000;
+
+    const fn new(index: u16, name: &'static str, params: &'static [Param]) -> Self {
+        Self {
+            index,
+            name,
+            short_name: None,
+            params,
+            condition: true,
+        }
+    }
+
+    const fn command(index: u16, name: &'static str, params: &'static [Param]) -> Self {
+        Self {
+            condition: false,
+            ..Self::new(index, name, params)
+        }
+    }
+
+    const fn short(self, short_name: &'static str) -> Self {
+        Self {
+            short_name: Some(short_name),
+            ..self
+        }
+    }
+
+    pub fn opcode(&self) -> u16 {
+        Self::OPCODE_BASE + self.index
+    }
+
+    /// Whether the function is known by the name, ignoring case
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .short_name
+                .is_some_and(|short_name| short_name.eq_ignore_ascii_case(name))
+    }
+
+    /// Number of parameters that must be provided
+    pub fn required_params(&self) -> usize {
+        self.params.iter().filter(|param| !param.optional).count()
+    }
+}
+
+/// Finds the function with the provided index
+pub fn by_index(index: u16) -> Option<&'static FunctionDef> {
+    FUNCTIONS
+        .binary_search_by_key(&index, |function| function.index)
+        .ok()
+        .map(|position| &FUNCTIONS[position])
+}
+
+/// Finds the condition function with the provided index, as stored in `CTDA`
+pub fn condition_by_index(index: u16) -> Option<&'static FunctionDef> {
+    by_index(index).filter(|function| function.condition)
+}
+
+/// Finds the function compiled to the provided opcode
+pub fn by_opcode(opcode: u16) -> Option<&'static FunctionDef> {
+    opcode
+        .checked_sub(FunctionDef::OPCODE_BASE)
+        .and_then(by_index)
+}
+
+/// Finds the function with the provided name or short name, ignoring case
+pub fn by_name(name: &str) -> Option<&'static FunctionDef> {
+    FUNCTIONS.iter().find(|function| function.is_named(name))
+}
+
+const STRING: Param = Param::required(ParamType::String);
+const INTEGER: Param = Param::required(ParamType::Integer);
+const FLOAT: Param = Param::required(ParamType::Float);
+const OBJECT_REF: Param = Param::required(ParamType::ObjectRef);
+const ACTOR: Param = Param::required(ParamType::Actor);
+const FORM: Param = Param::required(ParamType::Form);
+const ACTOR_VALUE: Param = Param::required(ParamType::ActorValue);
+const AXIS: Param = Param::required(ParamType::Axis);
+
+const OPT_INTEGER: Param = Param::optional(ParamType::Integer);
+const OPT_FLOAT: Param = Param::optional(ParamType::Float);
+const OPT_OBJECT_REF: Param = Param::optional(ParamType::ObjectRef);
+const OPT_ACTOR: Param = Param::optional(ParamType::Actor);
+const OPT_FORM: Param = Param::optional(ParamType::Form);
+
+/// Message followed by the values substituted into its text
+const MESSAGE_PARAMS: &[Param] = &[
+    FORM, OPT_FLOAT, OPT_FLOAT, OPT_FLOAT, OPT_FLOAT, OPT_FLOAT, OPT_FLOAT, OPT_FLOAT, OPT_FLOAT,
+    OPT_FLOAT,
+];
+
+/// Known script functions sorted by index
+#[rustfmt::skip]
+pub static FUNCTIONS: &[FunctionDef] = &[
+    FunctionDef::new(1, "GetDistance", &[OBJECT_REF]),
+    FunctionDef::command(2, "AddItem", &[FORM, INTEGER, OPT_INTEGER]),
+    FunctionDef::command(3, "SetEssential", &[FORM, INTEGER]),
+    FunctionDef::command(4, "Rotate", &[AXIS, FLOAT]),
+    FunctionDef::new(5, "GetLocked", &[]),
+    FunctionDef::new(6, "GetPos", &[AXIS]),
+    FunctionDef::command(7, "SetPos", &[AXIS, FLOAT]),
+    FunctionDef::new(8, "GetAngle", &[AXIS]),
+    FunctionDef::command(9, "SetAngle", &[AXIS, FLOAT]),
+    FunctionDef::new(10, "GetStartingPos", &[AXIS]),
+    FunctionDef::new(11, "GetStartingAngle", &[AXIS]),
+    FunctionDef::new(12, "GetSecondsPassed", &[]),
+    FunctionDef::command(13, "Activate", &[OPT_OBJECT_REF, OPT_INTEGER]),
+    FunctionDef::new(14, "GetActorValue", &[ACTOR_VALUE]).short("GetAV"),
+    FunctionDef::command(15, "SetActorValue", &[ACTOR_VALUE, FLOAT]).short("SetAV"),
+    FunctionDef::command(16, "ModActorValue", &[ACTOR_VALUE, FLOAT]).short("ModAV"),
+    FunctionDef::command(17, "SetAtStart", &[]),
+    FunctionDef::new(18, "GetCurrentTime", &[]),
+    FunctionDef::command(19, "PlayGroup", &[Param::required(ParamType::AnimationGroup), INTEGER]),
+    FunctionDef::command(20, "LoopGroup", &[Param::required(ParamType::AnimationGroup), INTEGER]),
+    FunctionDef::command(21, "SkipAnim", &[]),
+    FunctionDef::command(22, "StartCombat", &[OPT_ACTOR]),
+    FunctionDef::command(23, "StopCombat", &[OPT_ACTOR]),
+    FunctionDef::new(24, "GetScale", &[]),
+    FunctionDef::new(25, "IsMoving", &[]),
+    FunctionDef::new(26, "IsTurning", &[]),
+    FunctionDef::new(27, "GetLineOfSight", &[OBJECT_REF]).short("GetLOS"),
+    FunctionDef::command(28, "AddSpell", &[FORM]),
+    FunctionDef::command(29, "RemoveSpell", &[FORM]),
+    FunctionDef::command(30, "Cast", &[FORM, OPT_OBJECT_REF]),
+    FunctionDef::command(31, "GetButtonPressed", &[]),
+    FunctionDef::new(32, "GetInSameCell", &[OBJECT_REF]),
+    FunctionDef::command(33, "Enable", &[OPT_INTEGER]),
+    FunctionDef::command(34, "Disable", &[OPT_INTEGER]),
+    FunctionDef::new(35, "GetDisabled", &[]),
+    FunctionDef::new(36, "MenuMode", &[OPT_INTEGER]),
+    FunctionDef::command(37, "PlaceAtMe", &[FORM, OPT_INTEGER, OPT_FLOAT, OPT_INTEGER]),
+    FunctionDef::command(38, "PlaySound", &[FORM]),
+    FunctionDef::new(39, "GetDisease", &[]),
+    FunctionDef::new(40, "GetVampire", &[]),
+    FunctionDef::new(41, "GetClothingValue", &[]),
+    FunctionDef::new(42, "SameFaction", &[ACTOR]),
+    FunctionDef::new(43, "SameRace", &[ACTOR]),
+    FunctionDef::new(44, "SameSex", &[ACTOR]),
+    FunctionDef::new(45, "GetDetected", &[ACTOR]),
+    FunctionDef::new(46, "GetDead", &[]),
+    FunctionDef::new(47, "GetItemCount", &[FORM]),
+    FunctionDef::new(48, "GetGold", &[]),
+    FunctionDef::new(49, "GetSleeping", &[]),
+    FunctionDef::new(50, "GetTalkedToPC", &[]),
+    FunctionDef::command(51, "Say", &[FORM, OPT_INTEGER, OPT_ACTOR, OPT_INTEGER]),
+    FunctionDef::command(52, "SayTo", &[ACTOR, FORM, OPT_INTEGER, OPT_INTEGER]),
+    FunctionDef::new(53, "GetScriptVariable", &[OBJECT_REF, Param::required(ParamType::VariableName)]),
+    FunctionDef::command(54, "StartQuest", &[FORM]),
+    FunctionDef::command(55, "StopQuest", &[FORM]),
+    FunctionDef::new(56, "GetQuestRunning", &[FORM]).short("GetQR"),
+    FunctionDef::command(57, "SetStage", &[FORM, INTEGER]),
+    FunctionDef::new(58, "GetStage", &[FORM]),
+    FunctionDef::new(59, "GetStageDone", &[FORM, INTEGER]),
+    FunctionDef::new(60, "GetFactionRankDifference", &[FORM, ACTOR]),
+    FunctionDef::new(61, "GetAlarmed", &[]),
+    FunctionDef::new(62, "IsRaining", &[]),
+    FunctionDef::new(63, "GetAttacked", &[]),
+    FunctionDef::new(64, "GetIsCreature", &[]),
+    FunctionDef::new(65, "GetLockLevel", &[]),
+    FunctionDef::new(66, "GetShouldAttack", &[ACTOR]),
+    FunctionDef::new(67, "GetInCell", &[FORM]),
+    FunctionDef::new(68, "GetIsClass", &[FORM]),
+    FunctionDef::new(69, "GetIsRace", &[FORM]),
+    FunctionDef::new(70, "GetIsSex", &[Param::required(ParamType::Sex)]),
+    FunctionDef::new(71, "GetInFaction", &[FORM]),
+    FunctionDef::new(72, "GetIsID", &[FORM]),
+    FunctionDef::new(73, "GetFactionRank", &[FORM]),
+    FunctionDef::new(74, "GetGlobalValue", &[FORM]),
+    FunctionDef::new(75, "IsSnowing", &[]),
+    FunctionDef::new(76, "GetDisposition", &[ACTOR]),
+    FunctionDef::new(77, "GetRandomPercent", &[]),
+    FunctionDef::command(78, "StreamMusic", &[STRING]),
+    FunctionDef::new(79, "GetQuestVariable", &[FORM, Param::required(ParamType::VariableName)]),
+    FunctionDef::new(80, "GetLevel", &[]),
+    FunctionDef::new(81, "GetArmorRating", &[]),
+    FunctionDef::command(82, "RemoveItem", &[FORM, INTEGER, OPT_INTEGER]),
+    FunctionDef::command(83, "ModDisposition", &[ACTOR, INTEGER]),
+    FunctionDef::new(84, "GetDeadCount", &[FORM]),
+    FunctionDef::command(85, "ShowMap", &[FORM, OPT_INTEGER]),
+    FunctionDef::command(86, "StartConversation", &[ACTOR, OPT_FORM]),
+    FunctionDef::command(87, "Drop", &[FORM, INTEGER]),
+    FunctionDef::command(88, "AddTopic", &[FORM]),
+    FunctionDef::command(90, "SetAlert", &[INTEGER]),
+    FunctionDef::new(91, "GetIsAlerted", &[]),
+    FunctionDef::command(92, "Look", &[OBJECT_REF]),
+    FunctionDef::command(93, "StopLook", &[]),
+    FunctionDef::command(94, "EvaluatePackage", &[]).short("EVP"),
+    FunctionDef::command(95, "SendAssaultAlarm", &[OPT_ACTOR, OPT_FORM]),
+    FunctionDef::command(96, "EnablePlayerControls", &[]),
+    FunctionDef::command(97, "DisablePlayerControls", &[]),
+    FunctionDef::new(98, "GetPlayerControlsDisabled", &[]),
+    FunctionDef::new(99, "GetHeadingAngle", &[OBJECT_REF]),
+    FunctionDef::command(100, "PickIdle", &[]),
+    FunctionDef::new(101, "IsWeaponOut", &[]),
+    FunctionDef::new(102, "IsTorchOut", &[]),
+    FunctionDef::new(103, "IsShieldOut", &[]),
+    FunctionDef::new(106, "IsFacingUp", &[]),
+    FunctionDef::new(107, "GetKnockedState", &[]),
+    FunctionDef::new(108, "GetWeaponAnimType", &[]),
+    FunctionDef::new(109, "IsWeaponSkillType", &[ACTOR_VALUE]),
+    FunctionDef::new(110, "GetCurrentAIPackage", &[]),
+    FunctionDef::new(111, "IsWaiting", &[]),
+    FunctionDef::new(112, "IsIdlePlaying", &[]),
+    FunctionDef::command(113, "CompleteQuest", &[FORM]),
+    FunctionDef::command(114, "Lock", &[OPT_INTEGER, OPT_INTEGER]),
+    FunctionDef::command(115, "Unlock", &[OPT_INTEGER]),
+    FunctionDef::new(116, "GetMinorCrimeCount", &[]),
+    FunctionDef::new(117, "GetMajorCrimeCount", &[]),
+    FunctionDef::new(118, "GetActorAggroRadiusViolated", &[]),
+    FunctionDef::command(120, "SetEnemy", &[FORM, FORM, OPT_INTEGER, OPT_INTEGER]),
+    FunctionDef::command(121, "SetAlly", &[FORM, FORM, OPT_INTEGER, OPT_INTEGER]),
+    FunctionDef::new(122, "GetCrime", &[ACTOR, Param::required(ParamType::CrimeType)]),
+    FunctionDef::new(123, "IsGreetingPlayer", &[]),
+    FunctionDef::new(125, "IsGuard", &[]),
+    FunctionDef::new(127, "HasBeenEaten", &[]),
+    FunctionDef::new(128, "GetFatiguePercentage", &[]),
+    FunctionDef::new(129, "GetPCIsClass", &[FORM]),
+    FunctionDef::new(130, "GetPCIsRace", &[FORM]),
+    FunctionDef::new(131, "GetPCIsSex", &[Param::required(ParamType::Sex)]),
+    FunctionDef::new(132, "GetPCInFaction", &[FORM]),
+    FunctionDef::new(133, "SameFactionAsPC", &[]),
+    FunctionDef::new(134, "SameRaceAsPC", &[]),
+    FunctionDef::new(135, "SameSexAsPC", &[]),
+    FunctionDef::new(136, "GetIsReference", &[OBJECT_REF]),
+    FunctionDef::command(137, "SetFactionRank", &[FORM, INTEGER]),
+    FunctionDef::command(138, "ModFactionRank", &[FORM, INTEGER]),
+    FunctionDef::command(139, "KillActor", &[OPT_ACTOR, OPT_INTEGER, OPT_INTEGER]).short("Kill"),
+    FunctionDef::command(140, "ResurrectActor", &[OPT_INTEGER]).short("Resurrect"),
+    FunctionDef::new(141, "IsTalking", &[]),
+    FunctionDef::new(142, "GetWalkSpeed", &[]),
+    FunctionDef::new(143, "GetCurrentAIProcedure", &[]),
+    FunctionDef::new(144, "GetTrespassWarningLevel", &[]),
+    FunctionDef::new(145, "IsTrespassing", &[]),
+    FunctionDef::new(146, "IsInMyOwnedCell", &[]),
+    FunctionDef::new(147, "GetWindSpeed", &[]),
+    FunctionDef::new(148, "GetCurrentWeatherPercent", &[]),
+    FunctionDef::new(149, "GetIsCurrentWeather", &[FORM]),
+    FunctionDef::new(150, "IsContinuingPackagePCNear", &[]),
+    FunctionDef::command(151, "AddScriptPackage", &[FORM]),
+    FunctionDef::command(152, "RemoveScriptPackage", &[OPT_FORM]),
+    FunctionDef::new(153, "CanHaveFlames", &[]),
+    FunctionDef::new(154, "HasFlames", &[]),
+    FunctionDef::new(157, "GetOpenState", &[]),
+    FunctionDef::command(158, "MoveTo", &[OBJECT_REF, OPT_FLOAT, OPT_FLOAT, OPT_FLOAT]),
+    FunctionDef::new(159, "GetSitting", &[]),
+    FunctionDef::new(160, "GetFurnitureMarkerID", &[]),
+    FunctionDef::new(161, "GetIsCurrentPackage", &[FORM]),
+    FunctionDef::new(162, "IsCurrentFurnitureRef", &[OBJECT_REF]),
+    FunctionDef::new(163, "IsCurrentFurnitureObj", &[FORM]),
+    FunctionDef::command(165, "RemoveMe", &[OPT_OBJECT_REF]),
+    FunctionDef::new(170, "GetDayOfWeek", &[]),
+    FunctionDef::new(172, "GetTalkedToPCParam", &[ACTOR]),
+    FunctionDef::command(173, "RemoveAllItems", &[OPT_OBJECT_REF, OPT_INTEGER]),
+    FunctionDef::new(175, "IsPCSleeping", &[]),
+    FunctionDef::new(176, "IsPCAMurderer", &[]),
+    FunctionDef::command(178, "PlaySound3D", &[FORM]),
+    FunctionDef::new(180, "GetDetectionLevel", &[ACTOR]),
+    FunctionDef::new(182, "GetEquipped", &[FORM]),
+    FunctionDef::new(185, "IsSwimming", &[]),
+    FunctionDef::command(186, "ScriptEffectElapsedSeconds", &[]),
+    FunctionDef::new(190, "GetAmountSoldStolen", &[]),
+    FunctionDef::new(192, "GetIgnoreCrime", &[]),
+    FunctionDef::new(193, "GetPCExpelled", &[FORM]),
+    FunctionDef::new(195, "GetPCFactionMurder", &[FORM]),
+    FunctionDef::new(197, "GetPCEnemyofFaction", &[FORM]),
+    FunctionDef::new(199, "GetPCFactionAttack", &[FORM]),
+    FunctionDef::new(203, "GetDestroyed", &[]),
+    FunctionDef::command(204, "SetDestroyed", &[INTEGER]),
+    FunctionDef::command(205, "GetActionRef", &[]),
+    FunctionDef::command(206, "GetSelf", &[]).short("This"),
+    FunctionDef::command(207, "GetContainer", &[]),
+    FunctionDef::new(214, "HasMagicEffect", &[FORM]),
+    FunctionDef::new(215, "GetDefaultOpen", &[]),
+    FunctionDef::command(216, "SetDefaultOpen", &[INTEGER]),
+    FunctionDef::new(219, "GetAnimAction", &[]),
+    FunctionDef::command(221, "SetOpenState", &[INTEGER]),
+    FunctionDef::new(223, "IsSpellTarget", &[FORM]),
+    FunctionDef::new(224, "GetVATSMode", &[]),
+    FunctionDef::new(225, "GetPersuasionNumber", &[]),
+    FunctionDef::new(226, "GetSandman", &[]),
+    FunctionDef::new(227, "GetCannibal", &[]),
+    FunctionDef::new(228, "GetIsClassDefault", &[FORM]),
+    FunctionDef::new(229, "GetClassDefaultMatch", &[]),
+    FunctionDef::new(230, "GetInCellParam", &[FORM, OBJECT_REF]),
+    FunctionDef::new(235, "GetVatsTargetHeight", &[]),
+    FunctionDef::new(237, "GetIsGhost", &[]),
+    FunctionDef::command(238, "EquipItem", &[FORM, OPT_INTEGER, OPT_INTEGER]).short("EquipObject"),
+    FunctionDef::command(239, "UnequipItem", &[FORM, OPT_INTEGER, OPT_INTEGER]).short("UnequipObject"),
+    FunctionDef::command(241, "SetUnconscious", &[INTEGER]),
+    FunctionDef::new(242, "GetUnconscious", &[]),
+    FunctionDef::command(243, "SetRestrained", &[INTEGER]),
+    FunctionDef::new(244, "GetRestrained", &[]),
+    FunctionDef::new(246, "GetIsUsedItem", &[FORM]),
+    FunctionDef::new(247, "GetIsUsedItemType", &[Param::required(ParamType::FormType)]),
+    FunctionDef::new(254, "GetIsPlayableRace", &[]),
+    FunctionDef::new(255, "GetOffersServicesNow", &[]),
+    FunctionDef::new(258, "GetUsedItemLevel", &[]),
+    FunctionDef::new(259, "GetUsedItemActivate", &[]),
+    FunctionDef::new(264, "GetBarterGold", &[]),
+    FunctionDef::new(265, "IsTimePassing", &[]),
+    FunctionDef::new(266, "IsPleasant", &[]),
+    FunctionDef::new(267, "IsCloudy", &[]),
+    FunctionDef::command(270, "ForceActorValue", &[ACTOR_VALUE, FLOAT]).short("ForceAV"),
+    FunctionDef::new(274, "GetArmorRatingUpperBody", &[]),
+    FunctionDef::command(275, "GetParentRef", &[]),
+    FunctionDef::new(277, "GetBaseActorValue", &[ACTOR_VALUE]).short("GetBaseAV"),
+    FunctionDef::new(278, "IsOwner", &[FORM]),
+    FunctionDef::command(279, "SetOwnership", &[OPT_FORM]),
+    FunctionDef::new(280, "IsCellOwner", &[FORM, FORM]),
+    FunctionDef::new(282, "IsHorseStolen", &[]),
+    FunctionDef::new(285, "IsLeftUp", &[]),
+    FunctionDef::new(286, "IsSneaking", &[]),
+    FunctionDef::new(287, "IsRunning", &[]),
+    FunctionDef::new(288, "GetFriendHit", &[]),
+    FunctionDef::new(289, "IsInCombat", &[]),
+    FunctionDef::command(291, "PlayMagicShaderVisuals", &[FORM, OPT_FLOAT]).short("PMS"),
+    FunctionDef::command(293, "StopMagicShaderVisuals", &[FORM]).short("SMS"),
+    FunctionDef::new(300, "IsInInterior", &[]),
+    FunctionDef::new(304, "IsWaterObject", &[]),
+    FunctionDef::new(306, "IsActorUsingATorch", &[]),
+    FunctionDef::command(307, "SetActorsAI", &[INTEGER]),
+    FunctionDef::new(309, "IsXBox", &[]),
+    FunctionDef::new(310, "GetInWorldspace", &[FORM]),
+    FunctionDef::new(312, "GetPCMiscStat", &[Param::required(ParamType::MiscStat)]),
+    FunctionDef::new(313, "IsActorEvil", &[]),
+    FunctionDef::new(314, "IsActorAVictim", &[]),
+    FunctionDef::new(315, "GetTotalPersuasionNumber", &[]),
+    FunctionDef::command(316, "SetScale", &[FLOAT]),
+    FunctionDef::command(317, "ModScale", &[FLOAT]),
+    FunctionDef::new(318, "GetIdleDoneOnce", &[]),
+    FunctionDef::new(320, "GetNoRumors", &[]),
+    FunctionDef::command(322, "Dispel", &[FORM]),
+    FunctionDef::new(323, "WhichServiceMenu", &[]),
+    FunctionDef::new(327, "IsRidingHorse", &[]),
+    FunctionDef::command(328, "DispelAllSpells", &[]),
+    FunctionDef::new(332, "IsInDangerousWater", &[]),
+    FunctionDef::command(336, "ResetHealth", &[]),
+    FunctionDef::command(337, "SetIgnoreFriendlyHits", &[INTEGER]),
+    FunctionDef::new(338, "GetIgnoreFriendlyHits", &[]),
+    FunctionDef::new(339, "IsPlayersLastRiddenHorse", &[]),
+    FunctionDef::new(353, "IsActor", &[]),
+    FunctionDef::new(354, "IsEssential", &[]),
+    FunctionDef::new(358, "IsPlayerMovingIntoNewSpace", &[]),
+    FunctionDef::new(361, "GetTimeDead", &[]),
+    FunctionDef::new(362, "GetPlayerHasLastRiddenHorse", &[]),
+    FunctionDef::new(365, "IsChild", &[]),
+    FunctionDef::new(367, "GetLastPlayerAction", &[]),
+    FunctionDef::new(368, "IsPlayerActionActive", &[INTEGER]),
+    FunctionDef::new(370, "IsTalkingActivatorActor", &[ACTOR]),
+    FunctionDef::command(371, "ShowBarterMenu", &[OPT_INTEGER]),
+    FunctionDef::new(372, "IsInList", &[FORM]),
+    FunctionDef::command(373, "AddFormToFormList", &[FORM, FORM]),
+    FunctionDef::new(382, "GetHasNote", &[FORM]),
+    FunctionDef::command(383, "AddNote", &[FORM]),
+    FunctionDef::command(389, "ShowMessage", MESSAGE_PARAMS),
+    FunctionDef::new(391, "GetHitLocation", &[]),
+    FunctionDef::new(392, "IsPC1stPerson", &[]),
+    FunctionDef::new(397, "GetCauseofDeath", &[]),
+    FunctionDef::new(398, "IsLimbGone", &[Param::required(ParamType::BodyLocation)]),
+    FunctionDef::new(399, "IsWeaponInList", &[FORM]),
+    FunctionDef::new(403, "HasFriendDisposition", &[]),
+    FunctionDef::new(408, "GetVATSValue", &[INTEGER, INTEGER]),
+    FunctionDef::new(409, "IsKiller", &[ACTOR]),
+    FunctionDef::new(410, "IsKillerObject", &[FORM]),
+    FunctionDef::new(411, "GetFactionCombatReaction", &[FORM, FORM]),
+    FunctionDef::new(415, "Exists", &[OBJECT_REF]),
+    FunctionDef::new(416, "GetGroupMemberCount", &[]),
+    FunctionDef::new(417, "GetGroupTargetCount", &[]),
+    FunctionDef::command(418, "SetObjectiveCompleted", &[FORM, INTEGER, OPT_INTEGER]),
+    FunctionDef::command(419, "SetObjectiveDisplayed", &[FORM, INTEGER, OPT_INTEGER]),
+    FunctionDef::new(420, "GetObjectiveCompleted", &[FORM, INTEGER]),
+    FunctionDef::new(421, "GetObjectiveDisplayed", &[FORM, INTEGER]),
+    FunctionDef::new(427, "GetIsVoiceType", &[FORM]),
+    FunctionDef::new(428, "GetPlantedExplosive", &[]),
+    FunctionDef::new(430, "IsActorTalkingThroughActivator", &[]),
+    FunctionDef::new(431, "GetHealthPercentage", &[]),
+    FunctionDef::new(433, "GetIsObjectType", &[Param::required(ParamType::FormType)]),
+    FunctionDef::new(435, "GetDialogueEmotion", &[]),
+    FunctionDef::new(436, "GetDialogueEmotionValue", &[]),
+    FunctionDef::new(438, "GetIsCreatureType", &[Param::required(ParamType::CreatureType)]),
+    FunctionDef::new(446, "GetInZone", &[FORM]),
+    FunctionDef::command(447, "AddPerk", &[FORM, OPT_INTEGER]),
+    FunctionDef::command(448, "RemovePerk", &[FORM]),
+    FunctionDef::new(449, "HasPerk", &[FORM]),
+    FunctionDef::new(450, "GetFactionRelation", &[ACTOR]),
+    FunctionDef::new(451, "IsLastIdlePlayed", &[FORM]),
+    FunctionDef::new(454, "GetPlayerTeammate", &[]),
+    FunctionDef::new(455, "GetPlayerTeammateCount", &[]),
+    FunctionDef::new(459, "GetActorCrimePlayerEnemy", &[]),
+    FunctionDef::new(460, "GetActorFactionPlayerEnemy", &[]),
+    FunctionDef::new(464, "IsPlayerTagSkill", &[ACTOR_VALUE]),
+    FunctionDef::command(465, "RewardXP", &[INTEGER]),
+    FunctionDef::new(466, "IsPlayerGrabbedRef", &[OBJECT_REF]),
+    FunctionDef::new(471, "GetDestructionStage", &[]),
+    FunctionDef::new(474, "GetIsAlignment", &[Param::required(ParamType::Alignment)]),
+    FunctionDef::new(478, "GetThreatRatio", &[ACTOR]),
+    FunctionDef::new(480, "GetIsUsedItemEquipType", &[Param::required(ParamType::EquipType)]),
+    FunctionDef::new(489, "GetConcussed", &[]),
+    FunctionDef::new(492, "GetMapMarkerVisible", &[]),
+    FunctionDef::new(495, "GetPermanentActorValue", &[ACTOR_VALUE]).short("GetPermAV"),
+    FunctionDef::new(496, "GetKillingBlowLimb", &[]),
+    FunctionDef::new(500, "GetWeaponHealthPerc", &[]),
+    FunctionDef::new(503, "GetRadiationLevel", &[]),
+    FunctionDef::new(510, "GetLastHitCritical", &[]),
+    FunctionDef::new(515, "IsCombatTarget", &[ACTOR]),
+    FunctionDef::new(518, "GetVATSRightAreaFree", &[OBJECT_REF]),
+    FunctionDef::new(519, "GetVATSLeftAreaFree", &[OBJECT_REF]),
+    FunctionDef::new(520, "GetVATSBackAreaFree", &[OBJECT_REF]),
+    FunctionDef::new(521, "GetVATSFrontAreaFree", &[OBJECT_REF]),
+    FunctionDef::new(522, "GetIsLockBroken", &[]),
+    FunctionDef::new(523, "IsPS3", &[]),
+    FunctionDef::new(524, "IsWin32", &[]),
+    FunctionDef::new(525, "GetVATSRightTargetVisible", &[OBJECT_REF]),
+    FunctionDef::new(526, "GetVATSLeftTargetVisible", &[OBJECT_REF]),
+    FunctionDef::new(527, "GetVATSBackTargetVisible", &[OBJECT_REF]),
+    FunctionDef::new(528, "GetVATSFrontTargetVisible", &[OBJECT_REF]),
+    FunctionDef::new(531, "IsInCriticalStage", &[INTEGER]),
+    FunctionDef::new(533, "GetXPForNextLevel", &[]),
+    FunctionDef::new(546, "GetQuestCompleted", &[FORM]),
+    FunctionDef::new(550, "IsGoreDisabled", &[]),
+    FunctionDef::new(555, "GetSpellUsageNum", &[FORM]),
+    FunctionDef::new(557, "GetActorsInHigh", &[]),
+    FunctionDef::new(558, "HasLoaded3D", &[]),
+    FunctionDef::new(573, "GetReputation", &[FORM, INTEGER]),
+    FunctionDef::new(574, "GetReputationPct", &[FORM, INTEGER]),
+    FunctionDef::new(575, "GetReputationThreshold", &[FORM, INTEGER]),
+    FunctionDef::new(586, "IsHardcore", &[]),
+    FunctionDef::command(596, "ListRemoveForm", &[FORM, FORM]),
+    FunctionDef::new(601, "GetForceHitReaction", &[]),
+    FunctionDef::new(607, "ChallengeLocked", &[FORM]),
+    FunctionDef::new(610, "GetCasinoWinningStage", &[FORM]),
+    FunctionDef::new(612, "PlayerInRegion", &[FORM]),
+    FunctionDef::new(614, "GetChallengeCompleted", &[FORM]),
+    FunctionDef::new(619, "IsAlwaysHard", &[]),
+    // Script extender functions, only used by plugins depending on it
+    FunctionDef::command(1059, "ListGetCount", &[FORM]),
+    FunctionDef::command(1060, "ListGetNthForm", &[FORM, INTEGER]),
+    FunctionDef::command(1061, "ListGetFormIndex", &[FORM, FORM]),
+];
+
+#[cfg(test)]
+mod test {
+    use super::{by_name, by_opcode, FUNCTIONS};
+
+    /// Lookups rely on the table being sorted by index
+    #[test]
+    fn test_sorted() {
+        assert!(FUNCTIONS
+            .windows(2)
+            .all(|pair| pair[0].index < pair[1].index));
+
+        assert_eq!(
+            by_opcode(0x103A).map(|function| function.name),
+            Some("GetStage")
+        );
+        assert_eq!(by_name("getav").map(|function| function.index), Some(14));
+    }
+}
@@ -0,0 +1,942 @@
+//! Runtime for object, quest and effect scripts
+
+use bevy::{ecs::system::Resource, utils::HashMap};
+use thiserror::Error;
+
+use crate::esp::{
+    record::sub::script::{SLSDFlags, Script},
+    shared::FormId,
+};
+
+use super::{
+    ast::{
+        BinaryOp, Block, Call, Expr, ScriptAst, Span, Statement, StatementKind, UnaryOp,
+        VariableRef, VariableType,
+    },
+    bytecode::{self, DecompileError, NameResolver},
+    functions,
+    parser::{self, ParseError},
+};
+
+/// Delay between runs of quest scripts that don't set `fQuestDelayTime`
+pub const DEFAULT_QUEST_DELAY: f32 = 5.0;
+
+/// Variable quest scripts can declare to change their delay
+const QUEST_DELAY_VARIABLE: &str = "fQuestDelayTime";
+
+#[derive(Debug, Error)]
+pub enum ScriptLoadError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Decompile(#[from] DecompileError),
+}
+
+/// Loads the script from its source, falling back to decompiling
+/// the compiled data when the source has been stripped
+pub fn load_script<R: NameResolver>(
+    script: &Script,
+    resolver: &R,
+) -> Result<ScriptAst, ScriptLoadError> {
+    if script.source.trim().is_empty() {
+        Ok(bytecode::decompile(script, resolver)?)
+    } else {
+        Ok(parser::parse(&script.source)?)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("Unknown function {name}")]
+    UnknownFunction { name: String, span: Span },
+    #[error("Unknown name {name}")]
+    UnknownName { name: String, span: Span },
+    #[error("Cannot set variable {name}")]
+    UnknownVariable { name: String, span: Span },
+    #[error("Expected a number")]
+    ExpectedNumber { span: Span },
+    /// Error raised by a function implementation
+    #[error("{0}")]
+    Function(String),
+}
+
+pub type ScriptResult<T> = Result<T, ScriptError>;
+
+/// Value of a variable, argument or expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Float(f64),
+    Form(FormId),
+    /// Text arguments and names that didn't resolve to a form
+    /// (e.g. the `X` of `GetPos X`)
+    String(String),
+}
+
+impl Value {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            // References compare against 0 when checking if they are set
+            Value::Form(form_id) => Some(form_id.0 as f64),
+            Value::String(_) => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::Int(value) => Some(*value),
+            value => value.as_f64().map(|value| value as i32),
+        }
+    }
+
+    pub fn as_form(&self) -> Option<&FormId> {
+        match self {
+            Value::Form(form_id) if !form_id.is_null() => Some(form_id),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        self.as_f64().is_some_and(|value| value != 0.0)
+    }
+
+    fn default_for(ty: VariableType) -> Self {
+        match ty {
+            VariableType::Short | VariableType::Int | VariableType::Long => Value::Int(0),
+            VariableType::Float => Value::Float(0.0),
+            VariableType::Ref => Value::Form(FormId::NULL),
+        }
+    }
+
+    /// Converts the value to be stored in a variable of the type
+    fn coerce(self, ty: VariableType) -> Option<Self> {
+        Some(match ty {
+            VariableType::Short | VariableType::Int | VariableType::Long => {
+                Value::Int(self.as_i32()?)
+            }
+            VariableType::Float => Value::Float(self.as_f64()?),
+            VariableType::Ref => match self {
+                Value::Form(form_id) => Value::Form(form_id),
+                value => Value::Form(FormId(value.as_i32()? as u32)),
+            },
+        })
+    }
+}
+
+/// Access to the game for running scripts
+pub trait ScriptEnvironment {
+    /// FormID of the form with the editor ID
+    fn form(&self, editor_id: &str) -> Option<FormId>;
+
+    /// Value of the global variable
+    fn global(&self, _name: &str) -> Option<f64> {
+        None
+    }
+
+    /// Sets the global variable, returns false when it doesn't exist
+    fn set_global(&mut self, _name: &str, _value: f64) -> bool {
+        false
+    }
+
+    /// Variable of the script attached to another form (`reference.variable`)
+    fn variable(&self, _reference: &FormId, _name: &str) -> Option<Value> {
+        None
+    }
+
+    /// Sets a variable of the script attached to another form, returns
+    /// false when it doesn't exist
+    fn set_variable(&mut self, _reference: &FormId, _name: &str, _value: Value) -> bool {
+        false
+    }
+}
+
+/// State provided to script function implementations
+pub struct CallContext<'a> {
+    /// Reference the function was called on, either explicitly through
+    /// `reference.Function` or the object running the script
+    pub this: Option<FormId>,
+    pub environment: &'a mut dyn ScriptEnvironment,
+}
+
+pub type ScriptFunction =
+    Box<dyn Fn(&mut CallContext<'_>, &[Value]) -> ScriptResult<Value> + Send + Sync>;
+
+/// Implementations of the script functions, registered by the
+/// systems implementing them
+#[derive(Default, Resource)]
+pub struct ScriptFunctions {
+    functions: HashMap<String, ScriptFunction>,
+}
+
+impl ScriptFunctions {
+    /// Function names are stored by their full lowercase name
+    /// so short names resolve to the same implementation
+    fn key(name: &str) -> String {
+        functions::by_name(name)
+            .map_or(name, |function| function.name)
+            .to_ascii_lowercase()
+    }
+
+    pub fn register<F>(&mut self, name: &str, function: F)
+    where
+        F: Fn(&mut CallContext<'_>, &[Value]) -> ScriptResult<Value> + Send + Sync + 'static,
+    {
+        self.functions.insert(Self::key(name), Box::new(function));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ScriptFunction> {
+        self.functions.get(&Self::key(name))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LocalVariable {
+    index: u32,
+    name: String,
+    ty: VariableType,
+    value: Value,
+}
+
+/// Running instance of a script holding its local variables
+#[derive(Debug, Clone)]
+pub struct ScriptInstance {
+    /// Object the script is attached to
+    pub owner: Option<FormId>,
+    variables: Vec<LocalVariable>,
+}
+
+impl ScriptInstance {
+    /// Creates an instance for a parsed script, variables are indexed
+    /// in the order they are declared
+    pub fn new(ast: &ScriptAst, owner: Option<FormId>) -> Self {
+        let variables = ast
+            .variables
+            .iter()
+            .zip(1..)
+            .map(|(variable, index)| LocalVariable {
+                index,
+                name: variable.name.clone(),
+                ty: variable.ty,
+                value: Value::default_for(variable.ty),
+            })
+            .collect();
+
+        Self { owner, variables }
+    }
+
+    /// Creates an instance using the variable indices and types from the
+    /// script data, preferring the declared types of the loaded script
+    pub fn from_script(script: &Script, ast: &ScriptAst, owner: Option<FormId>) -> Self {
+        let variables = script
+            .local_variables
+            .iter()
+            .map(|variable| {
+                let ty = match ast.variable(&variable.name) {
+                    Some(declaration) => declaration.ty,
+                    None if variable.data.flags.contains(SLSDFlags::ENABLED) => VariableType::Long,
+                    None => VariableType::Float,
+                };
+
+                LocalVariable {
+                    index: variable.data.index,
+                    name: variable.name.clone(),
+                    ty,
+                    value: Value::default_for(ty),
+                }
+            })
+            .collect();
+
+        Self { owner, variables }
+    }
+
+    fn variable_mut(&mut self, name: &str) -> Option<&mut LocalVariable> {
+        self.variables
+            .iter_mut()
+            .find(|variable| variable.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Value of the local variable with the name
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.variables
+            .iter()
+            .find(|variable| variable.name.eq_ignore_ascii_case(name))
+            .map(|variable| &variable.value)
+    }
+
+    /// Value of the local variable with the index from the script data
+    pub fn get_index(&self, index: u32) -> Option<&Value> {
+        self.variables
+            .iter()
+            .find(|variable| variable.index == index)
+            .map(|variable| &variable.value)
+    }
+
+    /// Sets the local variable converting the value to the variable type,
+    /// returns false when there is no such variable
+    pub fn set(&mut self, name: &str, value: Value) -> bool {
+        let Some(variable) = self.variable_mut(name) else {
+            return false;
+        };
+        match value.coerce(variable.ty) {
+            Some(value) => {
+                variable.value = value;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Event dispatched to the blocks of a script
+#[derive(Debug, Clone)]
+pub struct ScriptEvent {
+    /// Name of the block type handling the event (e.g. `OnActivate`)
+    pub block: &'static str,
+    /// Value blocks with an argument are filtered by, such as the
+    /// activating reference or the menu mode
+    pub arg: Option<Value>,
+}
+
+impl ScriptEvent {
+    pub fn new(block: &'static str, arg: Option<Value>) -> Self {
+        Self { block, arg }
+    }
+
+    pub fn game_mode() -> Self {
+        Self::new("GameMode", None)
+    }
+
+    pub fn menu_mode(menu: i32) -> Self {
+        Self::new("MenuMode", Some(Value::Int(menu)))
+    }
+
+    pub fn on_activate(activator: FormId) -> Self {
+        Self::new("OnActivate", Some(Value::Form(activator)))
+    }
+
+    pub fn on_add(container: FormId) -> Self {
+        Self::new("OnAdd", Some(Value::Form(container)))
+    }
+
+    pub fn on_equip(actor: FormId) -> Self {
+        Self::new("OnEquip", Some(Value::Form(actor)))
+    }
+
+    pub fn on_unequip(actor: FormId) -> Self {
+        Self::new("OnUnequip", Some(Value::Form(actor)))
+    }
+
+    pub fn on_death(killer: FormId) -> Self {
+        Self::new("OnDeath", Some(Value::Form(killer)))
+    }
+
+    pub fn on_load() -> Self {
+        Self::new("OnLoad", None)
+    }
+
+    pub fn script_effect_start() -> Self {
+        Self::new("ScriptEffectStart", None)
+    }
+
+    pub fn script_effect_update() -> Self {
+        Self::new("ScriptEffectUpdate", None)
+    }
+
+    pub fn script_effect_finish() -> Self {
+        Self::new("ScriptEffectFinish", None)
+    }
+}
+
+/// Timer deciding when the script of a running quest should run
+#[derive(Debug, Default)]
+pub struct QuestTimer {
+    elapsed: f32,
+}
+
+impl QuestTimer {
+    /// Advances the timer, returns whether the quest script should run.
+    /// The delay comes from the `fQuestDelayTime` variable of the script
+    /// when set, otherwise `default_delay` from the quest data
+    pub fn tick(
+        &mut self,
+        instance: &ScriptInstance,
+        default_delay: Option<f32>,
+        delta: f32,
+    ) -> bool {
+        let delay = instance
+            .get(QUEST_DELAY_VARIABLE)
+            .and_then(Value::as_f64)
+            .map(|delay| delay as f32)
+            .filter(|delay| *delay > 0.0)
+            .or(default_delay.filter(|delay| *delay > 0.0))
+            .unwrap_or(DEFAULT_QUEST_DELAY);
+
+        self.elapsed += delta;
+        if self.elapsed < delay {
+            return false;
+        }
+        self.elapsed = 0.0;
+        true
+    }
+}
+
+/// Whether execution continues after a statement
+enum Flow {
+    Continue,
+    Return,
+}
+
+/// Executes script blocks against the environment
+pub struct ScriptRunner<'a> {
+    functions: &'a ScriptFunctions,
+    environment: &'a mut dyn ScriptEnvironment,
+    /// Span of the statement being executed for errors
+    span: Span,
+}
+
+impl<'a> ScriptRunner<'a> {
+    pub fn new(functions: &'a ScriptFunctions, environment: &'a mut dyn ScriptEnvironment) -> Self {
+        Self {
+            functions,
+            environment,
+            span: Span::default(),
+        }
+    }
+
+    /// Runs the blocks of the script handling the event, returns
+    /// the number of blocks that ran. Returning stops the script for
+    /// this run, skipping the later blocks handling the event
+    pub fn run(
+        &mut self,
+        script: &ScriptAst,
+        instance: &mut ScriptInstance,
+        event: &ScriptEvent,
+    ) -> ScriptResult<usize> {
+        let mut count = 0;
+        for block in &script.blocks {
+            if !self.handles(block, instance, event)? {
+                continue;
+            }
+            count += 1;
+            if let Flow::Return = self.execute(&block.body, instance)? {
+                break;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Runs a result script, which has no blocks or variables of its own,
+    /// on behalf of the owner of the instance
+    pub fn run_result_script(
+        &mut self,
+        statements: &[Statement],
+        instance: &mut ScriptInstance,
+    ) -> ScriptResult<()> {
+        self.execute(statements, instance)?;
+        Ok(())
+    }
+
+    fn handles(
+        &mut self,
+        block: &Block,
+        instance: &ScriptInstance,
+        event: &ScriptEvent,
+    ) -> ScriptResult<bool> {
+        if !block.name.eq_ignore_ascii_case(event.block) {
+            return Ok(false);
+        }
+
+        // Blocks without a filter handle every event, filtered blocks
+        // don't handle events without the value
+        let Some(expected) = block.args.first() else {
+            return Ok(true);
+        };
+        let Some(actual) = &event.arg else {
+            return Ok(false);
+        };
+
+        self.span = block.span;
+        let expected = self.argument(expected, instance)?;
+        Ok(values_equal(&expected, actual))
+    }
+
+    fn execute(
+        &mut self,
+        statements: &[Statement],
+        instance: &mut ScriptInstance,
+    ) -> ScriptResult<Flow> {
+        for statement in statements {
+            self.span = statement.span;
+            match &statement.kind {
+                StatementKind::Set { target, value } => {
+                    let value = self.evaluate(value, instance)?;
+                    self.assign(target, value, instance)?;
+                }
+                StatementKind::If {
+                    branches,
+                    otherwise,
+                } => {
+                    let mut body = otherwise.as_deref();
+                    for branch in branches {
+                        if self.evaluate(&branch.condition, instance)?.is_truthy() {
+                            body = Some(&branch.body);
+                            break;
+                        }
+                    }
+
+                    if let Some(body) = body {
+                        if let Flow::Return = self.execute(body, instance)? {
+                            return Ok(Flow::Return);
+                        }
+                    }
+                }
+                StatementKind::Call(call) => {
+                    self.call(call, instance)?;
+                }
+                StatementKind::Return => return Ok(Flow::Return),
+            }
+        }
+
+        Ok(Flow::Continue)
+    }
+
+    fn assign(
+        &mut self,
+        target: &VariableRef,
+        value: Value,
+        instance: &mut ScriptInstance,
+    ) -> ScriptResult<()> {
+        let assigned = match &target.reference {
+            Some(reference) => {
+                let reference = self.reference(reference, instance)?;
+                self.environment
+                    .set_variable(&reference, &target.name, value)
+            }
+            None => {
+                instance.set(&target.name, value.clone())
+                    || value
+                        .as_f64()
+                        .is_some_and(|value| self.environment.set_global(&target.name, value))
+            }
+        };
+
+        if assigned {
+            Ok(())
+        } else {
+            Err(ScriptError::UnknownVariable {
+                name: target.to_string(),
+                span: self.span,
+            })
+        }
+    }
+
+    /// Resolves the reference a variable is accessed or function called on
+    fn reference(&self, name: &str, instance: &ScriptInstance) -> ScriptResult<FormId> {
+        if let Some(Value::Form(form_id)) = instance.get(name) {
+            return Ok(form_id.clone());
+        }
+        self.environment
+            .form(name)
+            .ok_or_else(|| ScriptError::UnknownName {
+                name: name.to_string(),
+                span: self.span,
+            })
+    }
+
+    /// Value of a name that isn't a function call
+    fn name(&self, name: &str, instance: &ScriptInstance) -> Option<Value> {
+        if let Some(value) = instance.get(name) {
+            return Some(value.clone());
+        }
+        if let Some(value) = self.environment.global(name) {
+            return Some(Value::Float(value));
+        }
+        self.environment.form(name).map(Value::Form)
+    }
+
+    fn evaluate(&mut self, expr: &Expr, instance: &ScriptInstance) -> ScriptResult<Value> {
+        Ok(match expr {
+            Expr::Int(value) => Value::Int(*value),
+            Expr::Float(value) => Value::Float(*value),
+            Expr::String(value) => Value::String(value.clone()),
+            Expr::Identifier(name)
+            | Expr::Variable(VariableRef {
+                reference: None,
+                name,
+            }) => self
+                .name(name, instance)
+                .ok_or_else(|| ScriptError::UnknownName {
+                    name: name.clone(),
+                    span: self.span,
+                })?,
+            Expr::Variable(
+                variable @ VariableRef {
+                    reference: Some(reference),
+                    name,
+                },
+            ) => {
+                let reference = self.reference(reference, instance)?;
+                self.environment.variable(&reference, name).ok_or_else(|| {
+                    ScriptError::UnknownName {
+                        name: variable.to_string(),
+                        span: self.span,
+                    }
+                })?
+            }
+            Expr::Call(call) => self.call(call, instance)?,
+            Expr::Unary {
+                op: UnaryOp::Neg,
+                operand,
+            } => match self.evaluate(operand, instance)? {
+                Value::Int(value) => Value::Int(value.wrapping_neg()),
+                value => Value::Float(-self.number(&value)?),
+            },
+            Expr::Binary { op, lhs, rhs } => {
+                // Both sides are always evaluated, the game doesn't short
+                // circuit && and || so calls on the right still run
+                let lhs = self.evaluate(lhs, instance)?;
+                let rhs = self.evaluate(rhs, instance)?;
+                self.binary(*op, &lhs, &rhs)?
+            }
+        })
+    }
+
+    fn number(&self, value: &Value) -> ScriptResult<f64> {
+        value
+            .as_f64()
+            .ok_or(ScriptError::ExpectedNumber { span: self.span })
+    }
+
+    fn binary(&self, op: BinaryOp, lhs: &Value, rhs: &Value) -> ScriptResult<Value> {
+        if let (Value::Int(lhs), Value::Int(rhs)) = (lhs, rhs) {
+            match op {
+                BinaryOp::Add => return Ok(Value::Int(lhs.wrapping_add(*rhs))),
+                BinaryOp::Sub => return Ok(Value::Int(lhs.wrapping_sub(*rhs))),
+                BinaryOp::Mul => return Ok(Value::Int(lhs.wrapping_mul(*rhs))),
+                _ => {}
+            }
+        }
+
+        if matches!(op, BinaryOp::Eq | BinaryOp::Ne) {
+            let equal = values_equal(lhs, rhs);
+            return Ok(Value::Int((equal == (op == BinaryOp::Eq)) as i32));
+        }
+
+        let (lhs, rhs) = (self.number(lhs)?, self.number(rhs)?);
+        let condition = |value: bool| Value::Int(value as i32);
+        Ok(match op {
+            BinaryOp::Or => condition(lhs != 0.0 || rhs != 0.0),
+            BinaryOp::And => condition(lhs != 0.0 && rhs != 0.0),
+            BinaryOp::Lt => condition(lhs < rhs),
+            BinaryOp::Le => condition(lhs <= rhs),
+            BinaryOp::Gt => condition(lhs > rhs),
+            BinaryOp::Ge => condition(lhs >= rhs),
+            BinaryOp::Add => Value::Float(lhs + rhs),
+            BinaryOp::Sub => Value::Float(lhs - rhs),
+            BinaryOp::Mul => Value::Float(lhs * rhs),
+            BinaryOp::Div => Value::Float(lhs / rhs),
+            BinaryOp::Eq | BinaryOp::Ne => unreachable!("equality handled above"),
+        })
+    }
+
+    /// Arguments fall back to their name when they don't resolve to a
+    /// value, leaving the function to interpret them
+    fn argument(&mut self, expr: &Expr, instance: &ScriptInstance) -> ScriptResult<Value> {
+        match expr {
+            Expr::Identifier(name) => Ok(self
+                .name(name, instance)
+                .unwrap_or_else(|| Value::String(name.clone()))),
+            expr => self.evaluate(expr, instance),
+        }
+    }
+
+    fn call(&mut self, call: &Call, instance: &ScriptInstance) -> ScriptResult<Value> {
+        let functions = self.functions;
+        let function =
+            functions
+                .get(&call.function)
+                .ok_or_else(|| ScriptError::UnknownFunction {
+                    name: call.function.clone(),
+                    span: call.span,
+                })?;
+
+        let this = match &call.reference {
+            Some(reference) => Some(self.reference(reference, instance)?),
+            None => instance.owner.clone(),
+        };
+
+        let args = call
+            .args
+            .iter()
+            .map(|arg| self.argument(arg, instance))
+            .collect::<ScriptResult<Vec<Value>>>()?;
+
+        let mut context = CallContext {
+            this,
+            environment: &mut *self.environment,
+        };
+        function(&mut context, &args)
+    }
+}
+
+/// Equality used by conditions and block filters, forms compare by
+/// FormID and other values numerically
+fn values_equal(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::String(lhs), Value::String(rhs)) => lhs.eq_ignore_ascii_case(rhs),
+        (lhs, rhs) => match (lhs.as_f64(), rhs.as_f64()) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use bevy::utils::HashMap;
+
+    use super::{
+        load_script, QuestTimer, ScriptEnvironment, ScriptEvent, ScriptFunctions, ScriptInstance,
+        ScriptRunner, Value,
+    };
+    use crate::{
+        esp::{
+            record::sub::script::{
+                LocalVariable, SCHRFlags, SCHRType, SLSDFlags, Script, SCHR, SLSD,
+            },
+            shared::FormId,
+        },
+        script::parser::parse,
+    };
+
+    struct Environment;
+
+    impl ScriptEnvironment for Environment {
+        fn form(&self, editor_id: &str) -> Option<FormId> {
+            match editor_id.to_ascii_lowercase().as_str() {
+                "player" => Some(FormId(0x14)),
+                "mq01" => Some(FormId(0x100)),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_quest_script() {
+        let ast = parse(
+            r#"scn TestQuestScript
+
+short doOnce
+float fQuestDelayTime
+
+begin GameMode
+    if doOnce
+        return
+    endif
+    set fQuestDelayTime to 0.5
+    if GetStage MQ01 < 10
+        SetStage MQ01 10
+    endif
+    set doOnce to 1.7
+end
+
+begin OnActivate player
+    set doOnce to 0
+end
+"#,
+        )
+        .unwrap();
+
+        let stages: Arc<Mutex<HashMap<u32, i32>>> = Arc::default();
+        let mut functions = ScriptFunctions::default();
+        {
+            let stages = stages.clone();
+            functions.register("GetStage", move |_, args| {
+                let quest = args[0].as_form().unwrap().0;
+                Ok(Value::Int(
+                    *stages.lock().unwrap().get(&quest).unwrap_or(&0),
+                ))
+            });
+        }
+        {
+            let stages = stages.clone();
+            functions.register("SetStage", move |_, args| {
+                let quest = args[0].as_form().unwrap().0;
+                stages
+                    .lock()
+                    .unwrap()
+                    .insert(quest, args[1].as_i32().unwrap());
+                Ok(Value::Int(1))
+            });
+        }
+
+        let mut environment = Environment;
+        let mut instance = ScriptInstance::new(&ast, None);
+        let mut runner = ScriptRunner::new(&functions, &mut environment);
+
+        let ran = runner
+            .run(&ast, &mut instance, &ScriptEvent::game_mode())
+            .unwrap();
+        assert_eq!(ran, 1);
+        assert_eq!(stages.lock().unwrap().get(&0x100), Some(&10));
+        // Short variables truncate assigned values
+        assert_eq!(instance.get("doOnce"), Some(&Value::Int(1)));
+
+        // Activation by other references doesn't run the block
+        let ran = runner
+            .run(&ast, &mut instance, &ScriptEvent::on_activate(FormId(0x7)))
+            .unwrap();
+        assert_eq!(ran, 0);
+        runner
+            .run(&ast, &mut instance, &ScriptEvent::on_activate(FormId(0x14)))
+            .unwrap();
+        assert_eq!(instance.get("doOnce"), Some(&Value::Int(0)));
+
+        // The script shortened its own delay
+        let mut timer = QuestTimer::default();
+        assert!(!timer.tick(&instance, None, 0.25));
+        assert!(timer.tick(&instance, None, 0.25));
+    }
+
+    /// Both sides of && and || are evaluated even when the left side
+    /// decides the result
+    #[test]
+    fn test_no_short_circuit() {
+        let ast = parse(
+            r#"scn TestConditionScript
+
+short result
+
+begin GameMode
+    set result to 0 && GetStage MQ01
+    set result to 1 || GetStage MQ01
+end
+"#,
+        )
+        .unwrap();
+
+        let calls: Arc<Mutex<i32>> = Arc::default();
+        let mut functions = ScriptFunctions::default();
+        {
+            let calls = calls.clone();
+            functions.register("GetStage", move |_, _| {
+                *calls.lock().unwrap() += 1;
+                Ok(Value::Int(1))
+            });
+        }
+
+        let mut environment = Environment;
+        let mut instance = ScriptInstance::new(&ast, None);
+        let mut runner = ScriptRunner::new(&functions, &mut environment);
+        runner
+            .run(&ast, &mut instance, &ScriptEvent::game_mode())
+            .unwrap();
+        assert_eq!(*calls.lock().unwrap(), 2);
+        assert_eq!(instance.get("result"), Some(&Value::Int(1)));
+
+        // Unknown names on the right are still reported
+        let ast = parse(
+            r#"scn TestConditionScript
+
+begin GameMode
+    if 0 && MissingQuest.variable
+    endif
+end
+"#,
+        )
+        .unwrap();
+        let mut instance = ScriptInstance::new(&ast, None);
+        assert!(runner
+            .run(&ast, &mut instance, &ScriptEvent::game_mode())
+            .is_err());
+    }
+
+    /// Statement with a u16 length prefixed body
+    fn statement(opcode: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = opcode.to_le_bytes().to_vec();
+        out.extend((body.len() as u16).to_le_bytes());
+        out.extend(body);
+        out
+    }
+
+    /// Sets the local variable with the index to the number
+    fn set(index: u8, value: &[u8]) -> Vec<u8> {
+        let expression = [b" ", value].concat();
+        let mut body = vec![b's', index, 0];
+        body.extend((expression.len() as u16).to_le_bytes());
+        body.extend(expression);
+        statement(0x15, &body)
+    }
+
+    #[test]
+    fn test_run_compiled_script() {
+        let game_mode = statement(0x10, &[0, 0, 0, 0, 0, 0, 0, 0]);
+        let bytecode = [
+            statement(0x1D, &[]),
+            game_mode.clone(),
+            set(1, b"2.7"),
+            statement(0x1E, &[]),
+            statement(0x11, &[]),
+            game_mode,
+            set(3, b"1.5"),
+            statement(0x11, &[]),
+        ]
+        .concat();
+
+        // Source stripped from the script, which has a gap in its variable indices
+        let script = Script {
+            basic_data: SCHR {
+                ref_count: 0,
+                compiled_size: bytecode.len() as u32,
+                variable_count: 2,
+                ty: SCHRType::Object,
+                flags: SCHRFlags::ENABLED,
+            },
+            compiled_source: bytecode,
+            source: String::new(),
+            local_variables: vec![
+                LocalVariable {
+                    data: SLSD {
+                        index: 1,
+                        flags: SLSDFlags::ENABLED,
+                    },
+                    name: "count".to_string(),
+                },
+                LocalVariable {
+                    data: SLSD {
+                        index: 3,
+                        flags: SLSDFlags::empty(),
+                    },
+                    name: "timer".to_string(),
+                },
+            ],
+            references: Vec::new(),
+        };
+
+        let resolver = |_: &FormId| None;
+        let ast = load_script(&script, &resolver).unwrap();
+        let mut instance = ScriptInstance::from_script(&script, &ast, None);
+
+        let functions = ScriptFunctions::default();
+        let mut environment = Environment;
+        let ran = ScriptRunner::new(&functions, &mut environment)
+            .run(&ast, &mut instance, &ScriptEvent::game_mode())
+            .unwrap();
+
+        // Returning from the first block skips the second
+        assert_eq!(ran, 1);
+        assert_eq!(instance.get_index(1), Some(&Value::Int(2)));
+        assert_eq!(instance.get_index(3), Some(&Value::Float(0.0)));
+    }
+}
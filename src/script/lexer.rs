@@ -0,0 +1,130 @@
+use super::{ast::Span, parser::ParseError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Identifier(String),
+    Int(i32),
+    Float(f64),
+    String(String),
+    /// Operator or punctuation
+    Symbol(&'static str),
+    Newline,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+impl Token {
+    /// Whether the token is the identifier, ignoring case
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Identifier(name) if name.eq_ignore_ascii_case(keyword))
+    }
+
+    pub fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.kind, TokenKind::Symbol(value) if value == symbol)
+    }
+}
+
+/// Symbols ordered so that longer symbols are matched first
+const SYMBOLS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", ":=", "<", ">", "+", "-", "*", "/", "(", ")", ".", ",",
+];
+
+/// Splits script source into tokens, comments are skipped and
+/// line breaks are kept as they terminate statements
+pub fn tokenize(source: &str) -> Result<Vec<Token>, ParseError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let start = index;
+        let byte = bytes[index];
+
+        let kind = match byte {
+            b'\n' => {
+                index += 1;
+                TokenKind::Newline
+            }
+            _ if byte.is_ascii_whitespace() => {
+                index += 1;
+                continue;
+            }
+            b';' => {
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            b'"' => {
+                let end = source[start + 1..]
+                    .find(['"', '\n'])
+                    .map(|end| start + 1 + end)
+                    .filter(|&end| bytes[end] == b'"')
+                    .ok_or_else(|| {
+                        ParseError::new("Unterminated string", Span::new(start, start + 1))
+                    })?;
+                index = end + 1;
+                TokenKind::String(source[start + 1..end].to_string())
+            }
+            b'0'..=b'9' => {
+                index = scan(bytes, index, |byte| byte.is_ascii_digit() || byte == b'.');
+                number(&source[start..index], Span::new(start, index))?
+            }
+            b'.' if bytes.get(index + 1).is_some_and(u8::is_ascii_digit) => {
+                index = scan(bytes, index + 1, |byte| byte.is_ascii_digit());
+                number(&source[start..index], Span::new(start, index))?
+            }
+            _ if byte.is_ascii_alphabetic() || byte == b'_' => {
+                index = scan(bytes, index, |byte| {
+                    byte.is_ascii_alphanumeric() || byte == b'_'
+                });
+                TokenKind::Identifier(source[start..index].to_string())
+            }
+            _ => {
+                let symbol = SYMBOLS
+                    .iter()
+                    .find(|symbol| source[start..].starts_with(**symbol))
+                    .ok_or_else(|| {
+                        let length = source[start..].chars().next().map_or(1, char::len_utf8);
+                        ParseError::new(
+                            format!("Unexpected character {:?}", &source[start..start + length]),
+                            Span::new(start, start + length),
+                        )
+                    })?;
+                index += symbol.len();
+                TokenKind::Symbol(symbol)
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            span: Span::new(start, index),
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Advances past the bytes matching the predicate
+fn scan(bytes: &[u8], mut index: usize, predicate: impl Fn(u8) -> bool) -> usize {
+    while index < bytes.len() && predicate(bytes[index]) {
+        index += 1;
+    }
+    index
+}
+
+fn number(text: &str, span: Span) -> Result<TokenKind, ParseError> {
+    if !text.contains('.') {
+        if let Ok(value) = text.parse::<i32>() {
+            return Ok(TokenKind::Int(value));
+        }
+    }
+
+    text.parse::<f64>()
+        .map(TokenKind::Float)
+        .map_err(|_| ParseError::new(format!("Invalid number {text:?}"), span))
+}
@@ -0,0 +1,496 @@
+//! Static analysis of scripts reporting mistakes the game would
+//! silently ignore at runtime
+
+use crate::esp::{
+    record::sub::script::{Reference, SCHRType, Script},
+    shared::FormId,
+};
+
+use super::{
+    ast::{
+        Block, Call, Expr, ScriptAst, Span, Statement, StatementKind, VariableRef, VariableType,
+    },
+    bytecode::{self, NameResolver},
+    functions::{self, ParamType},
+    interpreter::{load_script, ScriptLoadError},
+};
+
+/// References available to every script without being defined by a plugin
+const BUILTIN_REFERENCES: &[&str] = &["player", "playerref"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Span of the statement or block the problem was found in
+    pub span: Span,
+}
+
+/// Kind of form an editor ID refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormKind {
+    Quest,
+    /// Placed reference (REFR, ACHR, ACRE, ...)
+    Reference,
+    Global,
+    Other,
+}
+
+/// Forms of the load order the script is checked against
+pub trait LintContext {
+    /// Kind of the form with the editor ID
+    fn form(&self, editor_id: &str) -> Option<FormKind>;
+
+    /// Variables of the script attached to the quest or reference,
+    /// [`None`] when the form doesn't have a script
+    fn script_variables(&self, editor_id: &str) -> Option<&[String]>;
+
+    /// Whether a form with the FormId exists
+    fn form_exists(&self, _form_id: &FormId) -> bool {
+        true
+    }
+}
+
+/// Checks the script, decompiling it when the source isn't available
+pub fn lint_script<C>(script: &Script, context: &C) -> Result<Vec<Diagnostic>, ScriptLoadError>
+where
+    C: LintContext + NameResolver,
+{
+    let ast = load_script(script, context)?;
+    let mut diagnostics = lint(&ast, script.basic_data.ty, context);
+
+    for reference in &script.references {
+        if let Reference::Object(form_id) = reference {
+            if !context.form_exists(form_id) {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    message: format!("Unknown FormId {:08X}", form_id.0),
+                    span: Span::default(),
+                });
+            }
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+/// Checks a parsed or decompiled script of the provided type
+pub fn lint<C: LintContext + ?Sized>(
+    ast: &ScriptAst,
+    ty: SCHRType,
+    context: &C,
+) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        ast,
+        context,
+        diagnostics: Vec::new(),
+    };
+
+    for block in &ast.blocks {
+        linter.block(block, ty);
+    }
+
+    linter.diagnostics
+}
+
+/// Checks a result script, which is a list of statements without any blocks
+/// or variables of its own (e.g. terminal menu items and quest stages)
+pub fn lint_result_script<C: LintContext + ?Sized>(
+    statements: &[Statement],
+    context: &C,
+) -> Vec<Diagnostic> {
+    let ast = ScriptAst::default();
+    let mut linter = Linter {
+        ast: &ast,
+        context,
+        diagnostics: Vec::new(),
+    };
+    linter.statements(statements);
+    linter.diagnostics
+}
+
+/// Whether blocks of the type are ever run for scripts of the type
+fn block_runs(ty: SCHRType, block: &str) -> bool {
+    let block = block.to_ascii_lowercase();
+    let effect = block.starts_with("scripteffect");
+    let always = matches!(block.as_str(), "gamemode" | "menumode" | "function");
+
+    match ty {
+        SCHRType::Quest => always,
+        SCHRType::Effect => always || effect,
+        SCHRType::Object => !effect,
+    }
+}
+
+struct Linter<'a, C: ?Sized> {
+    ast: &'a ScriptAst,
+    context: &'a C,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<C: LintContext + ?Sized> Linter<'_, C> {
+    fn report(&mut self, severity: Severity, message: String, span: Span) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            message,
+            span,
+        });
+    }
+
+    fn error(&mut self, message: String, span: Span) {
+        self.report(Severity::Error, message, span);
+    }
+
+    fn warning(&mut self, message: String, span: Span) {
+        self.report(Severity::Warning, message, span);
+    }
+
+    fn block(&mut self, block: &Block, ty: SCHRType) {
+        let known = bytecode::BLOCK_TYPES
+            .iter()
+            .any(|(_, name)| name.eq_ignore_ascii_case(&block.name));
+
+        if !known {
+            self.error(format!("Unknown block type {}", block.name), block.span);
+        } else if !block_runs(ty, &block.name) {
+            self.warning(
+                format!("{} blocks never run in {ty:?} scripts", block.name),
+                block.span,
+            );
+        }
+
+        for arg in &block.args {
+            self.expr(arg, block.span);
+        }
+        self.statements(&block.body);
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        let mut returned = false;
+
+        for statement in statements {
+            if returned {
+                self.warning("Unreachable code after return".to_string(), statement.span);
+                // Only the first unreachable statement is reported
+                returned = false;
+            }
+            self.statement(statement);
+
+            if matches!(statement.kind, StatementKind::Return) {
+                returned = true;
+            }
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        let span = statement.span;
+        match &statement.kind {
+            StatementKind::Set { target, value } => {
+                self.variable(target, span);
+                self.expr(value, span);
+            }
+            StatementKind::If {
+                branches,
+                otherwise,
+            } => {
+                for branch in branches {
+                    if matches!(branch.condition, Expr::Int(0)) {
+                        self.warning("Condition is always false".to_string(), span);
+                    }
+                    self.expr(&branch.condition, span);
+                    self.statements(&branch.body);
+                }
+                if let Some(otherwise) = otherwise {
+                    self.statements(otherwise);
+                }
+            }
+            StatementKind::Call(call) => self.call(call),
+            StatementKind::Return => {}
+        }
+    }
+
+    fn is_global(&self, name: &str) -> bool {
+        self.context.form(name) == Some(FormKind::Global)
+    }
+
+    fn is_builtin(name: &str) -> bool {
+        BUILTIN_REFERENCES
+            .iter()
+            .any(|builtin| builtin.eq_ignore_ascii_case(name))
+    }
+
+    /// Unresolved form, decompiled scripts name forms they can't
+    /// resolve after their FormId
+    fn unknown_form(&mut self, name: &str, span: Span) {
+        let message = match name.strip_prefix("FormId_") {
+            Some(form_id) => format!("Unknown FormId {form_id}"),
+            None => format!("Unknown editor ID {name}"),
+        };
+        self.error(message, span);
+    }
+
+    fn variable(&mut self, variable: &VariableRef, span: Span) {
+        let name = &variable.name;
+        match &variable.reference {
+            None if self.ast.variable(name).is_none() && !self.is_global(name) => {
+                self.error(format!("Undeclared variable {name}"), span);
+            }
+            None => {}
+            Some(reference) => self.remote_variable(reference, name, span),
+        }
+    }
+
+    /// Variable of another script accessed through `reference.variable`
+    fn remote_variable(&mut self, reference: &str, name: &str, span: Span) {
+        if !self.reference(reference, span) {
+            return;
+        }
+
+        let variables = self.context.script_variables(reference);
+        let declared = variables.map(|variables| {
+            variables
+                .iter()
+                .any(|variable| variable.eq_ignore_ascii_case(name))
+        });
+
+        match (self.context.form(reference), declared) {
+            (Some(FormKind::Quest), None) => {
+                self.error(format!("Quest {reference} has no script"), span);
+            }
+            (Some(FormKind::Quest | FormKind::Reference), Some(false)) => {
+                self.error(
+                    format!("Script of {reference} has no variable {name}"),
+                    span,
+                );
+            }
+            (Some(FormKind::Global | FormKind::Other), _) => {
+                self.error(
+                    format!(
+                        "{reference} is not a quest or reference, its variables can't be accessed"
+                    ),
+                    span,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the name a function or variable is accessed through, returns
+    /// whether it refers to a form that can be checked further
+    fn reference(&mut self, name: &str, span: Span) -> bool {
+        if let Some(variable) = self.ast.variable(name) {
+            if variable.ty != VariableType::Ref {
+                self.error(format!("{name} is not a ref variable"), span);
+            }
+            return false;
+        }
+        if Self::is_builtin(name) {
+            return false;
+        }
+        if self.context.form(name).is_none() {
+            self.unknown_form(name, span);
+            return false;
+        }
+        true
+    }
+
+    fn call(&mut self, call: &Call) {
+        let span = call.span;
+        if let Some(reference) = &call.reference {
+            self.reference(reference, span);
+        }
+
+        // The function table doesn't cover every command, so calls it
+        // doesn't know about may still be valid
+        let Some(function) = functions::by_name(&call.function) else {
+            self.warning(format!("Unknown function {}", call.function), span);
+            for arg in &call.args {
+                self.expr(arg, span);
+            }
+            return;
+        };
+
+        let name = function.name;
+        if call.args.len() < function.required_params() {
+            self.error(
+                format!(
+                    "{name} expects at least {} arguments",
+                    function.required_params()
+                ),
+                span,
+            );
+        } else if call.args.len() > function.params.len() {
+            self.error(
+                format!("{name} takes at most {} arguments", function.params.len()),
+                span,
+            );
+        }
+
+        for (index, arg) in call.args.iter().enumerate() {
+            match function.params.get(index) {
+                Some(param) => self.argument(name, index, param.ty, arg, span),
+                None => self.expr(arg, span),
+            }
+        }
+    }
+
+    fn argument(&mut self, function: &str, index: usize, ty: ParamType, arg: &Expr, span: Span) {
+        let position = index + 1;
+        match arg {
+            Expr::Identifier(name) if ty.is_form() => {
+                if !Self::is_builtin(name) && self.context.form(name).is_none() {
+                    self.unknown_form(name, span);
+                }
+            }
+            Expr::Identifier(name) if ty.is_numeric() => match self.context.form(name) {
+                Some(FormKind::Global) => {}
+                Some(_) => self.error(
+                    format!("Argument {position} of {function} expects a number, {name} is a form"),
+                    span,
+                ),
+                None => self.unknown_form(name, span),
+            },
+            // Other parameters take names such as actor values or axes
+            Expr::Identifier(_) => {}
+            Expr::Int(_) | Expr::Float(_) | Expr::String(_) if ty.is_form() => {
+                self.error(
+                    format!("Argument {position} of {function} expects a form"),
+                    span,
+                );
+            }
+            Expr::String(_) if ty.is_numeric() => {
+                self.error(
+                    format!("Argument {position} of {function} expects a number"),
+                    span,
+                );
+            }
+            Expr::Variable(variable) => {
+                self.variable(variable, span);
+
+                let local = variable
+                    .reference
+                    .is_none()
+                    .then(|| self.ast.variable(&variable.name))
+                    .flatten();
+                if let Some(local) = local.filter(|local| local.ty != VariableType::Ref) {
+                    if ty.is_form() {
+                        self.error(
+                            format!(
+                                "Argument {position} of {function} expects a form, {} is a {} variable",
+                                local.name,
+                                local.ty.keyword()
+                            ),
+                            span,
+                        );
+                    }
+                }
+            }
+            _ => self.expr(arg, span),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr, span: Span) {
+        match expr {
+            Expr::Int(_) | Expr::Float(_) | Expr::String(_) => {}
+            Expr::Identifier(name) => {
+                if !Self::is_builtin(name) && self.context.form(name).is_none() {
+                    match name.starts_with("FormId_") {
+                        true => self.unknown_form(name, span),
+                        false => self.error(format!("Unknown name {name}"), span),
+                    }
+                }
+            }
+            Expr::Variable(variable) => self.variable(variable, span),
+            Expr::Call(call) => self.call(call),
+            Expr::Unary { operand, .. } => self.expr(operand, span),
+            Expr::Binary { lhs, rhs, .. } => {
+                self.expr(lhs, span);
+                self.expr(rhs, span);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::utils::HashMap;
+
+    use crate::{esp::record::sub::script::SCHRType, script::parser::parse};
+
+    use super::{lint, FormKind, LintContext, Severity};
+
+    struct Context {
+        forms: HashMap<&'static str, FormKind>,
+        quest_variables: Vec<String>,
+    }
+
+    impl LintContext for Context {
+        fn form(&self, editor_id: &str) -> Option<FormKind> {
+            self.forms.get(editor_id).copied()
+        }
+
+        fn script_variables(&self, editor_id: &str) -> Option<&[String]> {
+            (editor_id == "MQ01").then_some(self.quest_variables.as_slice())
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        let source = r#"scn TestScript
+
+short count
+float timer
+
+begin GameMode
+    set missing to 1
+    set MQ01.stage to count
+    set MQ01.unknown to 2
+    set TestGlobal to GetItemCount Caps001
+    AddItem UnknownItem 1
+    SetStage MQ01 timer
+    GetDistance 5
+    return
+    set count to 1
+end
+
+begin ScriptEffectStart
+end
+"#;
+        let context = Context {
+            forms: HashMap::from_iter([
+                ("MQ01", FormKind::Quest),
+                ("TestGlobal", FormKind::Global),
+                ("Caps001", FormKind::Other),
+            ]),
+            quest_variables: vec!["stage".to_string()],
+        };
+
+        let ast = parse(source).unwrap();
+        let diagnostics = lint(&ast, SCHRType::Object, &context);
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message.as_str()))
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                (Severity::Error, "Undeclared variable missing"),
+                (Severity::Error, "Script of MQ01 has no variable unknown"),
+                (Severity::Error, "Unknown editor ID UnknownItem"),
+                (Severity::Error, "Argument 1 of GetDistance expects a form"),
+                (Severity::Warning, "Unreachable code after return"),
+                (
+                    Severity::Warning,
+                    "ScriptEffectStart blocks never run in Object scripts"
+                ),
+            ]
+        );
+    }
+}
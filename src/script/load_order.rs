@@ -0,0 +1,716 @@
+//! Index of the forms and scripts within a load order, used to lint
+//! every script of a set of plugins
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::utils::HashMap;
+use thiserror::Error;
+
+use crate::esp::{
+    record::{
+        records::{scpt, term, tes4},
+        sub::{
+            script::{Reference, Script},
+            ACHR, ACRE, EDID, GLOB, NAME, PGRE, PMIS, QUST, REFR, SCPT, SCRI, TERM, TES4,
+        },
+        FromRecordBytes, RawEsmEntry, RawRecord, RecordFlags, RecordParseError,
+    },
+    shared::{EditorId, FormId},
+};
+
+use super::{
+    ast::Span,
+    bytecode::NameResolver,
+    lint::{lint_result_script, lint_script, Diagnostic, FormKind, LintContext, Severity},
+    parser::parse_result_script,
+};
+
+#[derive(Debug, Error)]
+pub enum LoadOrderError {
+    #[error("Failed to read {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("Failed to parse {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+}
+
+#[derive(Debug)]
+struct IndexedForm {
+    editor_id: Option<String>,
+    kind: FormKind,
+    /// Script attached to the form
+    script: Option<u32>,
+    /// Base object of references, which provides their script
+    base: Option<u32>,
+}
+
+/// Names and indices of the local variables of a script
+#[derive(Debug, Default)]
+struct ScriptVariables {
+    names: Vec<String>,
+    indices: Vec<u32>,
+}
+
+/// Script found within the load order
+#[derive(Debug)]
+pub struct IndexedScript {
+    /// Editor ID of the form the script belongs to, terminal menu items
+    /// are named after the terminal and the item number
+    pub name: String,
+    /// File name of the plugin that last defined the script
+    pub plugin: String,
+    pub script: Script,
+    /// Whether the script is a result script without any blocks
+    pub result: bool,
+}
+
+/// Diagnostics of a single script
+#[derive(Debug)]
+pub struct ScriptReport<'a> {
+    pub script: &'a IndexedScript,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Forms and scripts of the plugins of a load order, FormIds are
+/// stored relative to the load order rather than the plugin
+#[derive(Debug, Default)]
+pub struct LoadOrderIndex {
+    forms: HashMap<u32, IndexedForm>,
+    editor_ids: HashMap<String, u32>,
+    variables: HashMap<u32, ScriptVariables>,
+    /// Scripts by the FormId of the record containing them
+    scripts: HashMap<u32, Vec<IndexedScript>>,
+    /// Records that failed to parse, with the reason
+    pub failures: Vec<(String, u32, String)>,
+    /// Masters missing from the load order, with the plugin depending on them
+    pub missing_masters: Vec<(String, String)>,
+}
+
+/// Load order index given to FormIds of masters missing from the load order,
+/// it's used by forms created at runtime so no plugin can define them
+const UNRESOLVED_INDEX: u32 = 0xFF;
+
+/// Plugin currently being indexed
+struct Plugin {
+    name: String,
+    load_index: u32,
+    /// Load order index of each master of the plugin, [`None`] for
+    /// masters missing from the load order
+    masters: Vec<Option<u32>>,
+}
+
+impl Plugin {
+    /// Converts a FormId relative to the plugin into one relative to the load
+    /// order, [`None`] when the FormId belongs to a missing master
+    fn resolve(&self, form_id: u32) -> Option<u32> {
+        let load_index = match self.masters.get((form_id >> 24) as usize) {
+            Some(load_index) => (*load_index)?,
+            None => self.load_index,
+        };
+        Some((load_index << 24) | (form_id & 0x00FF_FFFF))
+    }
+
+    /// Resolves the references of the script, references to forms of
+    /// missing masters are given the [`UNRESOLVED_INDEX`]
+    fn resolve_script(&self, script: &mut Script) {
+        for reference in &mut script.references {
+            if let Reference::Object(form_id) = reference {
+                form_id.0 = self
+                    .resolve(form_id.0)
+                    .unwrap_or((UNRESOLVED_INDEX << 24) | (form_id.0 & 0x00FF_FFFF));
+            }
+        }
+    }
+}
+
+/// Describes a parse error without including the input nom failed on
+fn describe(error: RecordParseError) -> String {
+    match error {
+        RecordParseError::Nom(nom::Err::Error(error) | nom::Err::Failure(error)) => {
+            format!("{:?}", error.code)
+        }
+        error => error.to_string(),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl LoadOrderIndex {
+    /// Indexes the plugins in load order, masters must come before
+    /// the plugins depending on them
+    pub fn load(paths: &[PathBuf]) -> Result<Self, LoadOrderError> {
+        let names: Vec<String> = paths.iter().map(|path| file_name(path)).collect();
+        let mut index = Self::default();
+
+        for (load_index, path) in paths.iter().enumerate() {
+            let data = fs::read(path).map_err(|source| LoadOrderError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let parse_error = |message: String| LoadOrderError::Parse {
+                path: path.clone(),
+                message,
+            };
+
+            let (_, entries) = RawEsmEntry::parse_all(&data)
+                .map_err(|error| parse_error(describe(RecordParseError::Nom(error))))?;
+
+            let masters = match entries.first() {
+                Some(RawEsmEntry::Record(record)) if record.ty == TES4 => {
+                    record
+                        .parse_record::<tes4::TES4>()
+                        .map_err(|error| parse_error(describe(error)))?
+                        .masters
+                }
+                _ => Vec::new(),
+            };
+
+            let name = &names[load_index];
+            let masters = masters
+                .iter()
+                .map(|master| {
+                    // Masters have to be loaded before the plugin
+                    let master_index = names[..load_index]
+                        .iter()
+                        .position(|loaded| loaded.eq_ignore_ascii_case(master));
+                    if master_index.is_none() {
+                        index.missing_masters.push((name.clone(), master.clone()));
+                    }
+                    master_index.map(|master_index| master_index as u32)
+                })
+                .collect();
+
+            let plugin = Plugin {
+                name: name.clone(),
+                load_index: load_index as u32,
+                masters,
+            };
+
+            index
+                .add_entries(&entries, &plugin)
+                .map_err(|error| parse_error(describe(RecordParseError::Nom(error))))?;
+        }
+
+        Ok(index)
+    }
+
+    fn add_entries<'a>(
+        &mut self,
+        entries: &[RawEsmEntry<'a>],
+        plugin: &Plugin,
+    ) -> Result<(), nom::Err<nom::error::Error<&'a [u8]>>> {
+        for entry in entries {
+            match entry {
+                RawEsmEntry::Group(group) => {
+                    let (_, entries) = RawEsmEntry::parse_all(group.data)?;
+                    self.add_entries(&entries, plugin)?;
+                }
+                RawEsmEntry::Record(record) if record.ty != TES4 => {
+                    self.add_record(record, plugin);
+                }
+                RawEsmEntry::Record(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn add_record(&mut self, record: &RawRecord, plugin: &Plugin) {
+        // Overrides of forms from missing masters can't be placed
+        let form_id = match plugin.resolve(record.form_id) {
+            Some(form_id) => form_id,
+            None => return,
+        };
+        let kind = match record.ty {
+            QUST => FormKind::Quest,
+            REFR | ACHR | ACRE | PGRE | PMIS => FormKind::Reference,
+            GLOB => FormKind::Global,
+            _ => FormKind::Other,
+        };
+
+        let mut form = IndexedForm {
+            editor_id: None,
+            kind,
+            script: None,
+            base: None,
+        };
+
+        // Compressed records are inflated first, records that fail to
+        // inflate are only indexed by their FormId
+        let inflated;
+        let raw;
+        let record = if record.flags.contains(RecordFlags::COMPRESSED) {
+            match record.decompress() {
+                Ok(value) => {
+                    inflated = value;
+                    raw = inflated.as_raw();
+                    Some(&raw)
+                }
+                Err(error) => {
+                    self.failures.push((
+                        plugin.name.clone(),
+                        form_id,
+                        format!("Failed to decompress record: {error}"),
+                    ));
+                    None
+                }
+            }
+        } else {
+            Some(record)
+        };
+
+        if let Some(record) = record {
+            if let Ok((_, subrecords)) = record.parse_inner() {
+                for subrecord in subrecords {
+                    let form_id = || FormId::parse(subrecord.data).ok().map(|(_, id)| id.0);
+                    match subrecord.ty {
+                        EDID => {
+                            form.editor_id = EditorId::parse(subrecord.data)
+                                .ok()
+                                .map(|(_, editor_id)| editor_id.0)
+                        }
+                        SCRI => form.script = form_id().and_then(|id| plugin.resolve(id)),
+                        NAME if kind == FormKind::Reference => {
+                            form.base = form_id().and_then(|id| plugin.resolve(id))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            self.add_scripts(record, form_id, form.editor_id.as_deref(), plugin);
+        }
+
+        if let Some(editor_id) = &form.editor_id {
+            self.editor_ids
+                .insert(editor_id.to_ascii_lowercase(), form_id);
+        }
+        self.forms.insert(form_id, form);
+    }
+
+    fn add_scripts(
+        &mut self,
+        record: &RawRecord,
+        form_id: u32,
+        editor_id: Option<&str>,
+        plugin: &Plugin,
+    ) {
+        let name = editor_id.map_or_else(|| format!("{form_id:08X}"), str::to_string);
+        let mut failure = |error| {
+            self.failures
+                .push((plugin.name.clone(), form_id, describe(error)))
+        };
+
+        let scripts = match record.ty {
+            SCPT => match record.parse_record::<scpt::SCPT>() {
+                Ok(scpt::SCPT { mut script, .. }) => {
+                    plugin.resolve_script(&mut script);
+                    vec![(name, script, false)]
+                }
+                Err(error) => return failure(error),
+            },
+            TERM => match record.parse_record::<term::TERM>() {
+                Ok(term) => term
+                    .menu_items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        let mut script = item.embedded_script;
+                        plugin.resolve_script(&mut script);
+                        (format!("{name} item {}", index + 1), script, true)
+                    })
+                    .collect(),
+                Err(error) => return failure(error),
+            },
+            _ => return,
+        };
+
+        if record.ty == SCPT {
+            if let Some((_, script, _)) = scripts.first() {
+                let variables = ScriptVariables {
+                    names: script
+                        .local_variables
+                        .iter()
+                        .map(|variable| variable.name.clone())
+                        .collect(),
+                    indices: script
+                        .local_variables
+                        .iter()
+                        .map(|variable| variable.data.index)
+                        .collect(),
+                };
+                self.variables.insert(form_id, variables);
+            }
+        }
+
+        let scripts = scripts
+            .into_iter()
+            .map(|(name, script, result)| IndexedScript {
+                name,
+                plugin: plugin.name.clone(),
+                script,
+                result,
+            })
+            .collect();
+        // Scripts of overridden records are replaced
+        self.scripts.insert(form_id, scripts);
+    }
+
+    fn form_by_editor_id(&self, editor_id: &str) -> Option<&IndexedForm> {
+        self.editor_ids
+            .get(&editor_id.to_ascii_lowercase())
+            .and_then(|form_id| self.forms.get(form_id))
+    }
+
+    /// Script attached to the form, references use the script of their base object
+    fn attached_script(&self, form: &IndexedForm) -> Option<&ScriptVariables> {
+        let script = match form.kind {
+            FormKind::Reference => form
+                .base
+                .and_then(|base| self.forms.get(&base))
+                .and_then(|base| base.script),
+            _ => form.script,
+        };
+        script.and_then(|script| self.variables.get(&script))
+    }
+
+    /// Every script in the load order, unordered
+    pub fn scripts(&self) -> impl Iterator<Item = &IndexedScript> {
+        self.scripts.values().flatten()
+    }
+
+    /// Lints every script, ordered by plugin and name
+    pub fn lint(&self) -> Vec<ScriptReport<'_>> {
+        let mut scripts: Vec<&IndexedScript> = self.scripts().collect();
+        scripts.sort_by(|a, b| (&a.plugin, &a.name).cmp(&(&b.plugin, &b.name)));
+
+        scripts
+            .into_iter()
+            .map(|script| {
+                let diagnostics = self
+                    .lint_indexed(script)
+                    .unwrap_or_else(|diagnostic| vec![diagnostic]);
+                ScriptReport {
+                    script,
+                    diagnostics,
+                }
+            })
+            .collect()
+    }
+
+    fn lint_indexed(&self, indexed: &IndexedScript) -> Result<Vec<Diagnostic>, Diagnostic> {
+        let source = &indexed.script.source;
+
+        if indexed.result {
+            // Result scripts without source have nothing to check
+            if source.trim().is_empty() {
+                return Ok(Vec::new());
+            }
+            let statements = parse_result_script(source).map_err(|error| Diagnostic {
+                severity: Severity::Error,
+                message: error.message,
+                span: error.span,
+            })?;
+            return Ok(lint_result_script(&statements, self));
+        }
+
+        lint_script(&indexed.script, self).map_err(|error| Diagnostic {
+            severity: Severity::Error,
+            message: error.to_string(),
+            span: Span::default(),
+        })
+    }
+}
+
+impl LintContext for LoadOrderIndex {
+    fn form(&self, editor_id: &str) -> Option<FormKind> {
+        self.form_by_editor_id(editor_id).map(|form| form.kind)
+    }
+
+    fn script_variables(&self, editor_id: &str) -> Option<&[String]> {
+        let form = self.form_by_editor_id(editor_id)?;
+        self.attached_script(form)
+            .map(|variables| variables.names.as_slice())
+    }
+
+    fn form_exists(&self, form_id: &FormId) -> bool {
+        // Forms of missing masters can't be checked, the missing
+        // master is reported instead
+        form_id.0 >> 24 == UNRESOLVED_INDEX || self.forms.contains_key(&form_id.0)
+    }
+}
+
+impl NameResolver for LoadOrderIndex {
+    fn form_name(&self, form_id: &FormId) -> Option<String> {
+        self.forms.get(&form_id.0)?.editor_id.clone()
+    }
+
+    fn variable_name(&self, form_id: &FormId, index: u32) -> Option<String> {
+        let variables = self.attached_script(self.forms.get(&form_id.0)?)?;
+        let position = variables.indices.iter().position(|&value| value == index)?;
+        Some(variables.names[position].clone())
+    }
+}
+
+/// Command line entry point, lints the scripts of the plugin files
+/// provided as arguments and returns the exit code
+pub fn run_lint(paths: impl Iterator<Item = String>) -> i32 {
+    let paths: Vec<PathBuf> = paths.map(PathBuf::from).collect();
+    if paths.is_empty() {
+        eprintln!("Usage: lint_scripts <plugin>...");
+        return 2;
+    }
+
+    let index = match LoadOrderIndex::load(&paths) {
+        Ok(index) => index,
+        Err(error) => {
+            eprintln!("{error}");
+            return 2;
+        }
+    };
+
+    for (plugin, master) in &index.missing_masters {
+        eprintln!(
+            "{plugin}: warning: Master {master} is missing from the load order, its forms are not checked"
+        );
+    }
+
+    for (plugin, form_id, message) in &index.failures {
+        eprintln!("{plugin}: {form_id:08X}: warning: Failed to parse record: {message}");
+    }
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    for report in index.lint() {
+        let script = report.script;
+        for diagnostic in &report.diagnostics {
+            let (line, column) = diagnostic.span.location(&script.script.source);
+            let severity = match diagnostic.severity {
+                Severity::Error => {
+                    errors += 1;
+                    "error"
+                }
+                Severity::Warning => {
+                    warnings += 1;
+                    "warning"
+                }
+            };
+            println!(
+                "{}: {}:{line}:{column}: {severity}: {}",
+                script.plugin, script.name, diagnostic.message
+            );
+        }
+    }
+
+    println!("{errors} errors, {warnings} warnings");
+    i32::from(errors > 0)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{
+        esp::{
+            record::{
+                sub::{
+                    script::{Reference, SCHRFlags, SCHRType, Script, SCHR},
+                    DOOR,
+                },
+                RawRecord, RecordFlags,
+            },
+            shared::FormId,
+        },
+        script::{
+            bytecode::{decompile, matches_source, NameResolver},
+            lint::{FormKind, LintContext},
+        },
+    };
+
+    use super::{IndexedForm, LoadOrderIndex, Plugin, ScriptVariables};
+
+    #[test]
+    fn test_resolve() {
+        // Third plugin in the load order, depending only on the second
+        let plugin = Plugin {
+            name: "Patch.esp".to_string(),
+            load_index: 2,
+            masters: vec![Some(1)],
+        };
+
+        assert_eq!(plugin.resolve(0x0000_0ABC), Some(0x0100_0ABC));
+        assert_eq!(plugin.resolve(0x0100_0ABC), Some(0x0200_0ABC));
+        // Indices past the masters are the plugin's own forms
+        assert_eq!(plugin.resolve(0x0500_0ABC), Some(0x0200_0ABC));
+    }
+
+    /// FormIds of missing masters stay unresolved rather than being
+    /// treated as forms of the plugin
+    #[test]
+    fn test_missing_master() {
+        let plugin = Plugin {
+            name: "Patch.esp".to_string(),
+            load_index: 1,
+            masters: vec![None],
+        };
+
+        assert_eq!(plugin.resolve(0x0000_0ABC), None);
+        assert_eq!(plugin.resolve(0x0100_0ABC), Some(0x0100_0ABC));
+
+        let mut script = Script {
+            basic_data: SCHR {
+                ref_count: 2,
+                compiled_size: 0,
+                variable_count: 0,
+                ty: SCHRType::Object,
+                flags: SCHRFlags::ENABLED,
+            },
+            compiled_source: Vec::new(),
+            source: String::new(),
+            local_variables: Vec::new(),
+            references: vec![
+                Reference::Object(FormId(0x0000_0ABC)),
+                Reference::Object(FormId(0x0100_0DEF)),
+            ],
+        };
+        plugin.resolve_script(&mut script);
+
+        let index = LoadOrderIndex::default();
+        let exists: Vec<bool> = script
+            .references
+            .iter()
+            .map(|reference| match reference {
+                Reference::Object(form_id) => index.form_exists(form_id),
+                Reference::Variable(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(exists, [true, false]);
+    }
+
+    #[test]
+    fn test_attached_script() {
+        let form = |editor_id: &str, kind, script, base| IndexedForm {
+            editor_id: Some(editor_id.to_string()),
+            kind,
+            script,
+            base,
+        };
+
+        let mut index = LoadOrderIndex::default();
+        index.forms.extend([
+            (0x10, form("DoorScript", FormKind::Other, None, None)),
+            (0x20, form("VaultDoor", FormKind::Other, Some(0x10), None)),
+            (
+                0x30,
+                form("VaultDoorRef", FormKind::Reference, None, Some(0x20)),
+            ),
+        ]);
+        index.editor_ids.extend([
+            ("vaultdoor".to_string(), 0x20),
+            ("vaultdoorref".to_string(), 0x30),
+        ]);
+        index.variables.insert(
+            0x10,
+            ScriptVariables {
+                names: vec!["isOpen".to_string(), "timer".to_string()],
+                indices: vec![1, 3],
+            },
+        );
+
+        let names = ["isOpen".to_string(), "timer".to_string()];
+        assert_eq!(
+            index.script_variables("VaultDoorRef"),
+            Some(names.as_slice())
+        );
+        assert_eq!(
+            index.variable_name(&FormId(0x30), 3).as_deref(),
+            Some("timer")
+        );
+        assert_eq!(index.variable_name(&FormId(0x30), 2), None);
+    }
+
+    /// Zlib stream storing the data in a single uncompressed block
+    fn zlib_stored(data: &[u8]) -> Vec<u8> {
+        let (mut a, mut b) = (1u32, 0u32);
+        for &byte in data {
+            a = (a + byte as u32) % 65521;
+            b = (b + a) % 65521;
+        }
+
+        let length = data.len() as u16;
+        [
+            &[0x78, 0x01, 0x01][..],
+            &length.to_le_bytes(),
+            &(!length).to_le_bytes(),
+            data,
+            &((b << 16) | a).to_be_bytes(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_compressed_record() {
+        let subrecords = [
+            &b"EDID\x0a\x00VaultDoor\0"[..],
+            b"SCRI\x04\x00",
+            &0x0000_0010u32.to_le_bytes(),
+        ]
+        .concat();
+        let data = [
+            &(subrecords.len() as u32).to_le_bytes()[..],
+            &zlib_stored(&subrecords),
+        ]
+        .concat();
+        let record = RawRecord {
+            ty: DOOR,
+            flags: RecordFlags::COMPRESSED,
+            form_id: 0x0000_0020,
+            revision: 0,
+            version: 15,
+            data: &data,
+        };
+        let plugin = Plugin {
+            name: "Test.esm".to_string(),
+            load_index: 0,
+            masters: Vec::new(),
+        };
+
+        let mut index = LoadOrderIndex::default();
+        index.add_record(&record, &plugin);
+
+        assert!(index.failures.is_empty());
+        assert_eq!(index.form("vaultdoor"), Some(FormKind::Other));
+        assert_eq!(index.forms[&0x20].script, Some(0x10));
+    }
+
+    /// Decompiles every script of the vanilla master using the names from
+    /// the index and compares it with the source
+    #[test]
+    #[ignore = "requires FalloutNV.esm"]
+    fn test_vanilla_scripts() {
+        let index = LoadOrderIndex::load(&[PathBuf::from("../Data/FalloutNV.esm")]).unwrap();
+        let scripts: Vec<_> = index.scripts().filter(|indexed| !indexed.result).collect();
+        assert!(!scripts.is_empty());
+
+        let mismatched: Vec<&str> = scripts
+            .iter()
+            .filter(|indexed| {
+                !decompile(&indexed.script, &index)
+                    .is_ok_and(|ast| matches_source(&ast, &indexed.script.source))
+            })
+            .map(|indexed| indexed.name.as_str())
+            .collect();
+
+        assert!(
+            mismatched.is_empty(),
+            "{} of {} scripts differ from their source: {mismatched:?}",
+            mismatched.len(),
+            scripts.len()
+        );
+    }
+}
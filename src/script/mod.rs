@@ -0,0 +1,11 @@
+//! Scripting language used by object, quest and effect scripts
+
+pub mod ast;
+pub mod bytecode;
+pub mod condition;
+pub mod functions;
+pub mod interpreter;
+pub mod lexer;
+pub mod lint;
+pub mod load_order;
+pub mod parser;
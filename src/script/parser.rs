@@ -0,0 +1,622 @@
+//! Parser for the script source (SCTX) into a [`ScriptAst`]
+
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use super::{
+    ast::{
+        BinaryOp, Block, Branch, Call, Expr, ScriptAst, Span, Statement, StatementKind, UnaryOp,
+        VariableDecl, VariableRef, VariableType,
+    },
+    functions,
+    lexer::{tokenize, Token, TokenKind},
+};
+
+#[derive(Debug, Error)]
+#[error("{message} at {}..{}", .span.start, .span.end)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte range within the source the error occurred at
+    pub span: Span,
+}
+
+impl ParseError {
+    pub fn new(message: impl Display, span: Span) -> Self {
+        Self {
+            message: message.to_string(),
+            span,
+        }
+    }
+
+    /// 1-based line and column of the start of the error within the source
+    pub fn location(&self, source: &str) -> (usize, usize) {
+        self.span.location(source)
+    }
+}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+/// Parses script source code
+pub fn parse(source: &str) -> ParseResult<ScriptAst> {
+    let tokens = tokenize(source)?;
+    Parser {
+        tokens: &tokens,
+        position: 0,
+        end: source.len(),
+        variables: Vec::new(),
+    }
+    .script()
+}
+
+/// Parses a result script, such as those of terminal menu items, which
+/// only contains statements
+pub fn parse_result_script(source: &str) -> ParseResult<Vec<Statement>> {
+    let tokens = tokenize(source)?;
+    Parser {
+        tokens: &tokens,
+        position: 0,
+        end: source.len(),
+        variables: Vec::new(),
+    }
+    .statements(&[])
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    /// Length of the source for reporting errors at the end
+    end: usize,
+    /// Names of the declared variables, used to tell variables
+    /// apart from other identifiers
+    variables: Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_nth(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    /// Span of the next token or the end of the source
+    fn span(&self) -> Span {
+        self.peek()
+            .map(|token| token.span)
+            .unwrap_or(Span::new(self.end, self.end))
+    }
+
+    /// End of the previously consumed token
+    fn previous_end(&self) -> usize {
+        self.position
+            .checked_sub(1)
+            .and_then(|position| self.tokens.get(position))
+            .map_or(0, |token| token.span.end)
+    }
+
+    fn error<T>(&self, message: impl Display) -> ParseResult<T> {
+        Err(ParseError::new(message, self.span()))
+    }
+
+    fn at_line_end(&self) -> bool {
+        match self.peek() {
+            Some(token) => token.kind == TokenKind::Newline,
+            None => true,
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|token| token.kind == TokenKind::Newline)
+        {
+            self.position += 1;
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_keyword(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self.peek_keyword(keyword);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn peek_symbol(&self, symbol: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_symbol(symbol))
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let matches = self.peek_symbol(symbol);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            self.error(format!("Expected {keyword:?}"))
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> ParseResult<()> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            self.error(format!("Expected {symbol:?}"))
+        }
+    }
+
+    fn identifier(&mut self) -> ParseResult<String> {
+        match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::Identifier(name)) => {
+                self.position += 1;
+                Ok(name.clone())
+            }
+            _ => self.error("Expected an identifier"),
+        }
+    }
+
+    /// Requires the statement ends with a line break or the end of the source
+    fn end_line(&mut self) -> ParseResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) if token.kind == TokenKind::Newline => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(_) => self.error("Expected the end of the line"),
+        }
+    }
+
+    fn is_variable(&self, name: &str) -> bool {
+        self.variables
+            .iter()
+            .any(|variable| variable.eq_ignore_ascii_case(name))
+    }
+
+    fn script(mut self) -> ParseResult<ScriptAst> {
+        let mut ast = ScriptAst::default();
+        self.skip_newlines();
+
+        if self.eat_keyword("scn") || self.eat_keyword("scriptname") {
+            ast.name = Some(self.identifier()?);
+            self.end_line()?;
+        }
+
+        loop {
+            self.skip_newlines();
+            let Some(token) = self.peek() else {
+                break;
+            };
+
+            if token.is_keyword("begin") {
+                ast.blocks.push(self.block()?);
+            } else if let Some(variable) = self.declaration()? {
+                ast.variables.push(variable);
+            } else {
+                return self.error("Expected a variable declaration or begin block");
+            }
+        }
+
+        Ok(ast)
+    }
+
+    fn declaration(&mut self) -> ParseResult<Option<VariableDecl>> {
+        let Some(token) = self.peek() else {
+            return Ok(None);
+        };
+        let ty = match &token.kind {
+            TokenKind::Identifier(keyword) => VariableType::from_keyword(keyword),
+            _ => None,
+        };
+        let Some(ty) = ty else {
+            return Ok(None);
+        };
+
+        self.position += 1;
+        let name = self.identifier()?;
+        let span = token
+            .span
+            .to(Span::new(self.previous_end(), self.previous_end()));
+        self.end_line()?;
+
+        if self.is_variable(&name) {
+            return Err(ParseError::new(
+                format!("Variable {name} is already declared"),
+                span,
+            ));
+        }
+        self.variables.push(name.clone());
+
+        Ok(Some(VariableDecl { ty, name, span }))
+    }
+
+    fn block(&mut self) -> ParseResult<Block> {
+        let start = self.span().start;
+        self.expect_keyword("begin")?;
+        let name = self.identifier()?;
+
+        let mut args = Vec::new();
+        while !self.at_line_end() {
+            args.push(self.argument()?);
+            self.eat_symbol(",");
+        }
+        self.end_line()?;
+
+        let body = self.statements(&["end"])?;
+        if !self.eat_keyword("end") {
+            return Err(ParseError::new(
+                format!("Missing end for begin {name}"),
+                Span::new(start, self.previous_end()),
+            ));
+        }
+        let span = Span::new(start, self.previous_end());
+        // Blocks may be followed by the name of the block type
+        while !self.at_line_end() {
+            self.next();
+        }
+
+        Ok(Block {
+            name,
+            args,
+            body,
+            span,
+        })
+    }
+
+    /// Parses statements until one of the terminating keywords
+    fn statements(&mut self, terminators: &[&str]) -> ParseResult<Vec<Statement>> {
+        let mut statements = Vec::new();
+
+        loop {
+            self.skip_newlines();
+            let Some(token) = self.peek() else {
+                return Ok(statements);
+            };
+            if terminators.iter().any(|keyword| token.is_keyword(keyword)) {
+                return Ok(statements);
+            }
+            statements.push(self.statement()?);
+        }
+    }
+
+    fn statement(&mut self) -> ParseResult<Statement> {
+        let start = self.span().start;
+
+        let kind = if self.eat_keyword("set") {
+            let target = self.variable_ref()?;
+            self.expect_keyword("to")?;
+            let value = self.expression()?;
+            StatementKind::Set { target, value }
+        } else if self.eat_keyword("let") {
+            let target = self.variable_ref()?;
+            self.expect_symbol(":=")?;
+            let value = self.expression()?;
+            StatementKind::Set { target, value }
+        } else if self.peek_keyword("if") {
+            return self.if_statement();
+        } else if self.eat_keyword("return") {
+            StatementKind::Return
+        } else if ["elseif", "else", "endif", "begin"]
+            .iter()
+            .any(|keyword| self.peek_keyword(keyword))
+            || self
+                .peek()
+                .and_then(|token| match &token.kind {
+                    TokenKind::Identifier(name) => VariableType::from_keyword(name),
+                    _ => None,
+                })
+                .is_some()
+        {
+            return self.error("Unexpected keyword");
+        } else {
+            let call = self.call(None)?;
+            StatementKind::Call(call)
+        };
+
+        let span = Span::new(start, self.previous_end());
+        self.end_line()?;
+        Ok(Statement { kind, span })
+    }
+
+    fn if_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.span();
+        self.expect_keyword("if")?;
+
+        let mut branches = Vec::new();
+        let mut otherwise = None;
+
+        let condition = self.expression()?;
+        self.end_line()?;
+        let body = self.statements(&["elseif", "else", "endif", "end"])?;
+        branches.push(Branch { condition, body });
+
+        loop {
+            if self.eat_keyword("elseif") {
+                let condition = self.expression()?;
+                self.end_line()?;
+                let body = self.statements(&["elseif", "else", "endif", "end"])?;
+                branches.push(Branch { condition, body });
+            } else if self.eat_keyword("else") {
+                self.end_line()?;
+                otherwise = Some(self.statements(&["elseif", "else", "endif", "end"])?);
+                if self.peek_keyword("elseif") || self.peek_keyword("else") {
+                    return self.error("Unexpected branch after else");
+                }
+            } else if self.eat_keyword("endif") {
+                break;
+            } else {
+                return Err(ParseError::new("Missing endif for if", start));
+            }
+        }
+
+        let span = start.to(Span::new(self.previous_end(), self.previous_end()));
+        self.end_line()?;
+
+        Ok(Statement {
+            kind: StatementKind::If {
+                branches,
+                otherwise,
+            },
+            span,
+        })
+    }
+
+    /// Variable optionally accessed through a reference (`reference.variable`)
+    fn variable_ref(&mut self) -> ParseResult<VariableRef> {
+        let name = self.identifier()?;
+        if self.eat_symbol(".") {
+            let variable = self.identifier()?;
+            return Ok(VariableRef {
+                reference: Some(name),
+                name: variable,
+            });
+        }
+        Ok(VariableRef {
+            reference: None,
+            name,
+        })
+    }
+
+    /// Function call, optionally on a reference. Within expressions
+    /// `max_args` limits the arguments to those the function accepts
+    fn call(&mut self, max_args: Option<usize>) -> ParseResult<Call> {
+        let start = self.span().start;
+        let mut reference = None;
+        let mut function = self.identifier()?;
+        if self.eat_symbol(".") {
+            reference = Some(function);
+            function = self.identifier()?;
+        }
+
+        let max_args = max_args.unwrap_or(usize::MAX);
+        let mut args = Vec::new();
+        while self.starts_argument() && args.len() < max_args {
+            args.push(self.argument()?);
+            self.eat_symbol(",");
+        }
+
+        Ok(Call {
+            reference,
+            function,
+            args,
+            span: Span::new(start, self.previous_end()),
+        })
+    }
+
+    fn starts_argument(&self) -> bool {
+        match self.peek().map(|token| &token.kind) {
+            Some(
+                TokenKind::Identifier(_)
+                | TokenKind::Int(_)
+                | TokenKind::Float(_)
+                | TokenKind::String(_),
+            ) => true,
+            Some(TokenKind::Symbol("(")) => true,
+            // Negative numbers, other uses of - are binary operators
+            Some(TokenKind::Symbol("-")) => matches!(
+                self.peek_nth(1).map(|token| &token.kind),
+                Some(TokenKind::Int(_) | TokenKind::Float(_))
+            ),
+            _ => false,
+        }
+    }
+
+    /// Argument of a function call or block, function calls are only
+    /// allowed within parentheses
+    fn argument(&mut self) -> ParseResult<Expr> {
+        if self.peek_symbol("(") {
+            return self.primary();
+        }
+        if self.eat_symbol("-") {
+            return Ok(negate(self.argument()?));
+        }
+
+        match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::Identifier(_)) => {
+                let variable = self.variable_ref()?;
+                Ok(self.name_expr(variable))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    /// Turns a name that isn't a function call into a variable or identifier
+    fn name_expr(&self, variable: VariableRef) -> Expr {
+        if variable.reference.is_some() || self.is_variable(&variable.name) {
+            Expr::Variable(variable)
+        } else {
+            Expr::Identifier(variable.name)
+        }
+    }
+
+    fn expression(&mut self) -> ParseResult<Expr> {
+        self.binary(1)
+    }
+
+    /// Precedence climbing over the binary operators
+    fn binary(&mut self, min_precedence: u8) -> ParseResult<Expr> {
+        let mut lhs = self.unary()?;
+
+        loop {
+            let op = match self.peek().map(|token| &token.kind) {
+                Some(TokenKind::Symbol(symbol)) => BinaryOp::from_symbol(symbol),
+                _ => None,
+            };
+            let Some(op) = op.filter(|op| op.precedence() >= min_precedence) else {
+                return Ok(lhs);
+            };
+
+            self.position += 1;
+            let rhs = self.binary(op.precedence() + 1)?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> ParseResult<Expr> {
+        if self.eat_symbol("-") {
+            return Ok(negate(self.unary()?));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> ParseResult<Expr> {
+        let Some(token) = self.peek() else {
+            return self.error("Expected an expression");
+        };
+
+        let expr = match &token.kind {
+            TokenKind::Int(value) => Expr::Int(*value),
+            TokenKind::Float(value) => Expr::Float(*value),
+            TokenKind::String(value) => Expr::String(value.clone()),
+            TokenKind::Symbol("(") => {
+                self.position += 1;
+                let expr = self.expression()?;
+                self.expect_symbol(")")?;
+                return Ok(expr);
+            }
+            TokenKind::Identifier(_) => return self.name_or_call(),
+            _ => return self.error("Expected an expression"),
+        };
+
+        self.position += 1;
+        Ok(expr)
+    }
+
+    /// Name within an expression, which is a function call when it
+    /// names a known function
+    fn name_or_call(&mut self) -> ParseResult<Expr> {
+        let start = self.position;
+        let variable = self.variable_ref()?;
+
+        if self.is_variable(&variable.name) && variable.reference.is_none() {
+            return Ok(Expr::Variable(variable));
+        }
+
+        match functions::by_name(&variable.name) {
+            Some(function) => {
+                self.position = start;
+                Ok(Expr::Call(self.call(Some(function.params.len()))?))
+            }
+            None => Ok(self.name_expr(variable)),
+        }
+    }
+}
+
+fn negate(expr: Expr) -> Expr {
+    match expr {
+        Expr::Int(value) => Expr::Int(-value),
+        Expr::Float(value) => Expr::Float(-value),
+        operand => Expr::Unary {
+            op: UnaryOp::Neg,
+            operand: Box::new(operand),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse;
+    use crate::script::ast::{BinaryOp, Expr, StatementKind, VariableType};
+
+    #[test]
+    fn test_parse() {
+        let source = r#"scn TestScript
+
+short doOnce
+float timer ; Seconds
+
+begin GameMode
+    if doOnce == 0 && GetStage MQ01 >= 10
+        player.AddItem Caps001, 5
+        set timer to timer + GetSecondsPassed * -2
+    elseif (doOnce)
+        return
+    else
+        ShowMessage "Done"
+    endif
+end
+"#;
+
+        let ast = parse(source).unwrap();
+        assert_eq!(ast.name.as_deref(), Some("TestScript"));
+        assert_eq!(ast.variables[1].ty, VariableType::Float);
+
+        let StatementKind::If {
+            branches,
+            otherwise,
+        } = &ast.blocks[0].body[0].kind
+        else {
+            panic!("expected an if statement");
+        };
+        assert_eq!(branches.len(), 2);
+        assert!(otherwise.is_some());
+
+        // && binds looser than the comparisons and GetStage takes one argument
+        let Expr::Binary { op, rhs, .. } = &branches[0].condition else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!(*op, BinaryOp::And);
+        assert!(matches!(
+            **rhs,
+            Expr::Binary {
+                op: BinaryOp::Ge,
+                ..
+            }
+        ));
+
+        let StatementKind::Call(call) = &branches[0].body[0].kind else {
+            panic!("expected a call");
+        };
+        assert_eq!(call.reference.as_deref(), Some("player"));
+        assert_eq!(call.args.len(), 2);
+    }
+
+    #[test]
+    fn test_error_span() {
+        let source = "scn Broken\n\nbegin GameMode\n    if 1\nend\n";
+        let error = parse(source).unwrap_err();
+        assert_eq!(error.message, "Missing endif for if");
+        assert_eq!(error.location(source), (4, 5));
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::Resource;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs::File;
 
 #[derive(Deserialize, Resource)]
@@ -72,10 +73,23 @@ pub struct Archive {
     pub SArchiveList: String,
 }
 
-pub fn load_config() -> GameConfiguration {
+/// Every value of the configuration by section then name, including
+/// the ones that aren't part of [`GameConfiguration`]
+#[derive(Default, Deserialize, Resource)]
+#[serde(transparent)]
+pub struct IniSettings {
+    pub sections: HashMap<String, HashMap<String, String>>,
+}
+
+/// Loads the configuration, the file is read once then deserialized into
+/// both the known settings and every value by section
+pub fn load_config() -> (GameConfiguration, IniSettings) {
     // TODO: Properly load configuration from Documents/My Games
-    let file: String =
+    let config =
         std::fs::read_to_string("Fallout.ini").expect("Failed to read Fallout.ini configuration");
-
-    serde_ini::from_str(&file).expect("Failed to load config")
+    let game_config =
+        serde_ini::from_str(&config).expect("Failed to parse Fallout.ini configuration");
+    let ini_settings =
+        serde_ini::from_str(&config).expect("Failed to parse Fallout.ini settings by section");
+    (game_config, ini_settings)
 }
@@ -0,0 +1,317 @@
+//! Runtime access to form lists (`FLST`), which scripts can add forms to
+
+use std::sync::Arc;
+
+use bevy::{
+    ecs::system::Resource,
+    utils::{HashMap, HashSet},
+};
+use thiserror::Error;
+
+use crate::{
+    esp::{
+        record::{records::flst::FLST, Record, RecordType},
+        shared::{FormId, TypedFormId},
+        store::Store,
+    },
+    script::interpreter::{ScriptError, ScriptFunctions, Value},
+};
+
+#[derive(Debug, Error)]
+pub enum FormListError {
+    #[error("Unknown form list {:08X}", .0 .0)]
+    UnknownList(FormId),
+}
+
+/// Form lists backed by the [`Store`], changes made by scripts are kept
+/// in its dynamic values so they are persisted with the rest of the
+/// runtime state
+#[derive(Clone, Resource)]
+pub struct FormLists {
+    store: Store<FLST>,
+    /// Store keys of the lists by their FormId
+    keys: Arc<HashMap<FormId, String>>,
+}
+
+impl FormLists {
+    pub fn new(store: Store<FLST>, keys: impl IntoIterator<Item = (FormId, String)>) -> Self {
+        Self {
+            store,
+            keys: Arc::new(keys.into_iter().collect()),
+        }
+    }
+
+    /// Whether the form is a form list
+    pub fn is_list(&self, form_id: &FormId) -> bool {
+        self.keys.contains_key(form_id)
+    }
+
+    fn with<T>(&self, list: &FormId, action: impl FnOnce(&FLST) -> T) -> Option<T> {
+        let key = self.keys.get(list)?;
+        self.store.with(key, action)
+    }
+
+    /// Direct members of the list, nested lists are not expanded
+    pub fn members(&self, list: &FormId) -> Option<Vec<FormId>> {
+        self.with(list, |flst| flst.form_ids.clone())
+    }
+
+    pub fn len(&self, list: &FormId) -> Option<usize> {
+        self.with(list, |flst| flst.form_ids.len())
+    }
+
+    /// Form at the index of the list (`ListGetNthForm`)
+    pub fn get(&self, list: &FormId, index: usize) -> Option<FormId> {
+        self.with(list, |flst| flst.form_ids.get(index).cloned())
+            .flatten()
+    }
+
+    /// Index of the form within the list (`ListGetFormIndex`)
+    pub fn index_of(&self, list: &FormId, form_id: &FormId) -> Option<usize> {
+        self.with(list, |flst| {
+            flst.form_ids.iter().position(|member| member == form_id)
+        })
+        .flatten()
+    }
+
+    /// Whether the form is a direct member of the list
+    pub fn contains(&self, list: &FormId, form_id: &FormId) -> bool {
+        self.index_of(list, form_id).is_some()
+    }
+
+    /// Whether the form is a member of the list or any of the lists nested within it
+    pub fn contains_nested(&self, list: &FormId, form_id: &FormId) -> bool {
+        self.flatten(list).contains(form_id)
+    }
+
+    /// Members of the list with nested lists replaced by their own members,
+    /// lists containing themselves are only expanded once
+    pub fn flatten(&self, list: &FormId) -> Vec<FormId> {
+        let mut members = Vec::new();
+        let mut visited = HashSet::new();
+        self.flatten_into(list, &mut members, &mut visited);
+        members
+    }
+
+    fn flatten_into(
+        &self,
+        list: &FormId,
+        members: &mut Vec<FormId>,
+        visited: &mut HashSet<FormId>,
+    ) {
+        if !visited.insert(list.clone()) {
+            return;
+        }
+
+        for member in self.members(list).unwrap_or_default() {
+            if self.is_list(&member) {
+                self.flatten_into(&member, members, visited);
+            } else if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+    }
+
+    /// Members of the list and its nested lists that are records of type `R`,
+    /// using `record_type` to find the type of each member
+    pub fn typed_members<R: Record>(
+        &self,
+        list: &FormId,
+        record_type: impl Fn(&FormId) -> Option<RecordType>,
+    ) -> Vec<TypedFormId<R>> {
+        self.flatten(list)
+            .into_iter()
+            .filter(|member| record_type(member) == Some(R::TYPE))
+            .map(FormId::into_typed)
+            .collect()
+    }
+
+    /// Adds the form to the list (`AddFormToFormList`), added forms are placed
+    /// at the start of the list. Returns whether the form was added
+    pub fn add(&self, list: &FormId, form_id: FormId) -> Result<bool, FormListError> {
+        let key = self
+            .keys
+            .get(list)
+            .ok_or_else(|| FormListError::UnknownList(list.clone()))?;
+
+        self.store
+            .modify(key, |flst| {
+                if flst.form_ids.contains(&form_id) {
+                    return false;
+                }
+                flst.form_ids.insert(0, form_id);
+                true
+            })
+            .ok_or_else(|| FormListError::UnknownList(list.clone()))
+    }
+
+    /// Removes the form from the list, returns whether it was a member
+    pub fn remove(&self, list: &FormId, form_id: &FormId) -> Result<bool, FormListError> {
+        let key = self
+            .keys
+            .get(list)
+            .ok_or_else(|| FormListError::UnknownList(list.clone()))?;
+
+        self.store
+            .modify(key, |flst| {
+                let length = flst.form_ids.len();
+                flst.form_ids.retain(|member| member != form_id);
+                flst.form_ids.len() != length
+            })
+            .ok_or_else(|| FormListError::UnknownList(list.clone()))
+    }
+
+    /// Registers the script functions operating on form lists
+    pub fn register_functions(&self, functions: &mut ScriptFunctions) {
+        fn form(args: &[Value], index: usize) -> Result<FormId, ScriptError> {
+            args.get(index)
+                .and_then(Value::as_form)
+                .cloned()
+                .ok_or_else(|| {
+                    ScriptError::Function(format!("Argument {} must be a form", index + 1))
+                })
+        }
+
+        let lists = self.clone();
+        functions.register("AddFormToFormList", move |_, args| {
+            let added = lists
+                .add(&form(args, 0)?, form(args, 1)?)
+                .map_err(|error| ScriptError::Function(error.to_string()))?;
+            Ok(Value::Int(added as i32))
+        });
+
+        let lists = self.clone();
+        functions.register("ListRemoveForm", move |_, args| {
+            let removed = lists
+                .remove(&form(args, 0)?, &form(args, 1)?)
+                .map_err(|error| ScriptError::Function(error.to_string()))?;
+            Ok(Value::Int(removed as i32))
+        });
+
+        let lists = self.clone();
+        functions.register("ListGetCount", move |_, args| {
+            let length = lists.len(&form(args, 0)?).unwrap_or_default();
+            Ok(Value::Int(length as i32))
+        });
+
+        let lists = self.clone();
+        functions.register("ListGetFormIndex", move |_, args| {
+            let index = lists.index_of(&form(args, 0)?, &form(args, 1)?);
+            Ok(Value::Int(index.map_or(-1, |index| index as i32)))
+        });
+
+        let lists = self.clone();
+        functions.register("ListGetNthForm", move |_, args| {
+            let index = args.get(1).and_then(Value::as_i32).unwrap_or_default();
+            let form_id = usize::try_from(index)
+                .ok()
+                .and_then(|index| lists.get(&form(args, 0).ok()?, index));
+            Ok(Value::Form(form_id.unwrap_or(FormId::NULL)))
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::utils::HashMap;
+
+    use crate::{
+        esp::{
+            record::records::flst::FLST,
+            shared::{EditorId, FormId},
+            store::Store,
+        },
+        script::{
+            interpreter::{
+                ScriptEnvironment, ScriptEvent, ScriptFunctions, ScriptInstance, ScriptRunner,
+                Value,
+            },
+            parser::parse,
+        },
+    };
+
+    use super::FormLists;
+
+    struct Environment;
+
+    impl ScriptEnvironment for Environment {
+        fn form(&self, editor_id: &str) -> Option<FormId> {
+            match editor_id.to_ascii_lowercase().as_str() {
+                "outer" => Some(FormId(0x1)),
+                "caps001" => Some(FormId(0xF)),
+                _ => None,
+            }
+        }
+    }
+
+    fn list(editor_id: &str, form_ids: &[u32]) -> (String, FLST) {
+        let flst = FLST {
+            editor_id: EditorId(editor_id.to_string()),
+            form_ids: form_ids.iter().copied().map(FormId).collect(),
+        };
+        (editor_id.to_string(), flst)
+    }
+
+    #[test]
+    fn test_form_lists() {
+        // Outer contains an item and the inner list, which contains the outer list
+        let store = Store::new(HashMap::from_iter([
+            list("Outer", &[0x10, 0x2]),
+            list("Inner", &[0x20, 0x1]),
+        ]));
+        let lists = FormLists::new(
+            store.clone(),
+            [
+                (FormId(0x1), "Outer".to_string()),
+                (FormId(0x2), "Inner".to_string()),
+            ],
+        );
+
+        assert!(!lists.contains(&FormId(0x1), &FormId(0x20)));
+        assert!(lists.contains_nested(&FormId(0x1), &FormId(0x20)));
+        assert_eq!(lists.flatten(&FormId(0x1)), [FormId(0x10), FormId(0x20)]);
+
+        assert!(lists.add(&FormId(0x1), FormId(0x30)).unwrap());
+        assert!(!lists.add(&FormId(0x1), FormId(0x30)).unwrap());
+        assert_eq!(lists.index_of(&FormId(0x1), &FormId(0x30)), Some(0));
+        assert!(lists.add(&FormId(0x3), FormId(0x30)).is_err());
+
+        // Additions are stored in the dynamic values of the store
+        assert_eq!(store.with("Outer", |flst| flst.form_ids.len()), Some(3));
+    }
+
+    #[test]
+    fn test_script_functions() {
+        let store = Store::new(HashMap::from_iter([list("Outer", &[0x10])]));
+        let lists = FormLists::new(store, [(FormId(0x1), "Outer".to_string())]);
+        let mut functions = ScriptFunctions::default();
+        lists.register_functions(&mut functions);
+
+        let ast = parse(
+            r#"scn TestScript
+
+short index
+short count
+ref last
+
+begin GameMode
+    AddFormToFormList Outer Caps001
+    set index to ListGetFormIndex Outer Caps001
+    set count to ListGetCount Outer
+    set last to ListGetNthForm Outer 1
+end
+"#,
+        )
+        .unwrap();
+
+        let mut environment = Environment;
+        let mut instance = ScriptInstance::new(&ast, None);
+        ScriptRunner::new(&functions, &mut environment)
+            .run(&ast, &mut instance, &ScriptEvent::game_mode())
+            .unwrap();
+
+        assert_eq!(instance.get("index"), Some(&Value::Int(0)));
+        assert_eq!(instance.get("count"), Some(&Value::Int(2)));
+        assert_eq!(instance.get("last"), Some(&Value::Form(FormId(0x10))));
+    }
+}
@@ -0,0 +1,514 @@
+//! Runtime values of global variables (`GLOB`) and game settings (`GMST`)
+
+use bevy::{
+    app::{App, Plugin, Startup, Update},
+    ecs::{
+        event::{Event, EventWriter},
+        schedule::{
+            common_conditions::{not, resource_added, resource_exists},
+            IntoSystemConfigs,
+        },
+        system::{Commands, Res, ResMut, Resource},
+    },
+    utils::HashMap,
+};
+use thiserror::Error;
+
+use crate::{
+    esp::{
+        record::records::{
+            glob::{GlobalType, GLOB},
+            gmst::{GMSTValue, GMST},
+        },
+        store::EsmStore,
+    },
+    utils::config::IniSettings,
+};
+
+/// Current hour of the day (0-24)
+pub const GAME_HOUR: &str = "GameHour";
+/// Number of game seconds that pass for every real second
+pub const TIME_SCALE: &str = "TimeScale";
+/// Number of days that have passed since the start of the game
+pub const GAME_DAYS_PASSED: &str = "GameDaysPassed";
+
+#[derive(Debug, Error)]
+pub enum VariableError {
+    #[error("Unknown global variable {0}")]
+    UnknownGlobal(String),
+    #[error("Unknown game setting {0}")]
+    UnknownSetting(String),
+    #[error("Game setting {name} expects a {expected} value")]
+    SettingType {
+        name: String,
+        expected: &'static str,
+    },
+}
+
+/// Value of a game setting, the type is determined by the first
+/// character of its editor ID
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingValue {
+    Float(f32),
+    Int(i32),
+    String(String),
+    Bool(bool),
+}
+
+impl SettingValue {
+    /// Parses a setting from text (e.g. an INI value) using the type from
+    /// the prefix of the setting name
+    pub fn parse(name: &str, value: &str) -> Option<Self> {
+        let value = value.trim();
+        match name.chars().next()? {
+            'f' => value.parse().ok().map(Self::Float),
+            'i' | 'u' => value.parse().ok().map(Self::Int),
+            's' | 'S' => Some(Self::String(value.to_string())),
+            'b' => value
+                .parse::<i32>()
+                .ok()
+                .map(|value| Self::Bool(value != 0)),
+            _ => None,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Float(_) => "float",
+            Self::Int(_) => "integer",
+            Self::String(_) => "string",
+            Self::Bool(_) => "bool",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Global {
+    editor_id: String,
+    ty: GlobalType,
+    value: f32,
+}
+
+#[derive(Debug)]
+struct Setting {
+    editor_id: String,
+    value: SettingValue,
+}
+
+/// Sent when a global variable or game setting changes
+#[derive(Debug, Clone, Event)]
+pub enum VariableChanged {
+    Global {
+        editor_id: String,
+        value: f32,
+    },
+    Setting {
+        editor_id: String,
+        value: SettingValue,
+    },
+}
+
+impl VariableChanged {
+    /// Whether the change is for the variable with the editor ID, ignoring case
+    pub fn is(&self, name: &str) -> bool {
+        let (Self::Global { editor_id, .. } | Self::Setting { editor_id, .. }) = self;
+        editor_id.eq_ignore_ascii_case(name)
+    }
+}
+
+/// Global variables and game settings, initialized from the loaded records
+/// then overridden by the INI and scripts. Editor IDs are case insensitive
+#[derive(Debug, Default, Resource)]
+pub struct GameVariables {
+    globals: HashMap<String, Global>,
+    settings: HashMap<String, Setting>,
+    /// Changes not yet sent as [`VariableChanged`] events
+    changes: Vec<VariableChanged>,
+}
+
+impl GameVariables {
+    pub fn load<'a, 'b>(
+        globals: impl IntoIterator<Item = &'a GLOB>,
+        settings: impl IntoIterator<Item = &'b GMST>,
+    ) -> Self {
+        let mut variables = Self::default();
+
+        for global in globals {
+            variables.globals.insert(
+                global.editor_id.to_ascii_lowercase(),
+                Global {
+                    editor_id: global.editor_id.to_string(),
+                    ty: global.ty,
+                    value: coerce(global.ty, global.value),
+                },
+            );
+        }
+
+        for setting in settings {
+            let value = match &setting.value {
+                GMSTValue::Float(value) => SettingValue::Float(*value),
+                GMSTValue::String(value) => SettingValue::String(value.clone()),
+                // Bool settings are stored as integers
+                GMSTValue::Int(value) if setting.editor_id.starts_with('b') => {
+                    SettingValue::Bool(*value != 0)
+                }
+                GMSTValue::Int(value) => SettingValue::Int(*value),
+            };
+            variables.settings.insert(
+                setting.editor_id.to_ascii_lowercase(),
+                Setting {
+                    editor_id: setting.editor_id.to_string(),
+                    value,
+                },
+            );
+        }
+
+        variables
+    }
+
+    /// Variables used while no records are loaded, only the time globals
+    /// the engine relies on with the values of a new game
+    pub fn with_defaults() -> Self {
+        let mut variables = Self::default();
+        for (editor_id, ty, value) in [
+            (GAME_HOUR, GlobalType::Float, 8.0),
+            (TIME_SCALE, GlobalType::Short, 30.0),
+            (GAME_DAYS_PASSED, GlobalType::Float, 0.0),
+        ] {
+            variables.globals.insert(
+                editor_id.to_ascii_lowercase(),
+                Global {
+                    editor_id: editor_id.to_string(),
+                    ty,
+                    value,
+                },
+            );
+        }
+        variables
+    }
+
+    /// Loads the variables from the globals and game settings of the store
+    pub fn from_store(store: &EsmStore) -> Self {
+        store.globals().with_values(|globals| {
+            store
+                .game_settings()
+                .with_values(|settings| Self::load(globals, settings))
+        })
+    }
+
+    /// Overrides game settings with the values from the INI. Other INI settings
+    /// are added as `name:Section`, the way the game names them, as long as
+    /// their prefix gives them a type
+    pub fn apply_ini(&mut self, ini: &IniSettings) {
+        for (section, values) in &ini.sections {
+            for (name, value) in values {
+                let Some(value) = SettingValue::parse(name, value) else {
+                    continue;
+                };
+
+                let editor_id = if self.settings.contains_key(&name.to_ascii_lowercase()) {
+                    name.clone()
+                } else {
+                    format!("{name}:{section}")
+                };
+                let setting = self
+                    .settings
+                    .entry(editor_id.to_ascii_lowercase())
+                    .or_insert_with(|| Setting {
+                        editor_id,
+                        value: value.clone(),
+                    });
+                setting.value = value.clone();
+
+                self.changes.push(VariableChanged::Setting {
+                    editor_id: setting.editor_id.clone(),
+                    value,
+                });
+            }
+        }
+    }
+
+    pub fn global(&self, name: &str) -> Option<f32> {
+        self.get_global(name).map(|global| global.value)
+    }
+
+    pub fn global_type(&self, name: &str) -> Option<GlobalType> {
+        self.get_global(name).map(|global| global.ty)
+    }
+
+    pub fn global_short(&self, name: &str) -> Option<i16> {
+        self.global(name).map(|value| value as i16)
+    }
+
+    pub fn global_long(&self, name: &str) -> Option<i32> {
+        self.global(name).map(|value| value as i32)
+    }
+
+    /// Sets a global variable, the value is truncated for short and long globals
+    pub fn set_global(&mut self, name: &str, value: f32) -> Result<(), VariableError> {
+        let global = self
+            .globals
+            .get_mut(&name.to_ascii_lowercase())
+            .ok_or_else(|| VariableError::UnknownGlobal(name.to_string()))?;
+
+        let value = coerce(global.ty, value);
+        if global.value != value {
+            global.value = value;
+            self.changes.push(VariableChanged::Global {
+                editor_id: global.editor_id.clone(),
+                value,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn setting(&self, name: &str) -> Option<&SettingValue> {
+        self.settings
+            .get(&name.to_ascii_lowercase())
+            .map(|setting| &setting.value)
+    }
+
+    pub fn setting_f32(&self, name: &str) -> Option<f32> {
+        match self.setting(name)? {
+            SettingValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn setting_i32(&self, name: &str) -> Option<i32> {
+        match self.setting(name)? {
+            SettingValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn setting_str(&self, name: &str) -> Option<&str> {
+        match self.setting(name)? {
+            SettingValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn setting_bool(&self, name: &str) -> Option<bool> {
+        match self.setting(name)? {
+            SettingValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Sets a game setting, the value must match the type of the setting
+    pub fn set_setting(&mut self, name: &str, value: SettingValue) -> Result<(), VariableError> {
+        let setting = self
+            .settings
+            .get_mut(&name.to_ascii_lowercase())
+            .ok_or_else(|| VariableError::UnknownSetting(name.to_string()))?;
+
+        if std::mem::discriminant(&setting.value) != std::mem::discriminant(&value) {
+            return Err(VariableError::SettingType {
+                name: setting.editor_id.clone(),
+                expected: setting.value.type_name(),
+            });
+        }
+
+        if setting.value != value {
+            setting.value = value.clone();
+            self.changes.push(VariableChanged::Setting {
+                editor_id: setting.editor_id.clone(),
+                value,
+            });
+        }
+        Ok(())
+    }
+
+    /// Advances [`GAME_HOUR`] by `delta` real seconds scaled by [`TIME_SCALE`],
+    /// incrementing [`GAME_DAYS_PASSED`] when the day wraps
+    pub fn advance_time(&mut self, delta: f32) -> Result<(), VariableError> {
+        let time_scale = self.global(TIME_SCALE).unwrap_or(1.0);
+        let hour = self
+            .global(GAME_HOUR)
+            .ok_or_else(|| VariableError::UnknownGlobal(GAME_HOUR.to_string()))?;
+
+        let hour = hour + delta * time_scale / 3600.0;
+        self.set_global(GAME_HOUR, hour.rem_euclid(24.0))?;
+
+        if hour >= 24.0 {
+            let days = self.global(GAME_DAYS_PASSED).unwrap_or_default();
+            self.set_global(GAME_DAYS_PASSED, days + (hour / 24.0).floor())?;
+        }
+        Ok(())
+    }
+
+    fn get_global(&self, name: &str) -> Option<&Global> {
+        self.globals.get(&name.to_ascii_lowercase())
+    }
+}
+
+/// Truncates the value to the range of the global type
+fn coerce(ty: GlobalType, value: f32) -> f32 {
+    match ty {
+        GlobalType::Short => value as i16 as f32,
+        GlobalType::Long => value as i32 as f32,
+        GlobalType::Float => value,
+    }
+}
+
+/// Sends the changes made to [`GameVariables`] as [`VariableChanged`] events
+pub fn send_variable_changes(
+    mut variables: ResMut<GameVariables>,
+    mut events: EventWriter<VariableChanged>,
+) {
+    if !variables.changes.is_empty() {
+        events.send_batch(variables.changes.drain(..));
+    }
+}
+
+/// Builds [`GameVariables`] from the defaults and the INI when no
+/// [`EsmStore`] is loaded, they're replaced once the store is added
+pub fn insert_default_game_variables(mut commands: Commands, ini: Option<Res<IniSettings>>) {
+    let mut variables = GameVariables::with_defaults();
+    if let Some(ini) = ini {
+        variables.apply_ini(&ini);
+    }
+    commands.insert_resource(variables);
+}
+
+/// Builds [`GameVariables`] from the records once the [`EsmStore`]
+/// is loaded, then applies the INI overrides
+pub fn insert_game_variables(
+    mut commands: Commands,
+    store: Res<EsmStore>,
+    ini: Option<Res<IniSettings>>,
+) {
+    let mut variables = GameVariables::from_store(&store);
+    if let Some(ini) = ini {
+        variables.apply_ini(&ini);
+    }
+    commands.insert_resource(variables);
+}
+
+/// Inserts [`GameVariables`] at startup and again when the [`EsmStore`]
+/// is loaded, sending its changes as [`VariableChanged`] events
+pub struct GameVariablesPlugin;
+
+impl Plugin for GameVariablesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<VariableChanged>()
+            .add_systems(
+                Startup,
+                insert_default_game_variables.run_if(not(resource_exists::<EsmStore>())),
+            )
+            .add_systems(
+                Update,
+                (
+                    insert_game_variables.run_if(resource_added::<EsmStore>()),
+                    send_variable_changes.run_if(resource_exists::<GameVariables>()),
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        esp::{
+            record::records::{
+                glob::{GlobalType, GLOB},
+                gmst::{GMSTValue, GMST},
+            },
+            shared::EditorId,
+        },
+        utils::config::IniSettings,
+    };
+
+    use super::{GameVariables, SettingValue, VariableChanged, GAME_HOUR, TIME_SCALE};
+
+    /// Without any records the time globals still advance and the
+    /// INI still applies
+    #[test]
+    fn test_defaults() {
+        let mut variables = GameVariables::with_defaults();
+        variables.apply_ini(&IniSettings {
+            sections: HashMap::from([(
+                "General".to_string(),
+                HashMap::from([("fDefaultFOV".to_string(), "75".to_string())]),
+            )]),
+        });
+        assert_eq!(variables.setting_f32("fDefaultFOV:General"), Some(75.0));
+
+        assert_eq!(variables.global_short(TIME_SCALE), Some(30));
+        // Two real minutes at a time scale of 30 pass a game hour
+        variables.advance_time(120.0).unwrap();
+        assert_eq!(variables.global(GAME_HOUR), Some(9.0));
+    }
+
+    #[test]
+    fn test_variables() {
+        let globals = [
+            GLOB {
+                editor_id: EditorId("GameHour".into()),
+                ty: GlobalType::Float,
+                value: 23.5,
+            },
+            GLOB {
+                editor_id: EditorId("TimeScale".into()),
+                ty: GlobalType::Short,
+                value: 30.0,
+            },
+            GLOB {
+                editor_id: EditorId("GameDaysPassed".into()),
+                ty: GlobalType::Float,
+                value: 0.0,
+            },
+        ];
+        let settings = [
+            GMST {
+                editor_id: EditorId("fJumpHeightMin".into()),
+                value: GMSTValue::Float(64.0),
+            },
+            GMST {
+                editor_id: EditorId("bAllowHardcore".into()),
+                value: GMSTValue::Int(1),
+            },
+        ];
+
+        let mut variables = GameVariables::load(&globals, &settings);
+        let ini = IniSettings {
+            sections: HashMap::from([(
+                "Display".to_string(),
+                HashMap::from([
+                    ("fJumpHeightMin".to_string(), "80".to_string()),
+                    ("iMaxDecals".to_string(), "100".to_string()),
+                ]),
+            )]),
+        };
+        variables.apply_ini(&ini);
+
+        assert_eq!(variables.setting_f32("fjumpheightmin"), Some(80.0));
+        assert_eq!(variables.setting_i32("iMaxDecals:Display"), Some(100));
+        assert!(variables
+            .changes
+            .iter()
+            .any(|change| change.is("fJumpHeightMin")));
+        assert_eq!(variables.setting_bool("bAllowHardcore"), Some(true));
+        assert!(variables
+            .set_setting("bAllowHardcore", SettingValue::Int(0))
+            .is_err());
+
+        variables.set_global("timescale", 20.9).unwrap();
+        assert_eq!(variables.global_short("TimeScale"), Some(20));
+
+        // One real hour at a time scale of 20 passes 20 game hours
+        variables.advance_time(3600.0).unwrap();
+        assert_eq!(variables.global("GameHour"), Some(19.5));
+        assert_eq!(variables.global("GameDaysPassed"), Some(1.0));
+        assert!(
+            variables
+                .changes
+                .iter()
+                .any(|change| matches!(change, VariableChanged::Global { .. })
+                    && change.is(GAME_HOUR))
+        );
+    }
+}
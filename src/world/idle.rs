@@ -0,0 +1,272 @@
+use crate::esp::{
+    record::{
+        records::idle::{AnimationGroupSection, IdleFlags, IDLE},
+        sub::condition::CTDA,
+    },
+    shared::FormId,
+};
+use std::collections::HashMap;
+
+/// Maximum depth of the idle tree that will be walked, guards against
+/// idles that (directly or indirectly) list themselves as their parent
+const MAX_IDLE_TREE_DEPTH: usize = 32;
+
+/// State of the actor the idle is being selected for
+#[derive(Debug, Clone, Copy)]
+pub struct IdleContext {
+    /// Section of the animation group being selected, idles from other
+    /// sections are ignored. [`None`] allows idles from any section
+    pub section: Option<AnimationGroupSection>,
+    /// Whether the actor is currently attacking
+    pub attacking: bool,
+}
+
+struct IdleNode<'a> {
+    form_id: FormId,
+    idle: &'a IDLE,
+    children: Vec<usize>,
+}
+
+/// Tree of idle animations built from the parent and previous
+/// sibling links of the IDLE records
+pub struct IdleTree<'a> {
+    nodes: Vec<IdleNode<'a>>,
+    roots: Vec<usize>,
+}
+
+impl<'a> IdleTree<'a> {
+    /// Builds the tree from the provided idles, siblings are ordered by their
+    /// previous sibling links with any unlinked siblings placed at the end
+    pub fn new<I>(idles: I) -> Self
+    where
+        I: IntoIterator<Item = (FormId, &'a IDLE)>,
+    {
+        let mut nodes: Vec<IdleNode<'a>> = idles
+            .into_iter()
+            .map(|(form_id, idle)| IdleNode {
+                form_id,
+                idle,
+                children: Vec::new(),
+            })
+            .collect();
+
+        let lookup: HashMap<FormId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.form_id.clone(), index))
+            .collect();
+
+        // Group the nodes by their parent, nodes with a missing parent are roots
+        let mut roots: Vec<usize> = Vec::new();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+        for (index, node) in nodes.iter().enumerate() {
+            match lookup.get(&node.idle.related.parent.id) {
+                Some(&parent) if parent != index => children[parent].push(index),
+                _ => roots.push(index),
+            }
+        }
+
+        let roots = order_siblings(&nodes, &roots);
+        for (index, siblings) in children.into_iter().enumerate() {
+            nodes[index].children = order_siblings(&nodes, &siblings);
+        }
+
+        Self { nodes, roots }
+    }
+
+    /// Selects the idle to play by walking the tree.
+    ///
+    /// Siblings are checked in order and the first idle whose conditions pass
+    /// is descended into. When none of its children can be played the idle
+    /// itself is chosen if it has an animation, otherwise the search continues
+    /// with its next sibling.
+    ///
+    /// `evaluate` is used to evaluate the conditions of each idle
+    pub fn select<F>(&self, context: &IdleContext, mut evaluate: F) -> Option<(&FormId, &'a IDLE)>
+    where
+        F: FnMut(&[CTDA]) -> bool,
+    {
+        self.select_from(&self.roots, context, &mut evaluate, 0)
+            .map(|index| {
+                let node = &self.nodes[index];
+                (&node.form_id, node.idle)
+            })
+    }
+
+    fn select_from<F>(
+        &self,
+        siblings: &[usize],
+        context: &IdleContext,
+        evaluate: &mut F,
+        depth: usize,
+    ) -> Option<usize>
+    where
+        F: FnMut(&[CTDA]) -> bool,
+    {
+        if depth > MAX_IDLE_TREE_DEPTH {
+            return None;
+        }
+
+        for &index in siblings {
+            let idle = self.nodes[index].idle;
+
+            if context.attacking && idle.data.flags.contains(IdleFlags::NO_ATTACKING) {
+                continue;
+            }
+
+            if !evaluate(&idle.conditions) {
+                continue;
+            }
+
+            let children = &self.nodes[index].children;
+            if let Some(child) = self.select_from(children, context, evaluate, depth + 1) {
+                return Some(child);
+            }
+
+            if is_playable(idle, context) {
+                return Some(index);
+            }
+        }
+
+        None
+    }
+}
+
+/// Whether the idle has an animation that can be played in the context
+fn is_playable(idle: &IDLE, context: &IdleContext) -> bool {
+    let has_animation = idle
+        .model_data
+        .as_ref()
+        .is_some_and(|model| !model.model_file_name.is_empty());
+
+    let section_matches = match context.section {
+        Some(section) => idle.data.animation_group_section == section,
+        None => true,
+    };
+
+    has_animation && section_matches
+}
+
+/// Orders the siblings by following the chain of previous sibling links
+fn order_siblings(nodes: &[IdleNode<'_>], siblings: &[usize]) -> Vec<usize> {
+    let mut ordered: Vec<usize> = Vec::with_capacity(siblings.len());
+    let mut visited: Vec<bool> = vec![false; siblings.len()];
+
+    // Siblings that follow the provided form
+    let next = |previous: &FormId, visited: &[bool]| {
+        siblings.iter().enumerate().position(|(position, &index)| {
+            !visited[position] && nodes[index].idle.related.previous_sibling.id == *previous
+        })
+    };
+
+    // Start at the sibling without a previous sibling in the group
+    let mut current = siblings.iter().position(|&index| {
+        let previous = &nodes[index].idle.related.previous_sibling.id;
+        !siblings
+            .iter()
+            .any(|&other| other != index && nodes[other].form_id == *previous)
+    });
+
+    while let Some(position) = current {
+        visited[position] = true;
+        let index = siblings[position];
+        ordered.push(index);
+        current = next(&nodes[index].form_id, &visited);
+    }
+
+    // Siblings that couldn't be reached through the chain
+    ordered.extend(
+        siblings
+            .iter()
+            .zip(visited)
+            .filter(|(_, visited)| !visited)
+            .map(|(&index, _)| index),
+    );
+
+    ordered
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IdleContext, IdleTree};
+    use crate::esp::{
+        record::{
+            records::idle::{AnimationGroupSection, IdleData, IdleFlags, RelatedIdles, IDLE},
+            sub::model::ModelData,
+        },
+        shared::{EditorId, FormId},
+    };
+
+    fn idle(parent: u32, previous_sibling: u32, model: &str) -> IDLE {
+        IDLE {
+            editor_id: EditorId(String::new()),
+            model_data: Some(ModelData {
+                model_file_name: model.to_string(),
+                alternative_textures: None,
+                facegen_model_flags: None,
+            }),
+            conditions: Vec::new(),
+            related: RelatedIdles {
+                parent: FormId(parent).into_typed(),
+                previous_sibling: FormId(previous_sibling).into_typed(),
+            },
+            data: IdleData {
+                animation_group_section: AnimationGroupSection::SpecialIdle,
+                loop_min: 0,
+                loop_max: 0,
+                replay_delay: 0,
+                flags: IdleFlags::empty(),
+            },
+        }
+    }
+
+    const CONTEXT: IdleContext = IdleContext {
+        section: None,
+        attacking: false,
+    };
+
+    /// Children are preferred over their parent and siblings are
+    /// checked in the order of their previous sibling links
+    #[test]
+    fn test_select_child_in_sibling_order() {
+        let root = idle(0, 0, "");
+        let first = idle(0x1, 0, "first.kf");
+        let second = idle(0x1, 0x2, "second.kf");
+
+        // Inserted out of order to ensure the sibling links are used
+        let tree = IdleTree::new([
+            (FormId(0x3), &second),
+            (FormId(0x1), &root),
+            (FormId(0x2), &first),
+        ]);
+
+        let (form_id, _) = tree.select(&CONTEXT, |_| true).unwrap();
+        assert_eq!(*form_id, FormId(0x2));
+    }
+
+    /// When no children can be played the parent animation is used and
+    /// grouping idles without animations are skipped
+    #[test]
+    fn test_fallback_to_parent() {
+        let group = idle(0, 0, "");
+        let group_child = idle(0x1, 0, "");
+        let parent = idle(0, 0x1, "parent.kf");
+        let mut child = idle(0x3, 0, "child.kf");
+        child.data.animation_group_section = AnimationGroupSection::Weapon;
+
+        let tree = IdleTree::new([
+            (FormId(0x1), &group),
+            (FormId(0x2), &group_child),
+            (FormId(0x3), &parent),
+            (FormId(0x4), &child),
+        ]);
+
+        let context = IdleContext {
+            section: Some(AnimationGroupSection::SpecialIdle),
+            attacking: false,
+        };
+
+        let (form_id, _) = tree.select(&context, |_| true).unwrap();
+        assert_eq!(*form_id, FormId(0x3));
+    }
+}
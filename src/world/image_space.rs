@@ -0,0 +1,219 @@
+use crate::esp::record::records::{
+    imad::{FloatCurve, MultAddCurve, IMAD},
+    imgs::{ImageSpaceBloom, ImageSpaceCinematic, ImageSpaceData, ImageSpaceHdr, ImageSpaceTint},
+};
+use nalgebra::{Vector3, Vector4};
+
+/// Image space modifier that is currently playing
+#[derive(Debug, Clone, Copy)]
+pub struct ActiveImageSpaceModifier<'a> {
+    pub modifier: &'a IMAD,
+    /// Seconds since the modifier was started
+    pub elapsed: f32,
+    /// Strength the modifier is applied at (0-1)
+    pub strength: f32,
+}
+
+impl ActiveImageSpaceModifier<'_> {
+    /// Time within the modifier relative to its duration (0-1)
+    pub fn time(&self) -> f32 {
+        let duration = self.modifier.data.duration;
+        if duration > 0.0 {
+            (self.elapsed / duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether the modifier has played for its full duration
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.modifier.data.duration
+    }
+}
+
+/// Final set of tonemapping and post-process parameters produced by
+/// blending an image space with its active modifiers
+#[derive(Debug, Clone)]
+pub struct ImageSpaceParameters {
+    pub hdr: ImageSpaceHdr,
+    pub bloom: ImageSpaceBloom,
+    pub cinematic: ImageSpaceCinematic,
+    pub tint: ImageSpaceTint,
+    /// RGB color faded to, the alpha being the amount of fade
+    pub fade_color: Vector4<f32>,
+    pub blur_radius: f32,
+    pub double_vision_strength: f32,
+    pub motion_blur_strength: f32,
+}
+
+impl From<&ImageSpaceData> for ImageSpaceParameters {
+    fn from(value: &ImageSpaceData) -> Self {
+        Self {
+            hdr: value.hdr,
+            bloom: value.bloom,
+            cinematic: value.cinematic,
+            tint: value.tint,
+            fade_color: Vector4::zeros(),
+            blur_radius: 0.0,
+            double_vision_strength: 0.0,
+            motion_blur_strength: 0.0,
+        }
+    }
+}
+
+/// Blends the base image space with the active modifiers, the modifiers
+/// are applied in order each at their own time and strength
+pub fn blend_image_space(
+    base: &ImageSpaceData,
+    modifiers: &[ActiveImageSpaceModifier<'_>],
+) -> ImageSpaceParameters {
+    let mut out = ImageSpaceParameters::from(base);
+
+    for active in modifiers {
+        apply_modifier(&mut out, active);
+    }
+
+    out
+}
+
+fn apply_modifier(out: &mut ImageSpaceParameters, active: &ActiveImageSpaceModifier<'_>) {
+    let modifier = active.modifier;
+    let time = active.time();
+    let strength = active.strength.clamp(0.0, 1.0);
+
+    let apply = |value: &mut f32, curve: &MultAddCurve| {
+        let modified = curve.apply(*value, time);
+        *value += (modified - *value) * strength;
+    };
+
+    let hdr = &mut out.hdr;
+    let curves = &modifier.hdr;
+    apply(&mut hdr.eye_adapt_speed, &curves.eye_adapt_speed);
+    apply(&mut hdr.blur_radius, &curves.blur_radius);
+    apply(&mut hdr.skin_dimmer, &curves.skin_dimmer);
+    apply(&mut hdr.emissive_mult, &curves.emissive_mult);
+    apply(&mut hdr.target_lum, &curves.target_lum);
+    apply(&mut hdr.upper_lum_clamp, &curves.upper_lum_clamp);
+    apply(&mut hdr.bright_scale, &curves.bright_scale);
+    apply(&mut hdr.bright_clamp, &curves.bright_clamp);
+    apply(&mut hdr.lum_ramp_no_tex, &curves.lum_ramp_no_tex);
+    apply(&mut hdr.lum_ramp_min, &curves.lum_ramp_min);
+    apply(&mut hdr.lum_ramp_max, &curves.lum_ramp_max);
+    apply(&mut hdr.sunlight_dimmer, &curves.sunlight_dimmer);
+    apply(&mut hdr.grass_dimmer, &curves.grass_dimmer);
+    apply(&mut hdr.tree_dimmer, &curves.tree_dimmer);
+
+    let bloom = &mut out.bloom;
+    let curves = &modifier.bloom;
+    apply(&mut bloom.blur_radius, &curves.blur_radius);
+    apply(&mut bloom.alpha_mult_interior, &curves.alpha_mult_interior);
+    apply(&mut bloom.alpha_mult_exterior, &curves.alpha_mult_exterior);
+
+    let cinematic = &mut out.cinematic;
+    let curves = &modifier.cinematic;
+    apply(&mut cinematic.saturation, &curves.saturation);
+    apply(&mut cinematic.contrast, &curves.contrast);
+    apply(&mut cinematic.contrast_avg_lum, &curves.contrast_avg_lum);
+    apply(&mut cinematic.brightness, &curves.brightness);
+
+    // Tint colors are blended towards the modifier color by its alpha
+    if let Some(color) = modifier.tint_color.sample(time) {
+        let amount = color.w * strength;
+        out.tint.color = out.tint.color.lerp(&color.xyz(), amount);
+        out.tint.value = out.tint.value.max(amount);
+    }
+
+    if let Some(color) = modifier.fade_color.sample(time) {
+        let amount = color.w * strength;
+        let rgb: Vector3<f32> = out.fade_color.xyz().lerp(&color.xyz(), amount);
+        out.fade_color = rgb.push(out.fade_color.w.max(amount));
+    }
+
+    // Effects not present in the base image space are summed
+    let sample = |curve: &FloatCurve| curve.sample(time).unwrap_or_default() * strength;
+    out.blur_radius += sample(&modifier.blur_radius);
+    out.double_vision_strength += sample(&modifier.double_vision_strength);
+    out.motion_blur_strength += sample(&modifier.motion_blur_strength);
+}
+
+#[cfg(test)]
+mod test {
+    use super::{blend_image_space, ActiveImageSpaceModifier};
+    use crate::esp::{
+        record::records::{
+            imad::{
+                FloatCurve, FloatKey, ImageSpaceModifierData, ImageSpaceModifierFlags,
+                MultAddCurve, IMAD,
+            },
+            imgs::ImageSpaceData,
+        },
+        shared::EditorId,
+    };
+
+    fn curve(keys: &[(f32, f32)]) -> FloatCurve {
+        FloatCurve(
+            keys.iter()
+                .map(|&(time, value)| FloatKey { time, value })
+                .collect(),
+        )
+    }
+
+    /// 10 second modifier scaling the target luminance up to 3x by its
+    /// halfway point and ramping up a blur over its full duration
+    fn modifier() -> IMAD {
+        let mut modifier = IMAD {
+            editor_id: EditorId(String::new()),
+            data: ImageSpaceModifierData {
+                flags: ImageSpaceModifierFlags::empty(),
+                duration: 10.0,
+            },
+            blur_radius: curve(&[(0.0, 0.0), (1.0, 4.0)]),
+            double_vision_strength: Default::default(),
+            tint_color: Default::default(),
+            fade_color: Default::default(),
+            radial_blur: Default::default(),
+            depth_of_field: Default::default(),
+            motion_blur_strength: Default::default(),
+            hdr: Default::default(),
+            bloom: Default::default(),
+            cinematic: Default::default(),
+            sound_intro: None,
+            sound_outro: None,
+        };
+        modifier.hdr.target_lum = MultAddCurve {
+            multiply: curve(&[(0.0, 1.0), (0.5, 3.0)]),
+            add: FloatCurve::default(),
+        };
+        modifier
+    }
+
+    fn blend(base: &ImageSpaceData, modifier: &IMAD, elapsed: f32, strength: f32) -> (f32, f32) {
+        let out = blend_image_space(
+            base,
+            &[ActiveImageSpaceModifier {
+                modifier,
+                elapsed,
+                strength,
+            }],
+        );
+        (out.hdr.target_lum, out.blur_radius)
+    }
+
+    #[test]
+    fn test_blend_image_space() {
+        let mut base = ImageSpaceData::default();
+        base.hdr.target_lum = 2.0;
+        let modifier = modifier();
+
+        // Interpolated between the keys
+        assert_eq!(blend(&base, &modifier, 2.5, 1.0), (4.0, 1.0));
+        // Held at the last key once past it
+        assert_eq!(blend(&base, &modifier, 8.0, 1.0), (6.0, 3.2));
+        // Clamped to the end after the duration
+        assert_eq!(blend(&base, &modifier, 20.0, 1.0), (6.0, 4.0));
+        // Partial strength only moves part of the way
+        assert_eq!(blend(&base, &modifier, 2.5, 0.5), (3.0, 0.5));
+        // Without modifiers the base values are unchanged
+        assert_eq!(blend_image_space(&base, &[]).hdr.target_lum, 2.0);
+    }
+}
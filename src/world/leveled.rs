@@ -0,0 +1,347 @@
+use crate::esp::{
+    record::{
+        records::{lvlc::LVLC, lvli::LVLI, lvln::LVLN},
+        sub::{
+            item::COED,
+            leveled_list::{LeveledEntry, LeveledFlags},
+        },
+    },
+    shared::FormId,
+};
+use fastrand::Rng;
+
+/// Maximum depth of nested leveled lists that will be resolved, guards
+/// against lists that (directly or indirectly) contain themselves
+const MAX_LEVELED_LIST_DEPTH: usize = 32;
+
+/// Common view over the leveled list records (LVLI, LVLC, LVLN)
+pub trait LeveledList {
+    /// Percentage chance (0-100) of the list producing nothing
+    fn chance_none(&self) -> u8;
+
+    /// Global variable providing the chance none, overrides
+    /// [`LeveledList::chance_none`] when present
+    fn chance_none_global(&self) -> Option<&FormId> {
+        None
+    }
+
+    fn flags(&self) -> LeveledFlags;
+
+    fn entries(&self) -> &[LeveledEntry];
+}
+
+impl LeveledList for LVLI {
+    fn chance_none(&self) -> u8 {
+        self.chance_none
+    }
+
+    fn chance_none_global(&self) -> Option<&FormId> {
+        self.global
+            .as_ref()
+            .filter(|global| !global.is_null())
+            .map(|global| &global.id)
+    }
+
+    fn flags(&self) -> LeveledFlags {
+        self.flags
+    }
+
+    fn entries(&self) -> &[LeveledEntry] {
+        &self.entries
+    }
+}
+
+impl LeveledList for LVLC {
+    fn chance_none(&self) -> u8 {
+        self.chance_none
+    }
+
+    fn flags(&self) -> LeveledFlags {
+        self.flags
+    }
+
+    fn entries(&self) -> &[LeveledEntry] {
+        &self.entries
+    }
+}
+
+impl LeveledList for LVLN {
+    fn chance_none(&self) -> u8 {
+        self.chance_none
+    }
+
+    fn flags(&self) -> LeveledFlags {
+        self.flags
+    }
+
+    fn entries(&self) -> &[LeveledEntry] {
+        &self.entries
+    }
+}
+
+/// Provides the resolver with access to nested leveled lists
+/// and the values of global variables
+pub trait LeveledListLookup<'a> {
+    /// Finds the leveled list with the provided FormID, returns [`None`]
+    /// when the FormID doesn't refer to a leveled list
+    fn leveled_list(&self, form_id: &FormId) -> Option<&'a dyn LeveledList>;
+
+    /// Gets the current value of the provided global variable
+    fn global_value(&self, form_id: &FormId) -> Option<f32>;
+}
+
+/// Concrete item or actor produced by resolving a leveled list
+#[derive(Debug)]
+pub struct ResolvedEntry<'a> {
+    /// FormID of the produced item or actor base
+    pub reference: FormId,
+    pub count: u32,
+    pub extra_data: Option<&'a COED>,
+}
+
+/// Deterministic resolver for turning leveled lists into
+/// the concrete items and actors they produce
+pub struct LeveledListResolver {
+    rng: Rng,
+}
+
+impl LeveledListResolver {
+    /// Creates a new resolver using the provided seed, resolvers
+    /// created with the same seed will produce the same results
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::with_seed(seed),
+        }
+    }
+
+    /// Resolves `count` of the provided leveled list for a player of
+    /// `player_level` recursing through any nested lists
+    pub fn resolve<'a, L>(
+        &mut self,
+        list: &'a dyn LeveledList,
+        count: u32,
+        player_level: u16,
+        lookup: &L,
+    ) -> Vec<ResolvedEntry<'a>>
+    where
+        L: LeveledListLookup<'a>,
+    {
+        let mut out = Vec::new();
+        self.resolve_into(list, count, player_level, lookup, 0, &mut out);
+        out
+    }
+
+    fn resolve_into<'a, L>(
+        &mut self,
+        list: &'a dyn LeveledList,
+        count: u32,
+        player_level: u16,
+        lookup: &L,
+        depth: usize,
+        out: &mut Vec<ResolvedEntry<'a>>,
+    ) where
+        L: LeveledListLookup<'a>,
+    {
+        if depth > MAX_LEVELED_LIST_DEPTH {
+            return;
+        }
+
+        let flags = list.flags();
+
+        // Each item in the count is calculated separately rather
+        // than calculating once and multiplying the result
+        let (iterations, multiplier) = if flags.contains(LeveledFlags::CALCULATE_FOR_EACH_ITEM) {
+            (count, 1)
+        } else {
+            (1, count)
+        };
+
+        let chance_none = list
+            .chance_none_global()
+            .and_then(|global| lookup.global_value(global))
+            .unwrap_or(list.chance_none() as f32);
+
+        for _ in 0..iterations {
+            if (self.rng.u32(0..100) as f32) < chance_none {
+                continue;
+            }
+
+            let entries: Vec<&'a LeveledEntry> = if flags.contains(LeveledFlags::USE_ALL) {
+                eligible_entries(list, player_level).collect()
+            } else {
+                self.choose_entry(list, player_level).into_iter().collect()
+            };
+
+            for entry in entries {
+                let count = entry.data.count.max(1) as u32 * multiplier;
+
+                match lookup.leveled_list(&entry.data.reference) {
+                    Some(nested) => {
+                        self.resolve_into(nested, count, player_level, lookup, depth + 1, out)
+                    }
+                    None => out.push(ResolvedEntry {
+                        reference: entry.data.reference.clone(),
+                        count,
+                        extra_data: entry.extra_data.as_ref(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Chooses a random entry from the list, only entries at the highest level
+    /// available to the player are considered unless the list is flagged to
+    /// calculate from all levels
+    fn choose_entry<'a>(
+        &mut self,
+        list: &'a dyn LeveledList,
+        player_level: u16,
+    ) -> Option<&'a LeveledEntry> {
+        let mut candidates: Vec<&'a LeveledEntry> = eligible_entries(list, player_level).collect();
+
+        if !list
+            .flags()
+            .contains(LeveledFlags::CALCULATE_FROM_ALL_LEVELS)
+        {
+            let highest_level = candidates.iter().map(|entry| entry.data.level).max()?;
+            candidates.retain(|entry| entry.data.level == highest_level);
+        }
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let index = self.rng.usize(0..candidates.len());
+        Some(candidates[index])
+    }
+}
+
+/// Entries within the list that are available at the provided player level
+fn eligible_entries(
+    list: &dyn LeveledList,
+    player_level: u16,
+) -> impl Iterator<Item = &LeveledEntry> {
+    list.entries()
+        .iter()
+        .filter(move |entry| entry.data.level as i32 <= player_level as i32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LeveledList, LeveledListLookup, LeveledListResolver};
+    use crate::esp::{
+        record::sub::leveled_list::{LeveledEntry, LeveledFlags, LVLO},
+        shared::FormId,
+    };
+
+    struct TestList {
+        chance_none: u8,
+        flags: LeveledFlags,
+        entries: Vec<LeveledEntry>,
+    }
+
+    impl LeveledList for TestList {
+        fn chance_none(&self) -> u8 {
+            self.chance_none
+        }
+
+        fn flags(&self) -> LeveledFlags {
+            self.flags
+        }
+
+        fn entries(&self) -> &[LeveledEntry] {
+            &self.entries
+        }
+    }
+
+    fn entry(level: i16, reference: u32, count: i16) -> LeveledEntry {
+        LeveledEntry {
+            data: LVLO {
+                level,
+                reference: FormId(reference),
+                count,
+            },
+            extra_data: None,
+        }
+    }
+
+    /// Lookup where the nested list is stored under FormID 0x100
+    struct TestLookup<'a>(Option<&'a TestList>);
+
+    impl<'a> LeveledListLookup<'a> for TestLookup<'a> {
+        fn leveled_list(&self, form_id: &FormId) -> Option<&'a dyn LeveledList> {
+            match self.0 {
+                Some(list) if form_id.0 == 0x100 => Some(list),
+                _ => None,
+            }
+        }
+
+        fn global_value(&self, _form_id: &FormId) -> Option<f32> {
+            None
+        }
+    }
+
+    /// Only the entries at the highest available level should be chosen
+    #[test]
+    fn test_highest_level_only() {
+        let list = TestList {
+            chance_none: 0,
+            flags: LeveledFlags::empty(),
+            entries: vec![entry(1, 0x1, 1), entry(5, 0x5, 1), entry(10, 0xA, 1)],
+        };
+
+        let mut resolver = LeveledListResolver::new(7);
+        for _ in 0..32 {
+            let resolved = resolver.resolve(&list, 1, 6, &TestLookup(None));
+            assert_eq!(resolved.len(), 1);
+            assert_eq!(resolved[0].reference, FormId(0x5));
+        }
+    }
+
+    /// Nested lists should be resolved into their concrete entries and
+    /// the counts should be multiplied through
+    #[test]
+    fn test_nested_lists() {
+        let nested = TestList {
+            chance_none: 0,
+            flags: LeveledFlags::empty(),
+            entries: vec![entry(1, 0x2, 3)],
+        };
+        let list = TestList {
+            chance_none: 0,
+            flags: LeveledFlags::USE_ALL,
+            entries: vec![entry(1, 0x1, 1), entry(1, 0x100, 2)],
+        };
+
+        let mut resolver = LeveledListResolver::new(7);
+        let resolved = resolver.resolve(&list, 1, 1, &TestLookup(Some(&nested)));
+
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].reference, FormId(0x1));
+        assert_eq!(resolved[0].count, 1);
+        assert_eq!(resolved[1].reference, FormId(0x2));
+        assert_eq!(resolved[1].count, 6);
+    }
+
+    /// Resolvers using the same seed should produce the same results
+    #[test]
+    fn test_deterministic() {
+        let list = TestList {
+            chance_none: 50,
+            flags: LeveledFlags::CALCULATE_FROM_ALL_LEVELS | LeveledFlags::CALCULATE_FOR_EACH_ITEM,
+            entries: (1..=20)
+                .map(|level| entry(level, level as u32, 1))
+                .collect(),
+        };
+
+        let resolve = |seed| {
+            LeveledListResolver::new(seed)
+                .resolve(&list, 16, 20, &TestLookup(None))
+                .into_iter()
+                .map(|entry| entry.reference.0)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(resolve(42), resolve(42));
+    }
+}
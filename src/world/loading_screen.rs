@@ -0,0 +1,196 @@
+use crate::{
+    esp::{
+        record::records::lscr::{LoadScreenLocation, LSCR},
+        shared::FormId,
+    },
+    utils::config::Loading,
+};
+use bevy::math::IVec2;
+use fastrand::Rng;
+
+/// Cell being travelled to when the loading screen is shown
+#[derive(Debug, Clone)]
+pub struct LoadingDestination {
+    pub cell: FormId,
+    /// Worldspace of the cell, [`None`] for interior cells
+    pub worldspace: Option<FormId>,
+    /// Grid position of exterior cells
+    pub grid: Option<IVec2>,
+}
+
+/// Loading screen chosen to be displayed
+#[derive(Debug, Clone, Copy)]
+pub enum LoadingScreen<'a> {
+    Record(&'a LSCR),
+    /// Texture from the `sWelcomeScreen` entries of the configuration,
+    /// used when no loading screen records are eligible
+    Welcome(&'a str),
+}
+
+/// Deterministic picker for the loading screen shown while
+/// travelling to a cell
+pub struct LoadingScreenPicker {
+    rng: Rng,
+}
+
+impl LoadingScreenPicker {
+    /// Creates a new picker using the provided seed, pickers
+    /// created with the same seed will make the same choices
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::with_seed(seed),
+        }
+    }
+
+    /// Picks a loading screen for the destination from the eligible
+    /// loading screens, falling back to the welcome screens from the
+    /// `Loading` configuration when none are eligible
+    pub fn pick<'a, I>(
+        &mut self,
+        screens: I,
+        destination: &LoadingDestination,
+        loading: &'a Loading,
+    ) -> Option<LoadingScreen<'a>>
+    where
+        I: IntoIterator<Item = &'a LSCR>,
+    {
+        let eligible = eligible_loading_screens(screens, destination);
+        if !eligible.is_empty() {
+            let index = self.rng.usize(0..eligible.len());
+            return Some(LoadingScreen::Record(eligible[index]));
+        }
+
+        let welcome = welcome_screens(loading);
+        if welcome.is_empty() {
+            return None;
+        }
+
+        let index = self.rng.usize(0..welcome.len());
+        Some(LoadingScreen::Welcome(welcome[index]))
+    }
+}
+
+/// Finds the loading screens that can be shown for the destination.
+///
+/// Loading screens with a location matching the destination are preferred,
+/// when there are none the loading screens without any locations are used
+pub fn eligible_loading_screens<'a, I>(
+    screens: I,
+    destination: &LoadingDestination,
+) -> Vec<&'a LSCR>
+where
+    I: IntoIterator<Item = &'a LSCR>,
+{
+    let mut located: Vec<&'a LSCR> = Vec::new();
+    let mut generic: Vec<&'a LSCR> = Vec::new();
+
+    for screen in screens {
+        if screen.locations.is_empty() {
+            generic.push(screen);
+        } else if screen
+            .locations
+            .iter()
+            .any(|location| location_matches(location, destination))
+        {
+            located.push(screen);
+        }
+    }
+
+    if located.is_empty() {
+        generic
+    } else {
+        located
+    }
+}
+
+/// Checks if the location covers the destination, either directly through
+/// its cell or worldspace or indirectly through a cell grid of a worldspace
+fn location_matches(location: &LoadScreenLocation, destination: &LoadingDestination) -> bool {
+    if !location.direct.is_null() {
+        return location.direct == destination.cell
+            || destination.worldspace.as_ref() == Some(&location.direct);
+    }
+
+    if location.indirect_worldspace.is_null()
+        || destination.worldspace.as_ref() != Some(&location.indirect_worldspace)
+    {
+        return false;
+    }
+
+    match destination.grid {
+        Some(grid) => grid.x == location.grid.x as i32 && grid.y == location.grid.y as i32,
+        None => false,
+    }
+}
+
+/// Non empty welcome screen textures from the configuration
+fn welcome_screens(loading: &Loading) -> Vec<&str> {
+    [
+        &loading.sWelcomeScreen1,
+        &loading.sWelcomeScreen2,
+        &loading.sWelcomeScreen3,
+        &loading.sWelcomeScreen4,
+    ]
+    .into_iter()
+    .map(String::as_str)
+    .filter(|texture| !texture.is_empty())
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{eligible_loading_screens, LoadingDestination};
+    use crate::esp::{
+        record::records::lscr::{LoadScreenLocation, LSCR},
+        shared::{EditorId, FormId},
+    };
+    use bevy::math::IVec2;
+    use nalgebra::Vector2;
+
+    fn screen(locations: Vec<LoadScreenLocation>) -> LSCR {
+        LSCR {
+            editor_id: EditorId(String::new()),
+            texture: None,
+            small_icon_file_name: None,
+            description: None,
+            locations,
+            load_screen_type: None,
+        }
+    }
+
+    /// Screens for the destination grid are preferred over generic screens
+    /// and screens for other locations are never chosen
+    #[test]
+    fn test_eligible_by_grid() {
+        let generic = screen(Vec::new());
+        let grid = screen(vec![LoadScreenLocation {
+            direct: FormId(0),
+            indirect_worldspace: FormId(0x10),
+            grid: Vector2::new(3, -2),
+        }]);
+        let other = screen(vec![LoadScreenLocation {
+            direct: FormId(0x20),
+            indirect_worldspace: FormId(0),
+            grid: Vector2::new(0, 0),
+        }]);
+
+        let destination = LoadingDestination {
+            cell: FormId(0x30),
+            worldspace: Some(FormId(0x10)),
+            grid: Some(IVec2::new(3, -2)),
+        };
+
+        let eligible = eligible_loading_screens([&generic, &grid, &other], &destination);
+        assert_eq!(eligible.len(), 1);
+        assert!(std::ptr::eq(eligible[0], &grid));
+
+        let elsewhere = LoadingDestination {
+            grid: Some(IVec2::new(0, 0)),
+            ..destination
+        };
+
+        let eligible = eligible_loading_screens([&generic, &grid, &other], &elsewhere);
+        assert_eq!(eligible.len(), 1);
+        assert!(std::ptr::eq(eligible[0], &generic));
+    }
+}
@@ -0,0 +1,313 @@
+//! Notifications and message boxes shown from `MESG` records
+
+use std::collections::VecDeque;
+
+use bevy::{ecs::system::Resource, utils::HashMap};
+use thiserror::Error;
+
+use crate::{
+    esp::{
+        record::records::mesg::{MessageFlags, MESG},
+        shared::FormId,
+    },
+    script::condition::{evaluate_all, ConditionContext},
+};
+
+/// Seconds notifications are shown for when the message doesn't specify a time
+pub const DEFAULT_NOTIFICATION_TIME: f32 = 2.0;
+
+/// Text of the button shown on message boxes without any buttons
+const DEFAULT_BUTTON_TEXT: &str = "OK";
+
+#[derive(Debug, Error)]
+pub enum MessageError {
+    #[error("No message box is being shown")]
+    NoMessageBox,
+    #[error("Button {0} is not shown on the message box")]
+    InvalidButton(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub text: String,
+    /// Icon (`MICN`) shown next to the text
+    pub icon: Option<FormId>,
+    /// Seconds the notification is shown for
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageButton {
+    /// Index of the button within the message, which is what
+    /// `GetButtonPressed` returns regardless of hidden buttons
+    pub index: usize,
+    pub text: String,
+}
+
+/// Modal message box waiting for the player to choose a button
+#[derive(Debug, Clone)]
+pub struct MessageBox {
+    /// Script owner that showed the message and receives the pressed button
+    pub owner: Option<FormId>,
+    pub title: Option<String>,
+    pub text: String,
+    pub buttons: Vec<MessageButton>,
+}
+
+/// Queued messages, only one notification and one message box are
+/// shown at a time with the rest shown in the order they were queued
+#[derive(Debug, Default, Resource)]
+pub struct Messages {
+    notifications: VecDeque<Notification>,
+    /// Seconds the current notification has been shown for
+    notification_time: f32,
+    message_boxes: VecDeque<MessageBox>,
+    /// Buttons chosen that haven't been read through `GetButtonPressed`, by owner
+    pressed: HashMap<Option<FormId>, usize>,
+}
+
+impl Messages {
+    /// Shows the message as a message box or notification, `args` are substituted
+    /// into the format specifiers of the text (e.g. `%.0f`). Buttons are hidden
+    /// when their conditions fail against `context`
+    pub fn show<C: ConditionContext + ?Sized>(
+        &mut self,
+        message: &MESG,
+        owner: Option<FormId>,
+        args: &[f32],
+        context: &mut C,
+    ) {
+        let text = format_message(&message.description, args);
+
+        if !message.flags.contains(MessageFlags::MESSAGE_BOX) {
+            self.notify(Notification {
+                text,
+                icon: (!message.icon.is_null()).then(|| message.icon.id.clone()),
+                duration: message
+                    .display_time
+                    .map_or(DEFAULT_NOTIFICATION_TIME, |time| time as f32),
+            });
+            return;
+        }
+
+        let mut buttons: Vec<MessageButton> = message
+            .buttons
+            .iter()
+            .enumerate()
+            .filter(|(_, button)| evaluate_all(&button.conditions, context))
+            .map(|(index, button)| MessageButton {
+                index,
+                text: button.text.clone().unwrap_or_default(),
+            })
+            .collect();
+
+        // Boxes must have a button to close them, including ones where
+        // every button was hidden by its conditions
+        if buttons.is_empty() {
+            buttons.push(MessageButton {
+                index: 0,
+                text: DEFAULT_BUTTON_TEXT.to_string(),
+            });
+        }
+
+        self.message_boxes.push_back(MessageBox {
+            owner,
+            title: message.name.clone(),
+            text,
+            buttons,
+        });
+    }
+
+    pub fn notify(&mut self, notification: Notification) {
+        if self.notifications.is_empty() {
+            self.notification_time = 0.0;
+        }
+        self.notifications.push_back(notification);
+    }
+
+    pub fn notification(&self) -> Option<&Notification> {
+        self.notifications.front()
+    }
+
+    pub fn message_box(&self) -> Option<&MessageBox> {
+        self.message_boxes.front()
+    }
+
+    /// Advances the current notification by `delta` seconds, moving on to
+    /// the next once it has been shown for its duration
+    pub fn tick(&mut self, delta: f32) {
+        let Some(notification) = self.notifications.front() else {
+            return;
+        };
+
+        self.notification_time += delta;
+        if self.notification_time >= notification.duration {
+            self.notifications.pop_front();
+            self.notification_time = 0.0;
+        }
+    }
+
+    /// Chooses the button with the index on the current message box,
+    /// closing it and showing the next queued box
+    pub fn choose(&mut self, index: usize) -> Result<(), MessageError> {
+        let message_box = self
+            .message_boxes
+            .front()
+            .ok_or(MessageError::NoMessageBox)?;
+
+        if !message_box
+            .buttons
+            .iter()
+            .any(|button| button.index == index)
+        {
+            return Err(MessageError::InvalidButton(index));
+        }
+
+        let owner = message_box.owner.clone();
+        self.message_boxes.pop_front();
+        self.pressed.insert(owner, index);
+        Ok(())
+    }
+
+    /// Button chosen on the last message box shown by the owner
+    /// (`GetButtonPressed`), -1 until a button is chosen. Reading the
+    /// button clears it so later calls return -1
+    pub fn button_pressed(&mut self, owner: Option<&FormId>) -> i32 {
+        self.pressed
+            .remove(&owner.cloned())
+            .map_or(-1, |index| index as i32)
+    }
+}
+
+/// Substitutes the values into the `%f`, `%.Nf` and `%g` specifiers of the
+/// message text in order, `%%` is a literal percent sign
+pub fn format_message(text: &str, args: &[f32]) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut args = args.iter();
+    let mut chars = text.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if char != '%' {
+            output.push(char);
+            continue;
+        }
+
+        let mut precision = String::new();
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                precision.push(digit);
+            }
+        }
+
+        match chars.next() {
+            Some('%') => output.push('%'),
+            Some(specifier @ ('f' | 'g')) => {
+                let value = args.next().copied().unwrap_or_default();
+                let formatted = match precision.parse::<usize>() {
+                    Ok(precision) => format!("{value:.precision$}"),
+                    Err(_) if specifier == 'f' => format!("{value:.6}"),
+                    Err(_) => value.to_string(),
+                };
+                output.push_str(&formatted);
+            }
+            // Unknown specifiers are kept as written
+            Some(other) => {
+                output.push('%');
+                if !precision.is_empty() {
+                    output.push('.');
+                    output.push_str(&precision);
+                }
+                output.push(other);
+            }
+            None => output.push('%'),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        esp::{
+            record::{
+                records::mesg::{MessageFlags, MessageMenuButton, MESG},
+                sub::condition::{
+                    ComparisonOperator, ConditionFlags, RawComparisonValue, RunOn, CTDA,
+                },
+            },
+            shared::{EditorId, FormId},
+        },
+        script::condition::{Condition, ConditionContext},
+    };
+
+    use super::{format_message, Messages};
+
+    /// Every condition function returns 0
+    struct Context;
+
+    impl ConditionContext for Context {
+        fn call(&mut self, _run_on: RunOn, _condition: &Condition) -> Option<f32> {
+            Some(0.0)
+        }
+
+        fn global(&self, _form_id: &FormId) -> Option<f32> {
+            None
+        }
+    }
+
+    fn button(text: &str, pass: bool) -> MessageMenuButton {
+        let value: f32 = if pass { 0.0 } else { 1.0 };
+        MessageMenuButton {
+            text: Some(text.to_string()),
+            conditions: vec![CTDA {
+                operator: ComparisonOperator::EqualTo,
+                flags: ConditionFlags::empty(),
+                comparison_value: RawComparisonValue(value.to_le_bytes()),
+                // GetLocked
+                func_index: 5,
+                param_1: [0; 4],
+                param_2: [0; 4],
+                run_on: RunOn::Subject,
+                reference: FormId::NULL,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_message_box() {
+        let message = MESG {
+            editor_id: EditorId("TestMessage".to_string()),
+            description: "You have %.0f caps".to_string(),
+            name: None,
+            icon: FormId::NULL.into_typed(),
+            flags: MessageFlags::MESSAGE_BOX,
+            display_time: None,
+            buttons: vec![
+                button("Hidden", false),
+                button("Yes", true),
+                button("No", true),
+            ],
+        };
+        let owner = Some(FormId(0x14));
+
+        let mut messages = Messages::default();
+        messages.show(&message, owner.clone(), &[25.0], &mut Context);
+        messages.show(&message, None, &[0.0], &mut Context);
+
+        let message_box = messages.message_box().unwrap();
+        assert_eq!(message_box.text, "You have 25 caps");
+        assert_eq!(message_box.buttons.len(), 2);
+        assert!(messages.choose(0).is_err());
+
+        assert_eq!(messages.button_pressed(owner.as_ref()), -1);
+        messages.choose(2).unwrap();
+        assert_eq!(messages.button_pressed(owner.as_ref()), 2);
+        assert_eq!(messages.button_pressed(owner.as_ref()), -1);
+
+        // The second message is shown once the first is closed
+        assert_eq!(messages.message_box().unwrap().text, "You have 0 caps");
+        assert_eq!(format_message("100%% %g", &[1.5]), "100% 1.5");
+    }
+}
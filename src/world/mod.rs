@@ -0,0 +1,14 @@
+//! Runtime state of the game world built on top of the loaded records
+
+pub mod form_list;
+pub mod globals;
+pub mod idle;
+pub mod image_space;
+pub mod leveled;
+pub mod loading_screen;
+pub mod message;
+pub mod music;
+pub mod ragdoll;
+pub mod terminal;
+pub mod water;
+pub mod weather;
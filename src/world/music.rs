@@ -0,0 +1,253 @@
+use crate::esp::record::records::{
+    mset::{MediaSetTrack, MediaSetType, MSET},
+    musc::MUSC,
+    wthr::TimeOfDay,
+};
+
+/// Fade out time used for battle sets that don't specify one
+const DEFAULT_BATTLE_FADE_OUT: f32 = 5.0;
+
+/// State of the player the music is being chosen for
+#[derive(Debug, Clone, Copy)]
+pub struct MusicContext<'a> {
+    /// Music type of the current location, from [`CELL::music_type`]
+    ///
+    /// [`CELL::music_type`]: crate::esp::record::records::cell::CELL::music_type
+    pub location_music: Option<&'a MUSC>,
+    /// Music played when the location doesn't specify any music
+    pub default_music: Option<&'a MUSC>,
+    /// Location or dungeon media set of the current location
+    pub media_set: Option<&'a MSET>,
+    /// Battle media set played during combat
+    pub battle_media_set: Option<&'a MSET>,
+    pub in_combat: bool,
+    /// Current time of day, from [`time_of_day`] with the climate timing
+    ///
+    /// [`time_of_day`]: crate::world::weather::time_of_day
+    pub time_of_day: TimeOfDay,
+}
+
+/// What should currently be playing
+#[derive(Debug)]
+pub enum MusicPlayback<'a> {
+    Silence,
+    Track(&'a MUSC),
+    /// Tracks of a location or dungeon media set, for location sets
+    /// these are the enabled layers for the time of day
+    MediaSet {
+        media_set: &'a MSET,
+        tracks: Vec<&'a MediaSetTrack>,
+    },
+    Battle {
+        media_set: &'a MSET,
+        /// Whether the battle music is fading out after combat ended
+        fading_out: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MusicState {
+    Explore,
+    Battle,
+    /// Combat has ended and the battle music is fading out
+    Recovery {
+        remaining: f32,
+    },
+}
+
+/// State machine deciding which music plays as the player moves
+/// between locations and in and out of combat
+#[derive(Debug)]
+pub struct MusicStateMachine {
+    state: MusicState,
+}
+
+impl Default for MusicStateMachine {
+    fn default() -> Self {
+        Self {
+            state: MusicState::Explore,
+        }
+    }
+}
+
+impl MusicStateMachine {
+    pub fn state(&self) -> MusicState {
+        self.state
+    }
+
+    /// Advances the state machine by `delta` seconds and determines
+    /// what should be playing
+    pub fn update<'a>(&mut self, context: &MusicContext<'a>, delta: f32) -> MusicPlayback<'a> {
+        self.state = match (self.state, context.in_combat) {
+            (_, true) => MusicState::Battle,
+            // The frame combat ends in counts towards the fade out
+            (MusicState::Battle, false) => recovery(
+                context
+                    .battle_media_set
+                    .and_then(|media_set| media_set.fade_out)
+                    .unwrap_or(DEFAULT_BATTLE_FADE_OUT),
+                delta,
+            ),
+            (MusicState::Recovery { remaining }, false) => recovery(remaining, delta),
+            (MusicState::Explore, false) => MusicState::Explore,
+        };
+
+        let fading_out = match self.state {
+            MusicState::Explore => return explore_playback(context),
+            MusicState::Battle => false,
+            MusicState::Recovery { .. } => true,
+        };
+
+        match context.battle_media_set {
+            Some(media_set) => MusicPlayback::Battle {
+                media_set,
+                fading_out,
+            },
+            None => explore_playback(context),
+        }
+    }
+}
+
+/// Continues fading out the battle music for `delta` seconds, returning
+/// to exploring once the fade out has finished
+fn recovery(remaining: f32, delta: f32) -> MusicState {
+    if remaining > delta {
+        MusicState::Recovery {
+            remaining: remaining - delta,
+        }
+    } else {
+        MusicState::Explore
+    }
+}
+
+/// Whether the nighttime layers of location media sets play, sunrise
+/// and sunset count as day
+pub fn is_night(time_of_day: TimeOfDay) -> bool {
+    matches!(time_of_day, TimeOfDay::Night | TimeOfDay::Midnight)
+}
+
+/// Determines the music played while not in combat, media sets take
+/// priority over the music of the location
+fn explore_playback<'a>(context: &MusicContext<'a>) -> MusicPlayback<'a> {
+    if let Some(media_set) = context.media_set {
+        let tracks: Vec<&'a MediaSetTrack> = match media_set.ty {
+            MediaSetType::Location => media_set
+                .layers(is_night(context.time_of_day))
+                .map(|(_, track)| track)
+                .collect(),
+            // Loop, explore and suspense tracks
+            MediaSetType::Dungeon => media_set.tracks[..3].iter().collect(),
+            _ => Vec::new(),
+        };
+
+        let tracks: Vec<&'a MediaSetTrack> = tracks
+            .into_iter()
+            .filter(|track| track.file_name.is_some())
+            .collect();
+
+        if !tracks.is_empty() {
+            return MusicPlayback::MediaSet { media_set, tracks };
+        }
+    }
+
+    match context.location_music.or(context.default_music) {
+        Some(music) => MusicPlayback::Track(music),
+        None => MusicPlayback::Silence,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MusicContext, MusicPlayback, MusicState, MusicStateMachine};
+    use crate::esp::{
+        record::records::{
+            mset::{MediaSetLayerFlags, MediaSetTrack, MediaSetType, MSET},
+            musc::MUSC,
+            wthr::TimeOfDay,
+        },
+        shared::EditorId,
+    };
+
+    fn media_set(ty: MediaSetType, fade_out: f32) -> MSET {
+        MSET {
+            editor_id: EditorId(String::new()),
+            name: None,
+            ty,
+            tracks: std::array::from_fn(|index| MediaSetTrack {
+                file_name: Some(format!("track{index}.mp3")),
+                db: None,
+                boundary: None,
+            }),
+            enabled_layers: MediaSetLayerFlags::DAY_OUTER | MediaSetLayerFlags::NIGHT_INNER,
+            min_time_on: None,
+            fade_out: Some(fade_out),
+            max_time_on: None,
+            night_time_max: None,
+            intro: None,
+            outro: None,
+        }
+    }
+
+    /// Combat switches to the battle set which fades out for the set's fade
+    /// out time before returning to the location layers for the time of day
+    #[test]
+    fn test_battle_recovery() {
+        let location = media_set(MediaSetType::Location, 0.0);
+        let battle = media_set(MediaSetType::Battle, 2.0);
+        let music = MUSC {
+            editor_id: EditorId(String::new()),
+            file_name: None,
+            db: None,
+        };
+
+        let mut context = MusicContext {
+            location_music: Some(&music),
+            default_music: None,
+            media_set: Some(&location),
+            battle_media_set: Some(&battle),
+            in_combat: true,
+            time_of_day: TimeOfDay::Night,
+        };
+
+        let mut machine = MusicStateMachine::default();
+        assert!(matches!(
+            machine.update(&context, 1.0),
+            MusicPlayback::Battle {
+                fading_out: false,
+                ..
+            }
+        ));
+
+        context.in_combat = false;
+        assert!(matches!(
+            machine.update(&context, 1.0),
+            MusicPlayback::Battle {
+                fading_out: true,
+                ..
+            }
+        ));
+        assert_eq!(machine.state(), MusicState::Recovery { remaining: 1.0 });
+        machine.update(&context, 0.5);
+        assert_eq!(machine.state(), MusicState::Recovery { remaining: 0.5 });
+
+        match machine.update(&context, 1.0) {
+            MusicPlayback::MediaSet { tracks, .. } => {
+                assert_eq!(tracks.len(), 1);
+                assert_eq!(tracks[0].file_name.as_deref(), Some("track5.mp3"));
+            }
+            playback => panic!("unexpected playback {playback:?}"),
+        }
+
+        // Fade outs shorter than the frame return to exploring straight away
+        let battle = media_set(MediaSetType::Battle, 0.5);
+        context.battle_media_set = Some(&battle);
+        context.in_combat = true;
+        machine.update(&context, 1.0);
+        context.in_combat = false;
+        assert!(matches!(
+            machine.update(&context, 1.0),
+            MusicPlayback::MediaSet { .. }
+        ));
+        assert_eq!(machine.state(), MusicState::Explore);
+    }
+}
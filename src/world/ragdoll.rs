@@ -0,0 +1,232 @@
+use crate::esp::record::records::rgdl::RGDL;
+use bevy::{ecs::entity::Entity, math::Vec3};
+use bevy_rapier3d::prelude::{ImpulseJoint, JointAxis, RigidBody, SphericalJointBuilder};
+
+/// Bone within the skeleton a ragdoll is being built for
+#[derive(Debug, Clone)]
+pub struct SkeletonBone {
+    pub name: String,
+    /// Index of the parent bone within the skeleton
+    pub parent: Option<usize>,
+    /// Translation of the bone relative to its parent
+    pub translation: Vec3,
+}
+
+/// Physics description of a ragdoll built from a RGDL record and
+/// the skeleton of the actor it belongs to
+#[derive(Debug)]
+pub struct RagdollDescription {
+    pub bodies: Vec<RagdollBody>,
+    pub joints: Vec<RagdollJoint>,
+    /// Blend between the keyframed animation (0) and the dynamic simulation (1)
+    pub keyframe_blend: f32,
+    /// File name of the pose the ragdoll is blended into on death
+    pub death_pose: Option<String>,
+}
+
+/// Rigid body created for a single skeleton bone
+#[derive(Debug)]
+pub struct RagdollBody {
+    /// Index of the bone within the skeleton
+    pub bone: usize,
+    /// Whether the body is simulated rather than following the animation
+    pub dynamic: bool,
+}
+
+/// Joint connecting the body of a bone to the body of its parent
+#[derive(Debug)]
+pub struct RagdollJoint {
+    /// Index of the parent bone within the skeleton
+    pub parent: usize,
+    /// Index of the child bone within the skeleton
+    pub child: usize,
+    /// Position of the joint relative to the parent bone
+    pub parent_anchor: Vec3,
+    /// Position of the joint relative to the child bone
+    pub child_anchor: Vec3,
+    /// Stiffness of the motors driving the joint towards the animated
+    /// pose, zero when pose matching is disabled
+    pub motor_stiffness: f32,
+    pub motor_damping: f32,
+}
+
+impl RagdollDescription {
+    pub fn new(ragdoll: &RGDL, skeleton: &[SkeletonBone]) -> Self {
+        // With feedback enabled only the listed bones are simulated while
+        // the rest follow the animation
+        let feedback = ragdoll.data.feedback_enabled;
+        let bodies: Vec<RagdollBody> = (0..skeleton.len())
+            .map(|bone| RagdollBody {
+                bone,
+                dynamic: !feedback
+                    || ragdoll
+                        .feedback_dynamic_bones
+                        .iter()
+                        .any(|&index| index as usize == bone),
+            })
+            .collect();
+
+        let motor_stiffness = ragdoll
+            .pose_matching_data
+            .as_ref()
+            .filter(|_| ragdoll.data.pose_matching_enabled)
+            .map(|data| data.motors_strength)
+            .unwrap_or_default();
+        let motor_damping = ragdoll
+            .feedback_data
+            .as_ref()
+            .map(|data| data.velocity_damping)
+            .unwrap_or_default();
+
+        let joints: Vec<RagdollJoint> = skeleton
+            .iter()
+            .enumerate()
+            .filter_map(|(child, bone)| {
+                let parent = bone.parent.filter(|&parent| parent < skeleton.len())?;
+                Some(RagdollJoint {
+                    parent,
+                    child,
+                    parent_anchor: bone.translation,
+                    child_anchor: Vec3::ZERO,
+                    motor_stiffness,
+                    motor_damping,
+                })
+            })
+            .collect();
+
+        let keyframe_blend = ragdoll
+            .feedback_data
+            .as_ref()
+            .map(|data| data.dynamic_keyframe_blend_amount)
+            .unwrap_or(1.0);
+
+        Self {
+            bodies,
+            joints,
+            keyframe_blend,
+            death_pose: ragdoll.death_pose.clone(),
+        }
+    }
+}
+
+impl RagdollBody {
+    /// Rigid body type used for the body
+    pub fn rigid_body(&self) -> RigidBody {
+        if self.dynamic {
+            RigidBody::Dynamic
+        } else {
+            RigidBody::KinematicPositionBased
+        }
+    }
+}
+
+impl RagdollJoint {
+    /// Creates the joint to attach to the child body entity
+    pub fn impulse_joint(&self, parent: Entity) -> ImpulseJoint {
+        let mut joint = SphericalJointBuilder::new()
+            .local_anchor1(self.parent_anchor)
+            .local_anchor2(self.child_anchor);
+
+        if self.motor_stiffness > 0.0 {
+            for axis in [JointAxis::AngX, JointAxis::AngY, JointAxis::AngZ] {
+                joint = joint.motor_position(axis, 0.0, self.motor_stiffness, self.motor_damping);
+            }
+        }
+
+        ImpulseJoint::new(parent, joint)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RagdollDescription, SkeletonBone};
+    use crate::esp::{
+        record::records::rgdl::{
+            PoseMatchingFlags, RagdollData, RagdollFeedbackData, RagdollPoseMatchingData, RGDL,
+        },
+        shared::EditorId,
+    };
+    use bevy::math::Vec3;
+
+    fn ragdoll(pose_matching_enabled: bool) -> RGDL {
+        RGDL {
+            editor_id: EditorId(String::new()),
+            version: 0,
+            data: RagdollData {
+                dynamic_bone_count: 1,
+                feedback_enabled: true,
+                foot_ik_enabled: false,
+                look_ik_enabled: false,
+                grab_transfer_enabled: false,
+                pose_matching_enabled,
+            },
+            actor_base: None,
+            body_part_data: None,
+            feedback_data: Some(RagdollFeedbackData {
+                dynamic_keyframe_blend_amount: 0.75,
+                hierarchy_gain: 0.0,
+                position_gain: 0.0,
+                velocity_gain: 0.0,
+                acceleration_gain: 0.0,
+                snap_gain: 0.0,
+                velocity_damping: 0.3,
+                snap_max_linear_velocity: 0.0,
+                snap_max_angular_velocity: 0.0,
+                snap_max_linear_distance: 0.0,
+                snap_max_angular_distance: 0.0,
+                position_max_linear_velocity: 0.0,
+                position_max_angular_velocity: 0.0,
+                projectile_impulse: 0.0,
+                melee_impulse: 0.0,
+            }),
+            feedback_dynamic_bones: vec![1],
+            pose_matching_data: Some(RagdollPoseMatchingData {
+                match_bones: [0, 1, 0],
+                flags: PoseMatchingFlags::empty(),
+                motors_strength: 0.6,
+                pose_activation_delay_time: 0.0,
+                match_error_allowance: 0.0,
+                displacement_to_disable: 0.0,
+            }),
+            death_pose: None,
+        }
+    }
+
+    /// Root bone with a single child one unit above it
+    fn skeleton() -> Vec<SkeletonBone> {
+        vec![
+            SkeletonBone {
+                name: "Bip01".to_string(),
+                parent: None,
+                translation: Vec3::ZERO,
+            },
+            SkeletonBone {
+                name: "Bip01 Spine".to_string(),
+                parent: Some(0),
+                translation: Vec3::new(0.0, 1.0, 0.0),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_two_bones() {
+        let description = RagdollDescription::new(&ragdoll(true), &skeleton());
+
+        // Only the feedback bone is simulated
+        let dynamic: Vec<bool> = description.bodies.iter().map(|body| body.dynamic).collect();
+        assert_eq!(dynamic, [false, true]);
+        assert_eq!(description.keyframe_blend, 0.75);
+
+        assert_eq!(description.joints.len(), 1);
+        let joint = &description.joints[0];
+        assert_eq!((joint.parent, joint.child), (0, 1));
+        assert_eq!(joint.parent_anchor, Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(joint.child_anchor, Vec3::ZERO);
+        assert_eq!(joint.motor_stiffness, 0.6);
+        assert_eq!(joint.motor_damping, 0.3);
+
+        // Without pose matching the motors are disabled
+        let description = RagdollDescription::new(&ragdoll(false), &skeleton());
+        assert_eq!(description.joints[0].motor_stiffness, 0.0);
+    }
+}
@@ -0,0 +1,574 @@
+//! Interaction with terminals (`TERM`) independent of how they are rendered
+
+use thiserror::Error;
+
+use crate::{
+    esp::{
+        record::{
+            records::{
+                note::NOTE,
+                term::{ANAMFlags, BaseHackingDifficulty, DNAMFlags, MenuItem, TERM},
+            },
+            sub::script::Script,
+        },
+        shared::FormId,
+    },
+    script::{
+        ast::ScriptAst,
+        condition::{evaluate_all, ConditionContext},
+        interpreter::{ScriptError, ScriptInstance, ScriptRunner},
+        parser::{parse_result_script, ParseError},
+    },
+};
+
+/// Number of failed hacking attempts before the terminal locks the player out
+pub const HACKING_ATTEMPTS: u32 = 4;
+
+#[derive(Debug, Error)]
+pub enum TerminalError {
+    #[error("Unknown terminal {:08X}", .0 .0)]
+    UnknownTerminal(FormId),
+    #[error("Terminal is locked")]
+    Locked,
+    #[error("Menu item {0} is not shown")]
+    InvalidItem(usize),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Script(#[from] ScriptError),
+}
+
+/// Records and game state the terminal is shown against
+pub trait TerminalContext<'a>: ConditionContext {
+    fn terminal(&self, form_id: &FormId) -> Option<&'a TERM>;
+
+    fn note(&self, form_id: &FormId) -> Option<&'a NOTE>;
+
+    /// Called when a menu item with the add note flag displays a note
+    fn add_note(&mut self, _note: &FormId) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockState {
+    Unlocked,
+    Locked {
+        attempts_remaining: u32,
+    },
+    /// Too many failed hacking attempts were made
+    LockedOut,
+}
+
+/// What the terminal is showing below the welcome text
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminalScreen {
+    Menu,
+    /// Result text of the chosen menu item
+    Text(String),
+    Note {
+        note: FormId,
+        title: String,
+        /// Contents of text notes
+        text: Option<String>,
+        /// Texture of image notes
+        image: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalMenuItem {
+    /// Index of the item within the terminal
+    pub index: usize,
+    pub text: String,
+}
+
+/// Current contents of the terminal to render
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalView {
+    /// Welcome text, [`None`] when the terminal hides it while showing text
+    pub welcome: Option<String>,
+    pub screen: TerminalScreen,
+    /// Items whose conditions pass, only listed on the menu screen
+    pub items: Vec<TerminalMenuItem>,
+}
+
+/// A player's session with a terminal, navigating through its menus
+/// and the sub terminals they link to
+#[derive(Debug)]
+pub struct TerminalSession {
+    /// Placed terminal result scripts run on behalf of
+    reference: Option<FormId>,
+    /// Terminals navigated through, the last is the one being shown
+    stack: Vec<FormId>,
+    screen: TerminalScreen,
+    lock: LockState,
+}
+
+impl TerminalSession {
+    /// Starts a session with the terminal placed as `reference`, which is
+    /// locked unless flagged as unlocked
+    pub fn open(form_id: FormId, terminal: &TERM, reference: Option<FormId>) -> Self {
+        let lock = if terminal.dnam.flags.contains(DNAMFlags::UNLOCKED) {
+            LockState::Unlocked
+        } else {
+            LockState::Locked {
+                attempts_remaining: HACKING_ATTEMPTS,
+            }
+        };
+
+        Self {
+            reference,
+            stack: vec![form_id],
+            screen: TerminalScreen::Menu,
+            lock,
+        }
+    }
+
+    pub fn lock_state(&self) -> LockState {
+        self.lock
+    }
+
+    pub fn screen(&self) -> &TerminalScreen {
+        &self.screen
+    }
+
+    /// FormId of the terminal being shown
+    pub fn current(&self) -> &FormId {
+        self.stack.last().expect("Terminal stack is never empty")
+    }
+
+    fn terminal<'a, C: TerminalContext<'a>>(&self, context: &C) -> Result<&'a TERM, TerminalError> {
+        context
+            .terminal(self.current())
+            .ok_or_else(|| TerminalError::UnknownTerminal(self.current().clone()))
+    }
+
+    /// Science skill required to hack the terminal, [`None`] for
+    /// terminals that can only be opened with their password
+    pub fn required_skill(difficulty: BaseHackingDifficulty) -> Option<u32> {
+        match difficulty {
+            BaseHackingDifficulty::VeryEasy => Some(0),
+            BaseHackingDifficulty::Easy => Some(25),
+            BaseHackingDifficulty::Average => Some(50),
+            BaseHackingDifficulty::Hard => Some(75),
+            BaseHackingDifficulty::VeryHard => Some(100),
+            BaseHackingDifficulty::RequiresKey => None,
+        }
+    }
+
+    /// Whether the player can attempt to hack the terminal
+    pub fn can_hack(&self, terminal: &TERM, science_skill: u32) -> bool {
+        matches!(self.lock, LockState::Locked { .. })
+            && Self::required_skill(terminal.dnam.base_hacking_difficulty)
+                .is_some_and(|required| science_skill >= required)
+    }
+
+    /// Unlocks the terminal when the player has its password note
+    pub fn unlock_with_password(
+        &mut self,
+        terminal: &TERM,
+        has_note: impl Fn(&FormId) -> bool,
+    ) -> bool {
+        let Some(password) = &terminal.password_note else {
+            return false;
+        };
+        if self.lock == LockState::LockedOut || !has_note(&password.id) {
+            return false;
+        }
+        self.lock = LockState::Unlocked;
+        true
+    }
+
+    /// Records the result of a hacking attempt, failing the last
+    /// attempt locks the player out of the terminal
+    pub fn hack_attempt(&mut self, success: bool) -> LockState {
+        if let LockState::Locked { attempts_remaining } = self.lock {
+            self.lock = match (success, attempts_remaining) {
+                (true, _) => LockState::Unlocked,
+                (false, 0 | 1) => LockState::LockedOut,
+                (false, attempts_remaining) => LockState::Locked {
+                    attempts_remaining: attempts_remaining - 1,
+                },
+            };
+        }
+        self.lock
+    }
+
+    /// Menu items of the current terminal whose conditions pass
+    fn visible_items<'a, C: TerminalContext<'a>>(
+        terminal: &'a TERM,
+        context: &mut C,
+    ) -> Vec<(usize, &'a MenuItem)> {
+        terminal
+            .menu_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| evaluate_all(&item.conditions, context))
+            .collect()
+    }
+
+    /// Contents of the terminal to show
+    pub fn view<'a, C: TerminalContext<'a>>(
+        &self,
+        context: &mut C,
+    ) -> Result<TerminalView, TerminalError> {
+        let terminal = self.terminal(context)?;
+
+        let hide_welcome = terminal
+            .dnam
+            .flags
+            .contains(DNAMFlags::HIDE_WELCOME_TEXT_WHEN_DISPLAYING_TEXT)
+            && self.screen != TerminalScreen::Menu;
+
+        let items = match (&self.screen, self.lock) {
+            (TerminalScreen::Menu, LockState::Unlocked) => Self::visible_items(terminal, context)
+                .into_iter()
+                .map(|(index, item)| TerminalMenuItem {
+                    index,
+                    text: item.item_text.clone().unwrap_or_default(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(TerminalView {
+            welcome: (!hide_welcome).then(|| terminal.description.clone()),
+            screen: self.screen.clone(),
+            items,
+        })
+    }
+
+    /// Runs the result script of a menu item, result scripts without
+    /// source have nothing to run
+    fn run_result_script(
+        &self,
+        script: &Script,
+        runner: &mut ScriptRunner<'_>,
+    ) -> Result<(), TerminalError> {
+        if script.source.trim().is_empty() {
+            return Ok(());
+        }
+
+        let statements = parse_result_script(&script.source)?;
+        let mut instance = ScriptInstance::new(&ScriptAst::default(), self.reference.clone());
+        runner.run_result_script(&statements, &mut instance)?;
+        Ok(())
+    }
+
+    /// Chooses the menu item with the index, running its result script then
+    /// navigating to its sub terminal, note or result text
+    pub fn select<'a, C: TerminalContext<'a>>(
+        &mut self,
+        index: usize,
+        context: &mut C,
+        runner: &mut ScriptRunner<'_>,
+    ) -> Result<(), TerminalError> {
+        if self.lock != LockState::Unlocked {
+            return Err(TerminalError::Locked);
+        }
+
+        let terminal = self.terminal(context)?;
+        let (_, item) = Self::visible_items(terminal, context)
+            .into_iter()
+            .find(|(item_index, _)| *item_index == index)
+            .ok_or(TerminalError::InvalidItem(index))?;
+
+        self.run_result_script(&item.embedded_script, runner)?;
+
+        if let Some(sub_menu) = item.sub_menu.as_ref().filter(|id| !id.is_null()) {
+            if context.terminal(&sub_menu.id).is_none() {
+                return Err(TerminalError::UnknownTerminal(sub_menu.id.clone()));
+            }
+            self.stack.push(sub_menu.id.clone());
+            self.screen = TerminalScreen::Menu;
+        } else if let Some(note_id) = item.display_note.as_ref().filter(|id| !id.is_null()) {
+            if item.flags.contains(ANAMFlags::ADD_NOTE) {
+                context.add_note(&note_id.id);
+            }
+            let note = context.note(&note_id.id);
+            self.screen = TerminalScreen::Note {
+                note: note_id.id.clone(),
+                title: note.map(|note| note.name.clone()).unwrap_or_default(),
+                text: note.and_then(NOTE::text),
+                image: note.and_then(|note| note.texture.clone()),
+            };
+        } else {
+            self.screen = TerminalScreen::Text(item.result_text.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Goes back to the menu from a text or note, or to the previous
+    /// terminal from a sub terminal. Returns false when there is nothing
+    /// to go back to and the terminal should be closed
+    pub fn back(&mut self) -> bool {
+        if self.screen != TerminalScreen::Menu {
+            self.screen = TerminalScreen::Menu;
+            return true;
+        }
+        if self.stack.len() > 1 {
+            self.stack.pop();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use bevy::utils::HashMap;
+    use nalgebra::Vector3;
+
+    use crate::{
+        esp::{
+            record::{
+                records::{
+                    note::NOTE,
+                    term::{
+                        ANAMFlags, BaseHackingDifficulty, DNAMFlags, MenuItem, ServerType, DNAM,
+                        TERM,
+                    },
+                },
+                sub::{
+                    condition::{
+                        ComparisonOperator, ConditionFlags, RawComparisonValue, RunOn, CTDA,
+                    },
+                    object_bounds::ObjectBounds,
+                    script::{SCHRFlags, SCHRType, Script, SCHR},
+                },
+            },
+            shared::{EditorId, FormId},
+        },
+        script::{
+            condition::{Condition, ConditionContext},
+            interpreter::{ScriptEnvironment, ScriptFunctions, ScriptRunner, Value},
+        },
+    };
+
+    use super::{LockState, TerminalContext, TerminalScreen, TerminalSession};
+
+    /// Every condition function returns 0
+    struct Context<'a> {
+        terminals: &'a HashMap<FormId, TERM>,
+        notes_added: Vec<FormId>,
+    }
+
+    struct Environment;
+
+    impl ScriptEnvironment for Environment {
+        fn form(&self, _editor_id: &str) -> Option<FormId> {
+            None
+        }
+    }
+
+    impl ConditionContext for Context<'_> {
+        fn call(&mut self, _run_on: RunOn, _condition: &Condition) -> Option<f32> {
+            Some(0.0)
+        }
+
+        fn global(&self, _form_id: &FormId) -> Option<f32> {
+            None
+        }
+    }
+
+    impl<'a> TerminalContext<'a> for Context<'a> {
+        fn terminal(&self, form_id: &FormId) -> Option<&'a TERM> {
+            self.terminals.get(form_id)
+        }
+
+        fn note(&self, _form_id: &FormId) -> Option<&'a NOTE> {
+            None
+        }
+
+        fn add_note(&mut self, note: &FormId) {
+            self.notes_added.push(note.clone());
+        }
+    }
+
+    fn item(text: &str, sub_menu: Option<u32>, shown: bool) -> MenuItem {
+        let value: f32 = if shown { 0.0 } else { 1.0 };
+        MenuItem {
+            item_text: Some(text.to_string()),
+            result_text: format!("{text} result"),
+            flags: ANAMFlags::empty(),
+            display_note: None,
+            sub_menu: sub_menu.map(|id| FormId(id).into_typed()),
+            embedded_script: Script {
+                basic_data: SCHR {
+                    ref_count: 0,
+                    compiled_size: 0,
+                    variable_count: 0,
+                    ty: SCHRType::Object,
+                    flags: SCHRFlags::empty(),
+                },
+                compiled_source: Vec::new(),
+                source: "ShowMessage TerminalMessage".to_string(),
+                local_variables: Vec::new(),
+                references: Vec::new(),
+            },
+            conditions: vec![CTDA {
+                operator: ComparisonOperator::EqualTo,
+                flags: ConditionFlags::empty(),
+                comparison_value: RawComparisonValue(value.to_le_bytes()),
+                // GetLocked
+                func_index: 5,
+                param_1: [0; 4],
+                param_2: [0; 4],
+                run_on: RunOn::Subject,
+                reference: FormId::NULL,
+            }],
+        }
+    }
+
+    fn terminal(editor_id: &str, flags: DNAMFlags, menu_items: Vec<MenuItem>) -> TERM {
+        TERM {
+            editor_id: EditorId(editor_id.to_string()),
+            object_bounds: ObjectBounds {
+                start: Vector3::zeros(),
+                end: Vector3::zeros(),
+            },
+            name: None,
+            model_data: None,
+            script: None,
+            destruction_data: None,
+            description: format!("Welcome to {editor_id}"),
+            sound_looping: None,
+            password_note: None,
+            dnam: DNAM {
+                base_hacking_difficulty: BaseHackingDifficulty::Average,
+                flags,
+                server_type: ServerType::Server1,
+            },
+            menu_items,
+        }
+    }
+
+    #[test]
+    fn test_terminal_session() {
+        let terminals = HashMap::from_iter([
+            (
+                FormId(0x1),
+                terminal(
+                    "MainTerminal",
+                    DNAMFlags::HIDE_WELCOME_TEXT_WHEN_DISPLAYING_TEXT,
+                    vec![
+                        item("Hidden", None, false),
+                        item("Logs", Some(0x2), true),
+                        item("Status", None, true),
+                    ],
+                ),
+            ),
+            (
+                FormId(0x2),
+                terminal(
+                    "LogTerminal",
+                    DNAMFlags::UNLOCKED,
+                    vec![item("Entry", None, true)],
+                ),
+            ),
+        ]);
+        let mut context = Context {
+            terminals: &terminals,
+            notes_added: Vec::new(),
+        };
+
+        // Result scripts show a message on behalf of the placed terminal
+        let messages_shown = Arc::new(AtomicUsize::new(0));
+        let mut functions = ScriptFunctions::default();
+        {
+            let messages_shown = messages_shown.clone();
+            functions.register("ShowMessage", move |context, _| {
+                assert_eq!(context.this, Some(FormId(0x50)));
+                messages_shown.fetch_add(1, Ordering::Relaxed);
+                Ok(Value::Int(0))
+            });
+        }
+        let mut environment = Environment;
+        let mut runner = ScriptRunner::new(&functions, &mut environment);
+
+        let mut session =
+            TerminalSession::open(FormId(0x1), &terminals[&FormId(0x1)], Some(FormId(0x50)));
+        assert!(session.can_hack(&terminals[&FormId(0x1)], 50));
+        assert!(!session.can_hack(&terminals[&FormId(0x1)], 25));
+        assert!(session.view(&mut context).unwrap().items.is_empty());
+        assert!(session.select(1, &mut context, &mut runner).is_err());
+
+        assert_eq!(
+            session.hack_attempt(false),
+            LockState::Locked {
+                attempts_remaining: 3
+            }
+        );
+        assert_eq!(session.hack_attempt(true), LockState::Unlocked);
+
+        let view = session.view(&mut context).unwrap();
+        let indices: Vec<usize> = view.items.iter().map(|item| item.index).collect();
+        assert_eq!(indices, [1, 2]);
+        assert!(session.select(0, &mut context, &mut runner).is_err());
+
+        session.select(2, &mut context, &mut runner).unwrap();
+        let view = session.view(&mut context).unwrap();
+        assert_eq!(
+            view.screen,
+            TerminalScreen::Text("Status result".to_string())
+        );
+        assert_eq!(view.welcome, None);
+        assert!(view.items.is_empty());
+
+        assert!(session.back());
+        session.select(1, &mut context, &mut runner).unwrap();
+        assert_eq!(session.current(), &FormId(0x2));
+        assert_eq!(messages_shown.load(Ordering::Relaxed), 2);
+
+        // Back from the sub terminal to the main one, then closing the terminal
+        assert!(session.back());
+        assert_eq!(session.current(), &FormId(0x1));
+        assert!(!session.back());
+    }
+
+    /// Only items with the add note flag give the player their note
+    #[test]
+    fn test_add_note() {
+        let note_item = |text, note, flags| MenuItem {
+            display_note: Some(FormId(note).into_typed()),
+            flags,
+            ..item(text, None, true)
+        };
+        let terminals = HashMap::from_iter([(
+            FormId(0x1),
+            terminal(
+                "NoteTerminal",
+                DNAMFlags::UNLOCKED,
+                vec![
+                    note_item("Read", 0x30, ANAMFlags::FORCE_REDRAW),
+                    note_item("Download", 0x31, ANAMFlags::ADD_NOTE),
+                ],
+            ),
+        )]);
+        let mut context = Context {
+            terminals: &terminals,
+            notes_added: Vec::new(),
+        };
+        let mut functions = ScriptFunctions::default();
+        functions.register("ShowMessage", |_, _| Ok(Value::Int(0)));
+        let mut environment = Environment;
+        let mut runner = ScriptRunner::new(&functions, &mut environment);
+
+        let mut session = TerminalSession::open(FormId(0x1), &terminals[&FormId(0x1)], None);
+        session.select(0, &mut context, &mut runner).unwrap();
+        assert!(matches!(
+            session.view(&mut context).unwrap().screen,
+            TerminalScreen::Note { .. }
+        ));
+        assert!(context.notes_added.is_empty());
+
+        assert!(session.back());
+        session.select(1, &mut context, &mut runner).unwrap();
+        assert_eq!(context.notes_added, [FormId(0x31)]);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::esp::{
+    record::records::{
+        cell::{CellFlags, CELL},
+        watr::{WaterFlags, WATR},
+        wrld::WRLD,
+    },
+    shared::FormId,
+};
+
+/// Water height stored by cells that use the default height
+/// of their worldspace
+const DEFAULT_WATER_HEIGHT_MARKER: f32 = f32::MAX;
+
+/// Surface of the water within a cell
+#[derive(Debug, Clone, Copy)]
+pub struct WaterPlane<'a> {
+    /// Height of the surface in world units
+    pub height: f32,
+    /// Type of the water, [`None`] when the default water is used
+    pub water: Option<&'a WATR>,
+}
+
+impl<'a> WaterPlane<'a> {
+    /// Determines the water plane of a cell, returns [`None`] when the cell
+    /// has no water.
+    ///
+    /// Cells without their own height or water type use the defaults of
+    /// their `worldspace`, `default_height` being the default water height
+    /// of the worldspace (or of interiors when no worldspace is provided).
+    /// `lookup` is used to resolve the water type records
+    pub fn for_cell<F>(
+        cell: &CELL,
+        worldspace: Option<&WRLD>,
+        default_height: f32,
+        lookup: F,
+    ) -> Option<Self>
+    where
+        F: Fn(&FormId) -> Option<&'a WATR>,
+    {
+        if !cell.flags.contains(CellFlags::HAS_WATER) {
+            return None;
+        }
+
+        let height = match cell.water_height {
+            Some(height) if height != DEFAULT_WATER_HEIGHT_MARKER => height,
+            _ => default_height,
+        };
+
+        let cell_water = cell.water.as_ref().filter(|water| !water.is_null());
+        let worldspace_water = worldspace
+            .and_then(|worldspace| worldspace.water.as_ref())
+            .filter(|water| !water.is_null());
+
+        let water = cell_water
+            .or(worldspace_water)
+            .and_then(|water| lookup(&water.id));
+
+        Some(Self { height, water })
+    }
+
+    /// Whether the point at the provided height is below the surface
+    pub fn is_submerged(&self, z: f32) -> bool {
+        z < self.height
+    }
+
+    /// Damage per second dealt to actors within the water
+    pub fn damage(&self) -> Option<u16> {
+        self.water
+            .filter(|water| water.flags.contains(WaterFlags::CAUSES_DAMAGE))
+            .and_then(|water| water.damage)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WaterPlane, DEFAULT_WATER_HEIGHT_MARKER};
+    use crate::esp::{
+        record::records::cell::{CellFlags, LightInheritFlags, LightTemplate, CELL},
+        shared::{EditorId, FormId},
+    };
+
+    fn cell(flags: CellFlags, water_height: Option<f32>) -> CELL {
+        CELL {
+            editor_id: EditorId(String::new()),
+            name: None,
+            flags,
+            grid: None,
+            lighting: None,
+            footstep_material: None,
+            light_template: LightTemplate {
+                template: FormId::NULL.into_typed(),
+                inherit: LightInheritFlags::empty(),
+            },
+            water_height,
+            water_noise_texture: None,
+            regions: None,
+            image_space: None,
+            encounter_zone: None,
+            climate: None,
+            water: None,
+            owner: None,
+            faction_rank: None,
+            acoustic_space: None,
+            music_type: None,
+        }
+    }
+
+    fn height(cell: &CELL) -> Option<f32> {
+        WaterPlane::for_cell(cell, None, -100.0, |_| None).map(|plane| plane.height)
+    }
+
+    #[test]
+    fn test_height() {
+        // The marker and a missing height both use the default
+        let marker = cell(CellFlags::HAS_WATER, Some(DEFAULT_WATER_HEIGHT_MARKER));
+        assert_eq!(height(&marker), Some(-100.0));
+        assert_eq!(height(&cell(CellFlags::HAS_WATER, None)), Some(-100.0));
+
+        assert_eq!(height(&cell(CellFlags::HAS_WATER, Some(25.0))), Some(25.0));
+
+        let dry = cell(CellFlags::IS_INTERIOR_CELL, Some(25.0));
+        assert_eq!(height(&dry), None);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::esp::{
+    record::records::{
+        clmt::{ClimateTiming, WeatherType, CLMT},
+        wthr::{TimeOfDay, WTHR},
+    },
+    shared::{FormId, TypedFormId},
+};
+use fastrand::Rng;
+
+/// Deterministic selector for choosing the next weather from the
+/// weighted weather list of a climate
+pub struct WeatherSelector {
+    rng: Rng,
+}
+
+impl WeatherSelector {
+    /// Creates a new selector using the provided seed, selectors
+    /// created with the same seed will make the same choices
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::with_seed(seed),
+        }
+    }
+
+    /// Picks the next weather from the weather list of the provided
+    /// climate.
+    ///
+    /// `global_value` is used to lookup the current value of any global
+    /// variables linked to the weather entries, when a value is present
+    /// it overrides the chance stored in the climate
+    pub fn next<'a, G>(
+        &mut self,
+        climate: &'a CLMT,
+        global_value: G,
+    ) -> Option<&'a TypedFormId<WTHR>>
+    where
+        G: Fn(&FormId) -> Option<f32>,
+    {
+        let weathers = climate.weather_types.as_deref()?;
+
+        let total: u32 = weathers
+            .iter()
+            .map(|weather| weather_chance(weather, &global_value))
+            .sum();
+
+        // No weathers are able to be chosen
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = self.rng.u32(0..total);
+
+        for weather in weathers {
+            let chance = weather_chance(weather, &global_value);
+            if roll < chance {
+                return Some(&weather.weather);
+            }
+            roll -= chance;
+        }
+
+        None
+    }
+}
+
+/// Determines the chance of a weather being chosen, using the value of
+/// its global variable when one is linked and has a value
+pub fn weather_chance<G>(weather: &WeatherType, global_value: G) -> u32
+where
+    G: Fn(&FormId) -> Option<f32>,
+{
+    // Null weathers are never chosen
+    if weather.weather.is_null() {
+        return 0;
+    }
+
+    let chance = if weather.global.is_null() {
+        None
+    } else {
+        global_value(&weather.global.id)
+    };
+
+    match chance {
+        Some(value) => value.max(0.0) as u32,
+        None => weather.chance.max(0) as u32,
+    }
+}
+
+/// Determines the time of day for the provided game hour (0-24) using the
+/// sunrise and sunset timings of a climate
+pub fn time_of_day(timing: &ClimateTiming, hour: f32) -> TimeOfDay {
+    // Climate timings are stored in units of 10 minutes
+    let to_hours = |value: u8| value as f32 / 6.0;
+
+    let hour = hour.rem_euclid(24.0);
+
+    if hour < to_hours(timing.sunrise_begin) {
+        TimeOfDay::Night
+    } else if hour < to_hours(timing.sunrise_end) {
+        TimeOfDay::Sunrise
+    } else if hour < to_hours(timing.sunset_begin) {
+        TimeOfDay::Day
+    } else if hour < to_hours(timing.sunset_end) {
+        TimeOfDay::Sunset
+    } else {
+        TimeOfDay::Night
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{time_of_day, weather_chance, WeatherSelector};
+    use crate::esp::{
+        record::records::{
+            clmt::{ClimateTiming, WeatherType, CLMT},
+            wthr::TimeOfDay,
+        },
+        shared::{EditorId, FormId},
+    };
+
+    fn weather_type(weather: u32, chance: i32, global: u32) -> WeatherType {
+        WeatherType {
+            weather: FormId(weather).into_typed(),
+            chance,
+            global: FormId(global).into_typed(),
+        }
+    }
+
+    /// Sunrise 06:00-08:00 and sunset 18:00-20:00
+    fn timing() -> ClimateTiming {
+        ClimateTiming {
+            sunrise_begin: 36,
+            sunrise_end: 48,
+            sunset_begin: 108,
+            sunset_end: 120,
+            volatility: 0,
+            moon_phase_length: 0,
+        }
+    }
+
+    fn climate(weather_types: Vec<WeatherType>) -> CLMT {
+        CLMT {
+            editor_id: EditorId(String::new()),
+            weather_types: Some(weather_types),
+            sun_texture: None,
+            sun_glare_texture: None,
+            model_data: None,
+            timing: timing(),
+        }
+    }
+
+    fn sequence<G>(seed: u64, climate: &CLMT, global_value: G) -> Vec<Option<u32>>
+    where
+        G: Fn(&FormId) -> Option<f32>,
+    {
+        let mut selector = WeatherSelector::new(seed);
+        (0..64)
+            .map(|_| {
+                selector
+                    .next(climate, &global_value)
+                    .map(|weather| weather.id.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_same_seed() {
+        let climate = climate(vec![
+            weather_type(0x10, 30, 0),
+            weather_type(0x11, 50, 0),
+            weather_type(0x12, 20, 0),
+        ]);
+
+        let first = sequence(7, &climate, |_| None);
+        assert_eq!(first, sequence(7, &climate, |_| None));
+        // Every weather with a chance shows up over enough rolls
+        for id in [0x10, 0x11, 0x12] {
+            assert!(first.contains(&Some(id)));
+        }
+    }
+
+    /// Null weathers and weathers without a chance are never picked
+    #[test]
+    fn test_never_picked() {
+        let climate = climate(vec![
+            weather_type(0x0, 100, 0),
+            weather_type(0x10, 10, 0),
+            weather_type(0x11, 0, 0),
+            weather_type(0x12, -5, 0),
+        ]);
+
+        assert!(sequence(3, &climate, |_| None)
+            .iter()
+            .all(|weather| *weather == Some(0x10)));
+
+        let climate = self::climate(vec![weather_type(0x0, 100, 0), weather_type(0x11, 0, 0)]);
+        assert!(WeatherSelector::new(3).next(&climate, |_| None).is_none());
+    }
+
+    /// Global variables linked to an entry replace the chance stored in
+    /// the climate
+    #[test]
+    fn test_global_override() {
+        let entry = weather_type(0x10, 90, 0x20);
+        assert_eq!(weather_chance(&entry, |_| None), 90);
+        assert_eq!(weather_chance(&entry, |_| Some(25.0)), 25);
+        assert_eq!(weather_chance(&entry, |_| Some(-1.0)), 0);
+
+        let climate = climate(vec![
+            weather_type(0x10, 90, 0x20),
+            weather_type(0x11, 10, 0),
+            weather_type(0x12, 0, 0x21),
+        ]);
+        let global_value = |global: &FormId| match global.0 {
+            0x20 => Some(0.0),
+            0x21 => Some(40.0),
+            _ => None,
+        };
+
+        let weathers = sequence(5, &climate, global_value);
+        assert!(!weathers.contains(&Some(0x10)));
+        assert!(weathers.contains(&Some(0x12)));
+    }
+
+    #[test]
+    fn test_time_of_day() {
+        let timing = timing();
+
+        for (hour, expected) in [
+            (0.0, TimeOfDay::Night),
+            (5.99, TimeOfDay::Night),
+            (6.0, TimeOfDay::Sunrise),
+            (7.99, TimeOfDay::Sunrise),
+            (8.0, TimeOfDay::Day),
+            (17.99, TimeOfDay::Day),
+            (18.0, TimeOfDay::Sunset),
+            (19.99, TimeOfDay::Sunset),
+            (20.0, TimeOfDay::Night),
+            (24.0, TimeOfDay::Night),
+            (30.0, TimeOfDay::Sunrise),
+            (-1.0, TimeOfDay::Night),
+        ] {
+            assert_eq!(time_of_day(&timing, hour), expected, "hour {hour}");
+        }
+    }
+}